@@ -0,0 +1,279 @@
+use crate::query::{displayed_text, element_to_aria_roles, list_matches, HasRole, Matcher, Query};
+use std::panic::Location;
+use web_sys::HtmlElement;
+
+/// Returns all roles of an element, both implicit and set explicitly with the `role` attribute.
+pub(crate) fn roles_of(elem: &HtmlElement) -> Vec<String> {
+    let mut roles = element_to_aria_roles(elem)
+        .into_iter()
+        .map(String::from)
+        .collect::<Vec<_>>();
+    if let Some(role) = elem.get_attribute("role") {
+        if !roles.contains(&role) {
+            roles.push(role);
+        }
+    }
+    roles
+}
+
+/// Asserts that an element has given accessible role.
+///
+/// Roles are computed the same way as for the [`HasRole`] matcher.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::assertions::assert_has_role;
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<button>Save</button>"#);
+/// body().append_child(&div).unwrap();
+///
+/// let save = div.get(&HasText("Save")).unwrap();
+/// assert_has_role(&save, "button");
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+///
+/// # Panics:
+/// If the element doesn't have given role. The message lists roles the element actually has.
+#[track_caller]
+pub fn assert_has_role(elem: &HtmlElement, role: &str) {
+    if HasRole(role).matches(elem) {
+        return;
+    }
+    let roles = roles_of(elem);
+    if roles.is_empty() {
+        panic!(
+            "{}: expected <{}> to have role {:?}, but it has no role",
+            Location::caller(),
+            elem.local_name(),
+            role
+        );
+    }
+    panic!(
+        "{}: expected <{}> to have role {:?}, but its roles are: {}",
+        Location::caller(),
+        elem.local_name(),
+        role,
+        roles.join(", ")
+    );
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_assert_has_role() {
+    use crate::query::{HasText, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button>Save</button>"#);
+    body().append_child(&div).unwrap();
+
+    let save = div.get(&HasText("Save")).unwrap();
+    assert_has_role(&save, "button");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+#[should_panic(
+    expected = "expected <a> to have role \"button\", but its roles are: link, menuitem"
+)]
+fn assert_has_role_lists_actual_roles() {
+    use gloo::utils::document;
+    use wasm_bindgen::JsCast;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<a href="/home" role="menuitem">Home</a>"#);
+    let link = div.first_element_child().unwrap().unchecked_into();
+
+    assert_has_role(&link, "button");
+}
+
+/// Asserts that the whole visible text of an element is exactly the expected one.
+///
+/// Unlike [`HasText`], which matches substrings, the text has to be equal. It is read the same way
+/// as by [`HasText`] and whitespace in it is collapsed, so that line breaks and indentation
+/// of the markup don't matter.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::assertions::assert_text_eq;
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<button>
+///         Save
+///     </button>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// let save = div.get(&HasRole("button")).unwrap();
+/// assert_text_eq(&save, "Save");
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+///
+/// # Panics:
+/// If the text is different. The message shows both the expected and the actual text.
+///
+/// [`HasText`]: crate::query::HasText
+#[track_caller]
+pub fn assert_text_eq(elem: &HtmlElement, expected: &str) {
+    let actual = displayed_text(elem, true);
+    if actual != expected {
+        panic!(
+            "{}: text of <{}> is different\n  expected: {:?}\n    actual: {:?}",
+            Location::caller(),
+            elem.local_name(),
+            expected,
+            actual
+        );
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_assert_text_eq() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<button>
+            Save
+        </button>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let save = div.get(&HasRole("button")).unwrap();
+    assert_text_eq(&save, "Save");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+#[should_panic(
+    expected = "text of <button> is different\n  expected: \"Save\"\n    actual: \"Save draft\""
+)]
+fn assert_text_eq_shows_both_texts() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::document;
+
+    // Not attached, as the panic skips removing it and it would leak into other tests
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button>Save <b>draft</b></button>"#);
+
+    let save = div.get(&HasRole("button")).unwrap();
+    assert_text_eq(&save, "Save");
+}
+
+/// Asserts that exactly one element under `root` is matched by a [`Matcher`] and returns it.
+///
+/// It is a more assertive alternative to `get(..).unwrap()`, which reports why the assertion failed
+/// regardless of the [`QueryConfig`]: whether nothing was found or which elements were found.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::assertions::assert_single;
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<button>Save</button><button>Cancel</button>"#);
+/// body().append_child(&div).unwrap();
+///
+/// let save = assert_single(&div, &HasRole("button").named("Save"));
+/// assert_eq!(save.inner_text(), "Save");
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+///
+/// # Panics:
+/// If no element or more than one is matched. The message says which one happened and shows
+/// the searched HTML or lists the matched elements.
+///
+/// [`QueryConfig`]: crate::query::QueryConfig
+#[track_caller]
+pub fn assert_single<Q: Query, M: Matcher + ?Sized>(root: &Q, matcher: &M) -> HtmlElement {
+    let mut matched = root.get_all(matcher);
+    match matched.len() {
+        1 => matched.pop().unwrap(),
+        0 => {
+            let root_html = root.root_html();
+            if root_html.is_empty() {
+                panic!(
+                    "{}: expected a single element matching {}, but found none",
+                    Location::caller(),
+                    matcher.describe()
+                );
+            }
+            panic!(
+                "{}: expected a single element matching {}, but found none in:\n{}",
+                Location::caller(),
+                matcher.describe(),
+                root_html
+            );
+        }
+        n => panic!(
+            "{}: expected a single element matching {}, but found {}:{}",
+            Location::caller(),
+            matcher.describe(),
+            n,
+            list_matches(&matched)
+        ),
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_assert_single() {
+    use crate::query::HasRole;
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button>Save</button><button>Cancel</button>"#);
+    body().append_child(&div).unwrap();
+
+    let save = assert_single(&div, &HasRole("button").named("Save"));
+    assert_eq!(save.inner_text(), "Save");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+#[should_panic(
+    expected = "expected a single element matching HasRole(\"link\"), but found none in:\n<div>"
+)]
+fn assert_single_reports_finding_none() {
+    use gloo::utils::document;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button>Save</button>"#);
+
+    assert_single(&div, &HasRole("link"));
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+#[should_panic(
+    expected = "expected a single element matching HasText(\"Delete\"), but found 2:\n  \
+                <button id=first role=button> \"Delete\"\n  <button id=second role=button> \"Delete\""
+)]
+fn assert_single_lists_all_found() {
+    use crate::query::HasText;
+    use gloo::utils::document;
+
+    // Not attached, as the panic skips removing it and it would leak into other tests
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button id="first">Delete</button><button id="second">Delete</button>"#);
+
+    assert_single(&div, &HasText("Delete"));
+}
@@ -0,0 +1,447 @@
+use crate::assertions::roles_of;
+use crate::query::{
+    accessible_name, candidates, displayed_text, element_to_aria_roles, is_until_found, label_for,
+    HasLabel, HasPlaceholder, HasRole, HasTestId, HasText, Matcher, MatchesSelector, QueryOptions,
+};
+use gloo::utils::window;
+use web_sys::{Element, HtmlElement};
+
+/// Returns the reason why an element isn't presented to the user, if it is hidden.
+///
+/// Checks the element and all its ancestors for `hidden` attribute, telling apart `hidden="until-found"`,
+/// `display: none` and `visibility: hidden`, as well as whether the element is attached to the document at all.
+pub fn hidden_reason(elem: &Element) -> Option<String> {
+    if !elem.is_connected() {
+        return Some(String::from("it is not attached to the document"));
+    }
+    if let Some(style) = window().get_computed_style(elem).ok().flatten() {
+        let visibility = style.get_property_value("visibility").unwrap_or_default();
+        if visibility == "hidden" || visibility == "collapse" {
+            return Some(format!("visibility:{}", visibility));
+        }
+    }
+    let mut current = Some(elem.clone());
+    while let Some(elem) = current {
+        if is_until_found(&elem) {
+            return Some(format!("hidden=\"until-found\" on <{}>", elem.local_name()));
+        }
+        if elem.has_attribute("hidden") {
+            return Some(format!("hidden attribute on <{}>", elem.local_name()));
+        }
+        if let Some(style) = window().get_computed_style(&elem).ok().flatten() {
+            if style.get_property_value("display").as_deref() == Ok("none") {
+                return Some(format!("display:none on <{}>", elem.local_name()));
+            }
+        }
+        current = elem.parent_element();
+    }
+    None
+}
+
+/// Explains why a [`Matcher`] doesn't match anything in the subtree of `root`.
+///
+/// Returns [`None`] if there is a matching element. Otherwise the search is repeated ignoring
+/// the visibility of elements (see [`Matcher::matches_ignoring_visibility`]), which allows distinguishing
+/// the case where there is no such element at all from the case where the element is hidden by css.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::debug::explain_no_match;
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<p style="visibility: hidden;">Secret</p>"#);
+/// body().append_child(&div).unwrap();
+///
+/// assert!(div.get(&HasText("Secret")).is_none());
+/// assert_eq!(
+///     explain_no_match(&div, &HasText("Secret")).unwrap(),
+///     r#"found 1 element matching HasText("Secret") but it is hidden via visibility:hidden"#
+/// );
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+pub fn explain_no_match<M: Matcher + ?Sized>(root: &Element, matcher: &M) -> Option<String> {
+    let candidates = candidates(root, QueryOptions::default()).collect::<Vec<_>>();
+    if candidates.iter().any(|elem| matcher.matches(elem)) {
+        return None;
+    }
+
+    let hidden = candidates
+        .iter()
+        .filter(|elem| matcher.matches_ignoring_visibility(elem))
+        .collect::<Vec<_>>();
+    let reason = |elem: &HtmlElement| {
+        hidden_reason(elem).unwrap_or_else(|| String::from("css rules changing its content"))
+    };
+    Some(match hidden.as_slice() {
+        [] => format!(
+            "no element matching {} was found, even ignoring visibility",
+            matcher.describe()
+        ),
+        [elem] => format!(
+            "found 1 element matching {} but it is hidden via {}",
+            matcher.describe(),
+            reason(elem)
+        ),
+        hidden => format!(
+            "found {} elements matching {} but they are hidden: {}",
+            hidden.len(),
+            matcher.describe(),
+            hidden
+                .iter()
+                .map(|elem| format!("<{}> via {}", elem.local_name(), reason(elem)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    })
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_explain_no_match() {
+    use crate::query::{HasText, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<p style="visibility: hidden;">Secret</p>"#);
+    body().append_child(&div).unwrap();
+
+    assert!(div.get(&HasText("Secret")).is_none());
+    assert_eq!(
+        explain_no_match(&div, &HasText("Secret")).unwrap(),
+        r#"found 1 element matching HasText("Secret") but it is hidden via visibility:hidden"#
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn explain_no_match_distinguishes_missing_and_visible() {
+    use crate::query::HasText;
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<p>Shown</p>
+        <div hidden><span>Collapsed</span></div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    assert!(explain_no_match(&div, &HasText("Shown")).is_none());
+    assert_eq!(
+        explain_no_match(&div, &HasText("Missing")).unwrap(),
+        r#"no element matching HasText("Missing") was found, even ignoring visibility"#
+    );
+    assert_eq!(
+        explain_no_match(&div, &HasText("Collapsed")).unwrap(),
+        r#"found 1 element matching HasText("Collapsed") but it is hidden via hidden attribute on <div>"#
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+/// A matcher recommended by [`suggest_query`], together with its description.
+pub struct Suggestion {
+    description: String,
+    matcher: Box<dyn Matcher>,
+}
+
+impl Suggestion {
+    fn new(matcher: Box<dyn Matcher>) -> Self {
+        Self {
+            description: matcher.describe(),
+            matcher,
+        }
+    }
+
+    /// Returns the suggested matcher.
+    pub fn matcher(&self) -> &dyn Matcher {
+        &*self.matcher
+    }
+
+    /// Converts the suggestion into the suggested matcher.
+    pub fn into_matcher(self) -> Box<dyn Matcher> {
+        self.matcher
+    }
+}
+
+impl std::fmt::Display for Suggestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.description)
+    }
+}
+
+impl std::fmt::Debug for Suggestion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Suggestion")
+            .field(&self.description)
+            .finish()
+    }
+}
+
+/// Recommends the best matcher for finding given element, the way [`testing-library`] suggests queries.
+///
+/// Matchers are tried in the order of priority described in the crate documentation:
+/// - [`HasRole`] with the [`accessible_name`] of the element,
+/// - [`HasLabel`] with the text of the associated `<label>`,
+/// - [`HasPlaceholder`],
+/// - [`HasText`] with the whole visible text,
+/// - [`HasTestId`].
+///
+/// The first one matching the element is returned. The matcher is not guaranteed to match only this
+/// element though. If none of them matches, [`MatchesSelector`] with the tag name is the last resort.
+/// Use [`QueryConfig::suggest_queries`] to get suggestions logged for all queries.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::debug::suggest_query;
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<button class="primary">Save</button>"#);
+/// body().append_child(&div).unwrap();
+///
+/// let save = div.get(&MatchesSelector(".primary")).unwrap();
+/// let suggestion = suggest_query(&save);
+/// assert_eq!(suggestion.to_string(), r#"HasRole("button").named("Save")"#);
+/// assert_eq!(div.get(suggestion.matcher()), Some(save));
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+///
+/// [`testing-library`]: https://testing-library.com/docs/queries/about#priority
+/// [`QueryConfig::suggest_queries`]: crate::query::QueryConfig::suggest_queries
+pub fn suggest_query(elem: &HtmlElement) -> Suggestion {
+    let mut suggestions: Vec<Box<dyn Matcher>> = vec![];
+    let name = accessible_name(elem);
+    if !name.is_empty() {
+        let explicit_role = elem.get_attribute("role");
+        for role in explicit_role
+            .iter()
+            .map(String::as_str)
+            .chain(element_to_aria_roles(elem))
+        {
+            suggestions.push(Box::new(HasRole(role.to_string()).named(name.clone())));
+        }
+    }
+    if let Some(label) = label_for(elem).and_then(|label| label.text_content()) {
+        // Wrapping labels are matched without the whitespace around the control
+        suggestions.push(Box::new(HasLabel(label.trim().to_string())));
+        suggestions.push(Box::new(HasLabel(label)));
+    }
+    if let Some(placeholder) = elem.get_attribute("placeholder").filter(|p| !p.is_empty()) {
+        suggestions.push(Box::new(HasPlaceholder(placeholder)));
+    }
+    let text = displayed_text(elem, true);
+    if !text.is_empty() {
+        suggestions.push(Box::new(HasText(text)));
+    }
+    if let Some(id) = elem.get_attribute("data-testid") {
+        suggestions.push(Box::new(HasTestId(id)));
+    }
+    let matcher = suggestions
+        .into_iter()
+        .find(|matcher| matcher.matches(elem))
+        .unwrap_or_else(|| Box::new(MatchesSelector(elem.local_name())));
+    Suggestion::new(matcher)
+}
+
+/// Prefixes of descriptions of matchers recommended by [`suggest_query`], from the most preferred one.
+const PRIORITY: [&str; 5] = [
+    "HasRole(",
+    "HasLabel(",
+    "HasPlaceholder(",
+    "HasText(",
+    "HasTestId(",
+];
+
+/// Returns the priority of a matcher by its description, lower is better. Other matchers come last.
+fn priority(description: &str) -> usize {
+    PRIORITY
+        .iter()
+        .position(|prefix| description.starts_with(prefix))
+        .unwrap_or(PRIORITY.len())
+}
+
+/// Returns the [`suggest_query`] for an element, if it is preferred over the matcher that found it.
+pub(crate) fn better_query<M: Matcher + ?Sized>(
+    elem: &HtmlElement,
+    matcher: &M,
+) -> Option<Suggestion> {
+    let suggestion = suggest_query(elem);
+    (priority(&suggestion.description) < priority(&matcher.describe())).then_some(suggestion)
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_suggest_query() {
+    use crate::query::{MatchesSelector, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button class="primary">Save</button>"#);
+    body().append_child(&div).unwrap();
+
+    let save = div.get(&MatchesSelector(".primary")).unwrap();
+    let suggestion = suggest_query(&save);
+    assert_eq!(suggestion.to_string(), r#"HasRole("button").named("Save")"#);
+    assert_eq!(div.get(suggestion.matcher()), Some(save));
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn suggest_query_prefers_accessible_matchers() {
+    use crate::configure;
+    use crate::query::{MatchesSelector, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<label>Email <input type="checkbox" id="email" /></label>
+        <label for="phone">Phone</label>
+        <input id="phone" type="tel" placeholder="+48" />
+        <input id="search" type="search" placeholder="Search" />
+        <p id="note">Saved</p>
+        <div data-testid="chart"></div>
+        <div id="empty"></div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let suggest = |selector| {
+        let elem = div.get(&MatchesSelector(selector)).unwrap();
+        let suggestion = suggest_query(&elem);
+        assert_eq!(div.get(suggestion.matcher()), Some(elem));
+        suggestion.to_string()
+    };
+    assert_eq!(suggest("#email"), r#"HasRole("checkbox").named("Email")"#);
+    // Telephone inputs have no role
+    assert_eq!(suggest("#phone"), r#"HasLabel("Phone")"#);
+    assert_eq!(
+        suggest("#search"),
+        r#"HasRole("searchbox").named("Search")"#
+    );
+    assert_eq!(suggest("#note"), r#"HasText("Saved")"#);
+    assert_eq!(suggest("[data-testid]"), r#"HasTestId("chart")"#);
+    assert_eq!(
+        suggest_query(&div.get(&MatchesSelector("#empty")).unwrap()).to_string(),
+        r#"MatchesSelector("div")"#
+    );
+
+    // Logging suggestions doesn't change results
+    let _config = configure(|config| config.suggest_queries(true));
+    assert!(div.get(&MatchesSelector("[data-testid]")).is_some());
+    assert!(div.get(&HasTestId("chart")).is_some());
+
+    // Only matchers of a higher priority are suggested
+    let email = div.get(&MatchesSelector("#email")).unwrap();
+    assert!(better_query(&email, &MatchesSelector("#email")).is_some());
+    assert!(better_query(&email, &HasLabel("Email")).is_some());
+    assert!(better_query(&email, &HasRole("checkbox")).is_none());
+    let empty = div.get(&MatchesSelector("#empty")).unwrap();
+    assert!(better_query(&empty, &MatchesSelector("#empty")).is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Summarizes an element in a single line, with its tag, roles, accessible name and visible text.
+///
+/// The format is `tag "name" [role=.., text=".."]`, where missing parts are skipped.
+/// It is handy for printing results of queries or in custom panic messages.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::debug::describe;
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<button aria-label="Save changes">Save</button>"#);
+/// body().append_child(&div).unwrap();
+///
+/// let button = div.get(&HasRole("button")).unwrap();
+/// assert_eq!(
+///     describe(&button),
+///     r#"button "Save changes" [role=button, text="Save"]"#
+/// );
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+pub fn describe(elem: &HtmlElement) -> String {
+    let mut description = elem.local_name();
+    let name = accessible_name(elem);
+    if !name.is_empty() {
+        description.push_str(&format!(" {:?}", name));
+    }
+    let mut details = roles_of(elem)
+        .into_iter()
+        .map(|role| format!("role={}", role))
+        .collect::<Vec<_>>();
+    let text = displayed_text(elem, true);
+    if !text.trim().is_empty() {
+        details.push(format!("text={:?}", text.trim()));
+    }
+    if !details.is_empty() {
+        description.push_str(&format!(" [{}]", details.join(", ")));
+    }
+    description
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_describe() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button aria-label="Save changes">Save</button>"#);
+    body().append_child(&div).unwrap();
+
+    let button = div.get(&HasRole("button")).unwrap();
+    assert_eq!(
+        describe(&button),
+        r#"button "Save changes" [role=button, text="Save"]"#
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn describe_skips_missing_parts() {
+    use crate::query::{MatchesSelector, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<label for="email">Email</label>
+        <input id="email" type="text" />
+        <span id="empty"> </span>
+        <div id="tab" role="tab">  Settings  </div>
+        <button id="save" role="button">Save</button>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let describe_selected = |selector| describe(&div.get(&MatchesSelector(selector)).unwrap());
+    assert_eq!(
+        describe_selected("#email"),
+        r#"input "Email" [role=textbox]"#
+    );
+    assert_eq!(describe_selected("#empty"), "span");
+    assert_eq!(
+        describe_selected("#tab"),
+        r#"div "Settings" [role=tab, text="Settings"]"#
+    );
+    // Explicit roles repeating the implicit ones are listed once
+    assert_eq!(
+        describe_selected("#save"),
+        r#"button "Save" [role=button, text="Save"]"#
+    );
+
+    body().remove_child(&div).unwrap();
+}
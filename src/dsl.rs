@@ -0,0 +1,440 @@
+use crate::query::{
+    displayed_text, HasLabel, HasPlaceholder, HasRole, HasText, IsInvalid, IsLiveRegion,
+    IsPresentational, IsValid, Joinable, Matcher, MatchesSelector, Not,
+};
+use web_sys::HtmlElement;
+
+/// Returned when an expression can't be parsed into a [`Matcher`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    position: usize,
+    message: String,
+}
+
+impl ParseError {
+    fn new(position: usize, message: impl Into<String>) -> Self {
+        Self {
+            position,
+            message: message.into(),
+        }
+    }
+
+    /// Returns the byte offset in the expression where the error was found.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the description of the error, without its position.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a [`Matcher`] from a textual expression.
+///
+/// Useful for table-driven tests, where matchers are listed as data, and for trying queries out.
+/// An expression consists of predicates joined with `&` (and), `|` (or) and `!` (not), in order
+/// of increasing precedence, and grouped with parentheses. A predicate is a key, an operator
+/// and a value, where `=` compares the whole value and `~=` checks if it contains given substring:
+///
+/// | predicate            | matcher                                      |
+/// |----------------------|----------------------------------------------|
+/// | `role=button`        | [`HasRole`]                                  |
+/// | `label=Name`         | [`HasLabel`]                                 |
+/// | `text~=Save`         | [`HasText`]                                  |
+/// | `text=Save`          | [`HasText`], if it is the whole visible text |
+/// | `placeholder~=Mail`  | [`HasPlaceholder`]                           |
+/// | `selector='a[href]'` | [`MatchesSelector`]                          |
+///
+/// Keys `disabled`, `valid`, `invalid`, `live` and `presentational` don't take any value and match
+/// elements matched by `:disabled` selector, [`IsValid`], [`IsInvalid`], [`IsLiveRegion`]
+/// and [`IsPresentational`] respectively.
+///
+/// Values containing whitespace or any of `&|!()=~` have to be quoted with `'` or `"`.
+/// Inside quotes, a backslash escapes the next character.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::dsl::parse;
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<button>Save draft</button>
+///     <button disabled>Save</button>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// let matcher = parse("role=button & text~='Save draft' & !disabled").unwrap();
+/// assert!(div.get(&matcher).is_some());
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+pub fn parse(expression: &str) -> Result<Box<dyn Matcher>, ParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(expression)?,
+        next: 0,
+        end: expression.len(),
+    };
+    let matcher = parser.or()?;
+    match parser.peek() {
+        None => Ok(matcher),
+        Some((position, Token::RightParen)) => Err(ParseError::new(position, "unmatched `)`")),
+        Some((position, token)) => Err(ParseError::new(
+            position,
+            format!("expected `&` or `|`, found {}", token),
+        )),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    And,
+    Or,
+    Not,
+    LeftParen,
+    RightParen,
+    Equals,
+    Contains,
+    Word(String),
+    Quoted(String),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::And => f.write_str("`&`"),
+            Token::Or => f.write_str("`|`"),
+            Token::Not => f.write_str("`!`"),
+            Token::LeftParen => f.write_str("`(`"),
+            Token::RightParen => f.write_str("`)`"),
+            Token::Equals => f.write_str("`=`"),
+            Token::Contains => f.write_str("`~=`"),
+            Token::Word(word) => write!(f, "`{}`", word),
+            Token::Quoted(value) => write!(f, "{:?}", value),
+        }
+    }
+}
+
+/// Characters ending an unquoted word.
+const SPECIAL: &[char] = &['&', '|', '!', '(', ')', '=', '~', '\'', '"'];
+
+fn tokenize(expression: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let mut tokens = vec![];
+    let mut chars = expression.char_indices().peekable();
+    while let Some((position, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '&' => Token::And,
+            '|' => Token::Or,
+            '!' => Token::Not,
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '=' => Token::Equals,
+            '~' => match chars.next() {
+                Some((_, '=')) => Token::Contains,
+                _ => return Err(ParseError::new(position, "expected `=` after `~`")),
+            },
+            '\'' | '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, end)) if end == c => break,
+                        Some((_, '\\')) => value.extend(chars.next().map(|(_, c)| c)),
+                        Some((_, c)) => value.push(c),
+                        None => return Err(ParseError::new(position, "unterminated quote")),
+                    }
+                }
+                Token::Quoted(value)
+            }
+            c => {
+                let mut word = String::from(c);
+                while let Some((_, c)) =
+                    chars.next_if(|(_, c)| !c.is_whitespace() && !SPECIAL.contains(c))
+                {
+                    word.push(c);
+                }
+                Token::Word(word)
+            }
+        };
+        tokens.push((position, token));
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+    /// Position reported for errors at the end of the expression.
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<(usize, &Token)> {
+        self.tokens
+            .get(self.next)
+            .map(|(position, token)| (*position, token))
+    }
+
+    fn advance(&mut self) -> Result<(usize, Token), ParseError> {
+        let token = self
+            .tokens
+            .get(self.next)
+            .cloned()
+            .ok_or_else(|| ParseError::new(self.end, "unexpected end of expression"))?;
+        self.next += 1;
+        Ok(token)
+    }
+
+    /// Consumes the next token if it is the given one.
+    fn eat(&mut self, expected: &Token) -> bool {
+        let found = self.peek().is_some_and(|(_, token)| token == expected);
+        if found {
+            self.next += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> Result<Box<dyn Matcher>, ParseError> {
+        let mut matcher = self.and()?;
+        while self.eat(&Token::Or) {
+            matcher = Box::new(matcher.or(self.and()?));
+        }
+        Ok(matcher)
+    }
+
+    fn and(&mut self) -> Result<Box<dyn Matcher>, ParseError> {
+        let mut matcher = self.not()?;
+        while self.eat(&Token::And) {
+            matcher = Box::new(matcher.and(self.not()?));
+        }
+        Ok(matcher)
+    }
+
+    fn not(&mut self) -> Result<Box<dyn Matcher>, ParseError> {
+        if self.eat(&Token::Not) {
+            return Ok(Box::new(Not(self.not()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Box<dyn Matcher>, ParseError> {
+        match self.advance()? {
+            (_, Token::LeftParen) => {
+                let matcher = self.or()?;
+                match self.advance() {
+                    Ok((_, Token::RightParen)) => Ok(matcher),
+                    Ok((position, token)) => Err(ParseError::new(
+                        position,
+                        format!("expected `)`, found {}", token),
+                    )),
+                    Err(_) => Err(ParseError::new(self.end, "expected `)`")),
+                }
+            }
+            (position, Token::Word(key)) => self.predicate(position, &key),
+            (position, token) => Err(ParseError::new(
+                position,
+                format!("expected a key, found {}", token),
+            )),
+        }
+    }
+
+    fn predicate(&mut self, position: usize, key: &str) -> Result<Box<dyn Matcher>, ParseError> {
+        let flag: Option<Box<dyn Matcher>> = match key {
+            "disabled" => Some(Box::new(MatchesSelector(":disabled"))),
+            "valid" => Some(Box::new(IsValid)),
+            "invalid" => Some(Box::new(IsInvalid)),
+            "live" => Some(Box::new(IsLiveRegion)),
+            "presentational" => Some(Box::new(IsPresentational)),
+            _ => None,
+        };
+        if let Some(flag) = flag {
+            if let Some((position, Token::Equals | Token::Contains)) = self.peek() {
+                return Err(ParseError::new(
+                    position,
+                    format!("`{}` doesn't take a value", key),
+                ));
+            }
+            return Ok(flag);
+        }
+        if !["role", "label", "text", "placeholder", "selector"].contains(&key) {
+            return Err(ParseError::new(position, format!("unknown key `{}`", key)));
+        }
+
+        let (op_position, contains) = match self.advance() {
+            Ok((position, Token::Equals)) => (position, false),
+            Ok((position, Token::Contains)) => (position, true),
+            _ => {
+                return Err(ParseError::new(
+                    position + key.len(),
+                    format!("expected `=` or `~=` after `{}`", key),
+                ))
+            }
+        };
+        let value = match self.advance() {
+            Ok((_, Token::Word(value) | Token::Quoted(value))) => value,
+            Ok((position, token)) => {
+                return Err(ParseError::new(
+                    position,
+                    format!("expected a value, found {}", token),
+                ))
+            }
+            Err(_) => return Err(ParseError::new(self.end, "expected a value")),
+        };
+        Ok(match (key, contains) {
+            ("role", false) => Box::new(HasRole(value)),
+            ("label", false) => Box::new(HasLabel(value)),
+            ("text", false) => Box::new(TextIs(value)),
+            ("text", true) => Box::new(HasText(value)),
+            ("placeholder", true) => Box::new(HasPlaceholder(value)),
+            ("selector", false) => Box::new(MatchesSelector(value)),
+            (key, contains) => {
+                let (used, supported) = if contains { ("~=", "=") } else { ("=", "~=") };
+                return Err(ParseError::new(
+                    op_position,
+                    format!("`{}` doesn't support `{}`, use `{}`", key, used, supported),
+                ));
+            }
+        })
+    }
+}
+
+/// Matches elements matched by [`HasText`], which whole visible text is the given one.
+struct TextIs(String);
+
+impl Matcher for TextIs {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        HasText(&self.0).matches(elem) && displayed_text(elem, true) == self.0
+    }
+
+    fn describe(&self) -> String {
+        format!("TextIs({:?})", self.0)
+    }
+
+    fn matches_ignoring_visibility(&self, elem: &HtmlElement) -> bool {
+        HasText(&self.0).matches_ignoring_visibility(elem) && displayed_text(elem, false) == self.0
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_parse() {
+    use crate::query::Query;
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<button>Save draft</button>
+        <button disabled>Save</button>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let matcher = parse("role=button & text~='Save draft' & !disabled").unwrap();
+    assert!(div.get(&matcher).is_some());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn parse_respects_precedence_and_quotes() {
+    use crate::query::Query;
+    use gloo::utils::{body, document};
+
+    let describe = |expression| parse(expression).unwrap().describe();
+    assert_eq!(
+        describe("role=link | role=button & !text~=Cancel"),
+        r#"HasRole("link").or(HasRole("button").and(Not(HasText("Cancel"))))"#
+    );
+    assert_eq!(
+        describe("(role=link | role=button) & !(text~=Cancel)"),
+        r#"HasRole("link").or(HasRole("button")).and(Not(HasText("Cancel")))"#
+    );
+    assert_eq!(describe("!!valid"), "Not(Not(IsValid))");
+    assert_eq!(
+        describe(r#"text='Save as draft' | text="It's \"done\"" | selector='a[href]'"#),
+        r#"TextIs("Save as draft").or(TextIs("It's \"done\"")).or(MatchesSelector("a[href]"))"#
+    );
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<button>Save</button>
+        <button>Save as draft</button>
+        <input type="submit" value="Save" />
+        <a href="/cancel">Cancel</a>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let found = |expression| div.get_all(&parse(expression).unwrap()).len();
+    assert_eq!(found("text~=Save"), 3);
+    assert_eq!(found("text=Save"), 2);
+    assert_eq!(found("text='Save as draft'"), 1);
+    assert_eq!(
+        found("role=button & !(text=Save | text~=draft) | role=link"),
+        1
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn parse_reports_error_positions() {
+    let error = |expression| {
+        let err = parse(expression).err().unwrap();
+        (err.position(), err.to_string())
+    };
+    assert_eq!(
+        error("role=button & colour=red"),
+        (14, String::from("unknown key `colour` at position 14"))
+    );
+    assert_eq!(
+        error("role~=button"),
+        (
+            4,
+            String::from("`role` doesn't support `~=`, use `=` at position 4")
+        )
+    );
+    assert_eq!(
+        error("text='Save"),
+        (5, String::from("unterminated quote at position 5"))
+    );
+    assert_eq!(
+        error("(role=button"),
+        (12, String::from("expected `)` at position 12"))
+    );
+    assert_eq!(
+        error("role=button)"),
+        (11, String::from("unmatched `)` at position 11"))
+    );
+    assert_eq!(
+        error("disabled=true"),
+        (
+            8,
+            String::from("`disabled` doesn't take a value at position 8")
+        )
+    );
+    assert_eq!(
+        error("role button"),
+        (
+            4,
+            String::from("expected `=` or `~=` after `role` at position 4")
+        )
+    );
+    assert_eq!(
+        error("role=button &"),
+        (
+            13,
+            String::from("unexpected end of expression at position 13")
+        )
+    );
+}
@@ -0,0 +1,3385 @@
+use crate::debug::describe;
+use crate::query::{is_focusable, normalize_whitespace, HasRole, Matcher, FOCUSABLE};
+use gloo::utils::{document, window};
+use wasm_bindgen::JsCast;
+use web_sys::{
+    ClipboardEvent, ClipboardEventInit, DataTransfer, DragEvent, DragEventInit, Element, Event,
+    EventInit, File, FilePropertyBag, HtmlElement, HtmlInputElement, HtmlOptionElement,
+    HtmlSelectElement, HtmlTextAreaElement, InputEvent, InputEventInit, KeyboardEvent,
+    KeyboardEventInit, MouseEvent, MouseEventInit, PointerEvent, PointerEventInit, Range,
+};
+
+/// Returns the coordinates of the center of an element, relative to the viewport.
+fn center(elem: &HtmlElement) -> (i32, i32) {
+    let rect = elem.get_bounding_client_rect();
+    (
+        (rect.left() + rect.width() / 2.0) as i32,
+        (rect.top() + rect.height() / 2.0) as i32,
+    )
+}
+
+/// Dispatches a pointer event of the primary mouse, with `buttons` currently pressed.
+///
+/// Returns `false` if the event was cancelled.
+fn dispatch_pointer(elem: &HtmlElement, type_: &str, bubbles: bool, buttons: u16) -> bool {
+    dispatch_pointer_at(elem, type_, bubbles, buttons, center(elem))
+}
+
+/// Dispatches a pointer event like [`dispatch_pointer`], at given coordinates.
+fn dispatch_pointer_at(
+    elem: &HtmlElement,
+    type_: &str,
+    bubbles: bool,
+    buttons: u16,
+    (x, y): (i32, i32),
+) -> bool {
+    let init = PointerEventInit::new();
+    init.set_bubbles(bubbles);
+    init.set_cancelable(bubbles);
+    init.set_composed(true);
+    init.set_pointer_id(1);
+    init.set_pointer_type("mouse");
+    init.set_is_primary(true);
+    init.set_client_x(x);
+    init.set_client_y(y);
+    init.set_buttons(buttons);
+    let event = PointerEvent::new_with_event_init_dict(type_, &init).unwrap();
+    elem.dispatch_event(&event).unwrap()
+}
+
+/// Dispatches a mouse event, with `buttons` currently pressed.
+///
+/// Events of a press, like `mousedown` or `click`, count as the first click of the main button.
+/// Returns `false` if the event was cancelled.
+fn dispatch_mouse(elem: &HtmlElement, type_: &str, bubbles: bool, buttons: u16) -> bool {
+    dispatch_mouse_at(elem, type_, bubbles, buttons, center(elem))
+}
+
+/// Dispatches a mouse event like [`dispatch_mouse`], at given coordinates.
+fn dispatch_mouse_at(
+    elem: &HtmlElement,
+    type_: &str,
+    bubbles: bool,
+    buttons: u16,
+    (x, y): (i32, i32),
+) -> bool {
+    let init = MouseEventInit::new();
+    init.set_bubbles(bubbles);
+    init.set_cancelable(bubbles);
+    init.set_composed(true);
+    init.set_client_x(x);
+    init.set_client_y(y);
+    init.set_buttons(buttons);
+    if matches!(type_, "mousedown" | "mouseup" | "click") {
+        init.set_detail(1);
+    }
+    let event = MouseEvent::new_with_mouse_event_init_dict(type_, &init).unwrap();
+    elem.dispatch_event(&event).unwrap()
+}
+
+/// Moves the focus like pressing the mouse button does: to the closest focusable ancestor,
+/// or nowhere if there is none.
+fn focus_on_press(elem: &HtmlElement) {
+    let focusable = elem.closest(FOCUSABLE).unwrap().filter(is_focusable);
+    match focusable {
+        Some(target) => target.unchecked_into::<HtmlElement>().focus().unwrap(),
+        None => {
+            let active = elem.owner_document().and_then(|doc| doc.active_element());
+            if let Some(active) = active.and_then(|active| active.dyn_into::<HtmlElement>().ok()) {
+                active.blur().unwrap();
+            }
+        }
+    }
+}
+
+/// Clicks an element like a user does, and lets the page react to it before continuing.
+///
+/// Dispatches in order: `pointerdown`, `mousedown`, `pointerup`, `mouseup` and `click`, as the main
+/// mouse button pressed in the center of the element. Pressing the button also moves the focus
+/// to the closest focusable element, unless `mousedown` is cancelled, so components reacting
+/// to any of those events work the same as in the browser. Disabled form controls get only
+/// the pointer events, as in the browser.
+///
+/// Event handlers run synchronously, but updates they cause, like re-renders of [`yew`] components,
+/// are scheduled for later. This awaits a [`tick`] after the click, so the DOM is already updated
+/// when the next line of the test runs. Use [`click_sync`] outside of async code.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// # use gloo::events::EventListener;
+/// use frontest::prelude::*;
+/// use gloo::timers::callback::Timeout;
+///
+/// # async fn click_button() {
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<button>Add</button><p>Value: 0</p>"#);
+/// body().append_child(&div).unwrap();
+///
+/// let button = div.get(&HasRole("button")).unwrap();
+/// // Updates the text later, like a re-render of a component
+/// let _listener = EventListener::new(&button, "click", {
+///     let value = div.get(&HasText("Value:")).unwrap();
+///     move |_| {
+///         let value = value.clone();
+///         Timeout::new(0, move || value.set_inner_text("Value: 1")).forget();
+///     }
+/// });
+///
+/// click(&button).await;
+/// assert!(div.get(&HasText("Value: 1")).is_some());
+///
+/// body().remove_child(&div).unwrap();
+/// # }
+/// ```
+///
+/// [`yew`]: ::yew
+/// [`tick`]: crate::tick
+pub async fn click(elem: &HtmlElement) {
+    click_sync(elem);
+    crate::tick().await;
+}
+
+/// Clicks an element without waiting for the page to react, see [`click`].
+///
+/// Only the handlers are run before it returns, so changes they schedule, eg. re-renders,
+/// are not visible yet.
+pub fn click_sync(elem: &HtmlElement) {
+    let disabled = elem.matches(":disabled").unwrap();
+    dispatch_pointer(elem, "pointerdown", true, 1);
+    if !disabled && dispatch_mouse(elem, "mousedown", true, 1) {
+        focus_on_press(elem);
+    }
+    dispatch_pointer(elem, "pointerup", true, 0);
+    if !disabled {
+        dispatch_mouse(elem, "mouseup", true, 0);
+        dispatch_mouse(elem, "click", true, 0);
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_click() {
+    use crate::query::{HasRole, HasText, Query};
+    use gloo::events::EventListener;
+    use gloo::timers::callback::Timeout;
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button>Add</button><p>Value: 0</p>"#);
+    body().append_child(&div).unwrap();
+
+    let button = div.get(&HasRole("button")).unwrap();
+    // Updates the text later, like a re-render of a component
+    let _listener = EventListener::new(&button, "click", {
+        let value = div.get(&HasText("Value:")).unwrap();
+        move |_| {
+            let value = value.clone();
+            Timeout::new(0, move || value.set_inner_text("Value: 1")).forget();
+        }
+    });
+
+    click(&button).await;
+    assert!(div.get(&HasText("Value: 1")).is_some());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(all(test, feature = "yew"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn click_dispatches_whole_sequence() {
+    use crate::query::{HasRole, HasText, Query};
+    use crate::yew::render;
+    use gloo::events::EventListener;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use yew::prelude::*;
+
+    // Opens on the press of the button, like many dropdowns do
+    #[function_component(Dropdown)]
+    fn dropdown() -> Html {
+        let open = use_state(|| false);
+        let onmousedown = {
+            let open = open.clone();
+            Callback::from(move |_| open.set(true))
+        };
+        html! {
+            <>
+                <button {onmousedown}>{ "Menu" }</button>
+                if *open { <ul role="menu"><li>{ "Settings" }</li></ul> }
+                <button disabled=true>{ "Delete" }</button>
+            </>
+        }
+    }
+
+    let mount = render(html! { <Dropdown /> }).await;
+    let menu = mount.get(&HasRole("button").named("Menu")).unwrap();
+    let delete = mount.get(&HasRole("button").named("Delete")).unwrap();
+
+    let events = Rc::new(RefCell::new(vec![]));
+    let types = [
+        "pointerdown",
+        "mousedown",
+        "focus",
+        "pointerup",
+        "mouseup",
+        "click",
+    ];
+    let _listeners = [&menu, &delete]
+        .into_iter()
+        .flat_map(|button| types.map(|type_| (button.clone(), type_)))
+        .map(|(button, type_)| {
+            let events = events.clone();
+            let name = button.inner_text();
+            EventListener::new(&button, type_, move |event| {
+                events
+                    .borrow_mut()
+                    .push(format!("{} {}", name, event.type_()))
+            })
+        })
+        .collect::<Vec<_>>();
+
+    click(&menu).await;
+    assert!(mount.get(&HasText("Settings")).is_some());
+    assert_eq!(
+        *events.borrow(),
+        [
+            "Menu pointerdown",
+            "Menu mousedown",
+            "Menu focus",
+            "Menu pointerup",
+            "Menu mouseup",
+            "Menu click"
+        ]
+    );
+
+    // Disabled controls get no mouse events and aren't focused
+    events.borrow_mut().clear();
+    click(&delete).await;
+    assert_eq!(*events.borrow(), ["Delete pointerdown", "Delete pointerup"]);
+    assert_eq!(gloo::utils::document().active_element(), Some(menu.into()));
+
+    crate::cleanup();
+}
+
+/// Returns the element and all its ancestors, from the outermost one.
+fn ancestors_from_root(elem: &HtmlElement) -> Vec<HtmlElement> {
+    let mut ancestors = vec![elem.clone()];
+    while let Some(parent) = ancestors.last().unwrap().parent_element() {
+        ancestors.push(parent.unchecked_into());
+    }
+    ancestors.reverse();
+    ancestors
+}
+
+/// Simulates moving the pointer over an element, the same way [`userEvent.hover`] does.
+///
+/// Dispatches in order: `pointerover`, `pointerenter`, `mouseover`, `mouseenter`,
+/// `pointermove` and `mousemove`, in the center of the element. As the pointer comes from
+/// outside of the document, `*enter` events are dispatched to every ancestor of the element too,
+/// starting from the outermost one, so eg. a wrapper showing a tooltip reacts to hovering its content.
+/// Only `*over` and `*move` events bubble, as in the browser. Use [`hover_async`] to also let
+/// the page react to it before continuing.
+///
+/// Note that only the events are simulated. The browser doesn't consider the element hovered,
+/// so css rules using `:hover` don't apply to it and styles depending on them can't be tested.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// # use gloo::events::EventListener;
+/// use frontest::interaction::hover;
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<button>Help</button>"#);
+/// body().append_child(&div).unwrap();
+///
+/// let button = div.get(&HasRole("button")).unwrap();
+/// let _listener = EventListener::new(&button, "mouseenter", {
+///     let div = div.clone();
+///     move |_| {
+///         div.insert_adjacent_html("beforeend", r#"<div role="tooltip">Click for help</div>"#)
+///             .unwrap()
+///     }
+/// });
+///
+/// hover(&button);
+/// assert!(div.get(&HasRole("tooltip")).is_some());
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+///
+/// [`userEvent.hover`]: https://testing-library.com/docs/user-event/convenience#hover
+pub fn hover(elem: &HtmlElement) {
+    let at = center(elem);
+    let ancestors = ancestors_from_root(elem);
+    dispatch_pointer_at(elem, "pointerover", true, 0, at);
+    for ancestor in &ancestors {
+        dispatch_pointer_at(ancestor, "pointerenter", false, 0, at);
+    }
+    dispatch_mouse_at(elem, "mouseover", true, 0, at);
+    for ancestor in &ancestors {
+        dispatch_mouse_at(ancestor, "mouseenter", false, 0, at);
+    }
+    dispatch_pointer_at(elem, "pointermove", true, 0, at);
+    dispatch_mouse_at(elem, "mousemove", true, 0, at);
+}
+
+/// Simulates moving the pointer over an element like [`hover`], and lets the page react to it
+/// before continuing.
+///
+/// Like [`click`], it awaits a [`tick`] after the events, so updates they cause, eg. re-renders
+/// of [`yew`] components, are already visible when the next line of the test runs.
+///
+/// [`yew`]: ::yew
+/// [`tick`]: crate::tick
+pub async fn hover_async(elem: &HtmlElement) {
+    hover(elem);
+    crate::tick().await;
+}
+
+/// Simulates moving the pointer out of an element, the same way [`userEvent.unhover`] does.
+///
+/// Dispatches in order: `pointermove`, `mousemove`, `pointerout`, `pointerleave`,
+/// `mouseout` and `mouseleave`, the reverse of [`hover`]. As the pointer leaves the document,
+/// `*leave` events are dispatched to the element and then to all its ancestors. Only `*out`
+/// and `*move` events bubble, as in the browser. Use [`unhover_async`] to also let the page react
+/// to it before continuing.
+///
+/// [`userEvent.unhover`]: https://testing-library.com/docs/user-event/convenience#unhover
+pub fn unhover(elem: &HtmlElement) {
+    let at = center(elem);
+    let mut ancestors = ancestors_from_root(elem);
+    ancestors.reverse();
+    dispatch_pointer_at(elem, "pointermove", true, 0, at);
+    dispatch_mouse_at(elem, "mousemove", true, 0, at);
+    dispatch_pointer_at(elem, "pointerout", true, 0, at);
+    for ancestor in &ancestors {
+        dispatch_pointer_at(ancestor, "pointerleave", false, 0, at);
+    }
+    dispatch_mouse_at(elem, "mouseout", true, 0, at);
+    for ancestor in &ancestors {
+        dispatch_mouse_at(ancestor, "mouseleave", false, 0, at);
+    }
+}
+
+/// Simulates moving the pointer out of an element like [`unhover`], and lets the page react to it
+/// before continuing, see [`hover_async`].
+pub async fn unhover_async(elem: &HtmlElement) {
+    unhover(elem);
+    crate::tick().await;
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn hover_shows_tooltip() {
+    use crate::query::{HasRole, Query};
+    use gloo::events::EventListener;
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button>Help</button>"#);
+    body().append_child(&div).unwrap();
+
+    let button = div.get(&HasRole("button")).unwrap();
+    let _show = EventListener::new(&button, "mouseenter", {
+        let div = div.clone();
+        move |_| {
+            div.insert_adjacent_html("beforeend", r#"<div role="tooltip">Click for help</div>"#)
+                .unwrap()
+        }
+    });
+    let _hide = EventListener::new(&button, "mouseleave", {
+        let div = div.clone();
+        move |_| {
+            div.query_selector("[role=tooltip]")
+                .unwrap()
+                .unwrap()
+                .remove()
+        }
+    });
+
+    assert!(div.get(&HasRole("tooltip")).is_none());
+    hover(&button);
+    assert!(div.get(&HasRole("tooltip")).is_some());
+    unhover(&button);
+    assert!(div.get(&HasRole("tooltip")).is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn hover_enters_and_leaves_ancestors() {
+    use crate::query::{HasRole, Query};
+    use gloo::events::EventListener;
+    use gloo::utils::{body, document};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<nav><button>Help</button></nav>"#);
+    body().append_child(&div).unwrap();
+
+    let button = div.get(&HasRole("button")).unwrap();
+    let nav = div.get(&HasRole("navigation")).unwrap();
+    let events = Rc::new(RefCell::new(vec![]));
+    let listeners = [(&nav, "nav"), (&button, "button")]
+        .into_iter()
+        .flat_map(|(elem, name)| {
+            ["mouseenter", "mouseleave", "mouseover", "mousemove"].map(|type_| {
+                let events = events.clone();
+                EventListener::new(elem, type_, move |event| {
+                    let event = event.dyn_ref::<MouseEvent>().unwrap();
+                    let at = (event.client_x(), event.client_y());
+                    events
+                        .borrow_mut()
+                        .push((format!("{} {}", event.type_(), name), at));
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let at = center(&button);
+    hover_async(&button).await;
+    unhover_async(&button).await;
+    let expected = [
+        "mouseover button",
+        "mouseover nav",
+        "mouseenter nav",
+        "mouseenter button",
+        "mousemove button",
+        "mousemove nav",
+        "mousemove button",
+        "mousemove nav",
+        "mouseleave button",
+        "mouseleave nav",
+    ];
+    assert_eq!(
+        *events.borrow(),
+        expected.map(|event| (String::from(event), at))
+    );
+
+    drop(listeners);
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(all(test, feature = "yew"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn hover_opens_tooltip_component() {
+    use crate::query::{HasRole, HasText, Query};
+    use crate::yew::render;
+    use yew::prelude::*;
+
+    #[function_component(Help)]
+    fn help() -> Html {
+        let open = use_state(|| false);
+        let onmouseenter = {
+            let open = open.clone();
+            Callback::from(move |_| open.set(true))
+        };
+        let onmouseleave = {
+            let open = open.clone();
+            Callback::from(move |_| open.set(false))
+        };
+        html! {
+            <span {onmouseenter} {onmouseleave}>
+                <button>{ "Help" }</button>
+                if *open { <div role="tooltip">{ "Click for help" }</div> }
+            </span>
+        }
+    }
+
+    let mount = render(html! { <Help /> }).await;
+    let button = mount.get(&HasText("Help")).unwrap();
+    assert!(mount.get(&HasRole("tooltip")).is_none());
+
+    hover_async(&button).await;
+    assert!(mount.get(&HasRole("tooltip")).is_some());
+    unhover_async(&button).await;
+    assert!(mount.get(&HasRole("tooltip")).is_none());
+
+    crate::cleanup();
+}
+
+/// Returned when an interaction isn't possible, because a user couldn't perform it either.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InteractionError {
+    /// The element has no editable text, eg. it isn't a text field.
+    NotEditable(HtmlElement),
+    /// The element is disabled.
+    Disabled(HtmlElement),
+    /// The element is read-only.
+    ReadOnly(HtmlElement),
+    /// The element isn't a `<select>`.
+    NotSelect(HtmlElement),
+    /// The element isn't a checkbox, a radio or a switch.
+    NotCheckable(HtmlElement),
+    /// The element is a radio, which can't be unchecked by a user.
+    CannotUncheck(HtmlElement),
+    /// More than one option was given for an element without the `multiple` attribute.
+    NotMultiple(HtmlElement),
+    /// The element isn't an `<input type="file">`.
+    NotFileInput(HtmlElement),
+    /// The `<select>` has no such option.
+    NoOption {
+        /// The `<select>` element.
+        select: HtmlElement,
+        /// Description of the missing option, eg. `label "Kiwi"`.
+        option: String,
+        /// Labels of all options of the `<select>`.
+        available: Vec<String>,
+    },
+}
+
+impl std::fmt::Display for InteractionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotEditable(elem) => write!(f, "{} is not editable", describe(elem)),
+            Self::Disabled(elem) => write!(f, "{} is disabled", describe(elem)),
+            Self::ReadOnly(elem) => write!(f, "{} is read-only", describe(elem)),
+            Self::NotSelect(elem) => write!(f, "{} is not a select", describe(elem)),
+            Self::NotCheckable(elem) => {
+                write!(f, "{} is not a checkbox, radio or switch", describe(elem))
+            }
+            Self::CannotUncheck(elem) => {
+                write!(f, "{} is a radio, which can't be unchecked", describe(elem))
+            }
+            Self::NotMultiple(elem) => {
+                write!(f, "{} doesn't allow multiple values", describe(elem))
+            }
+            Self::NotFileInput(elem) => write!(f, "{} is not a file input", describe(elem)),
+            Self::NoOption {
+                select,
+                option,
+                available,
+            } => {
+                let available = available
+                    .iter()
+                    .map(|label| format!("{:?}", label))
+                    .collect::<Vec<_>>();
+                write!(
+                    f,
+                    "{} has no option with {}, available options: [{}]",
+                    describe(select),
+                    option,
+                    available.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for InteractionError {}
+
+/// An element with editable text: a form control or a `contenteditable` element.
+enum TextField {
+    Input(HtmlInputElement),
+    TextArea(HtmlTextAreaElement),
+    Editable(HtmlElement),
+}
+
+impl TextField {
+    fn new(elem: &HtmlElement) -> Option<Self> {
+        if let Some(input) = elem.dyn_ref::<HtmlInputElement>() {
+            // Other inputs, like checkboxes, have values not editable by typing
+            let text = [
+                "text", "search", "url", "tel", "email", "password", "number",
+            ];
+            text.contains(&input.type_().as_str())
+                .then(|| Self::Input(input.clone()))
+        } else if let Some(area) = elem.dyn_ref::<HtmlTextAreaElement>() {
+            Some(Self::TextArea(area.clone()))
+        } else {
+            elem.is_content_editable()
+                .then(|| Self::Editable(elem.clone()))
+        }
+    }
+
+    fn value(&self) -> String {
+        match self {
+            Self::Input(input) => input.value(),
+            Self::TextArea(area) => area.value(),
+            Self::Editable(elem) => elem.text_content().unwrap_or_default(),
+        }
+    }
+
+    fn set_value(&self, value: &str) {
+        match self {
+            Self::Input(input) => input.set_value(value),
+            Self::TextArea(area) => area.set_value(value),
+            Self::Editable(elem) => elem.set_text_content(Some(value)),
+        }
+    }
+
+    /// Returns whether a user can change the value, ie. it isn't disabled nor read-only.
+    fn is_writable(&self) -> bool {
+        match self {
+            Self::Input(input) => !input.disabled() && !input.read_only(),
+            Self::TextArea(area) => !area.disabled() && !area.read_only(),
+            Self::Editable(_) => true,
+        }
+    }
+
+    /// Returns the `maxlength` of a form control in UTF-16 code units, like browsers count it.
+    fn max_length(&self) -> Option<usize> {
+        let max_length = match self {
+            Self::Input(input) => input.max_length(),
+            Self::TextArea(area) => area.max_length(),
+            Self::Editable(_) => -1,
+        };
+        usize::try_from(max_length).ok()
+    }
+
+    /// Returns the selected range of a form control in UTF-16 code units, if it supports selection.
+    fn selection(&self) -> Option<(usize, usize)> {
+        let (start, end) = match self {
+            Self::Input(input) => (input.selection_start(), input.selection_end()),
+            Self::TextArea(area) => (area.selection_start(), area.selection_end()),
+            Self::Editable(_) => return None,
+        };
+        Some((start.ok()?? as usize, end.ok()?? as usize))
+    }
+
+    /// Selects given range of a form control, in UTF-16 code units.
+    fn set_selection_range(&self, start: u32, end: u32) {
+        // Some inputs, eg. `type="email"`, don't support selection
+        let _ = match self {
+            Self::Input(input) => input.set_selection_range(start, end),
+            Self::TextArea(area) => area.set_selection_range(start, end),
+            Self::Editable(_) => Ok(()),
+        };
+    }
+
+    fn select_all(&self) {
+        match self {
+            Self::Input(input) => input.select(),
+            Self::TextArea(area) => area.select(),
+            Self::Editable(elem) => {
+                let range = document().create_range().unwrap();
+                range.select_node_contents(elem).unwrap();
+                select_range(&range);
+            }
+        }
+    }
+
+    /// Moves the caret to the end of the text, as clicking into the field would.
+    fn move_caret_to_end(&self) {
+        match self {
+            Self::Input(_) | Self::TextArea(_) => {
+                let end = self.value().encode_utf16().count() as u32;
+                self.set_selection_range(end, end);
+            }
+            Self::Editable(elem) => {
+                let range = document().create_range().unwrap();
+                range.select_node_contents(elem).unwrap();
+                range.collapse_with_to_start(false);
+                select_range(&range);
+            }
+        }
+    }
+
+    /// Selects the character before the caret, if nothing is selected yet.
+    ///
+    /// Returns `false` if there is nothing to delete, as the caret is at the start.
+    fn select_previous_char(&self) -> bool {
+        match self {
+            Self::Input(_) | Self::TextArea(_) => {
+                let Some((start, end)) = self.selection() else {
+                    return !self.value().is_empty();
+                };
+                if start != end {
+                    return true;
+                }
+                let value = self.value().encode_utf16().collect::<Vec<_>>();
+                let previous = value[..start.min(value.len())]
+                    .iter()
+                    .rev()
+                    // Surrogate pairs are deleted together
+                    .take_while(|&&unit| (0xDC00..0xE000).contains(&unit))
+                    .count()
+                    + 1;
+                if start == 0 {
+                    return false;
+                }
+                self.set_selection_range(start.saturating_sub(previous) as u32, end as u32);
+                true
+            }
+            Self::Editable(elem) => {
+                let range = editable_range(elem);
+                if !range.collapsed() {
+                    return true;
+                }
+                let (container, offset) = (
+                    range.start_container().unwrap(),
+                    range.start_offset().unwrap(),
+                );
+                if container.node_type() != web_sys::Node::TEXT_NODE || offset == 0 {
+                    return false;
+                }
+                range.set_start(&container, offset - 1).unwrap();
+                select_range(&range);
+                true
+            }
+        }
+    }
+
+    /// Returns the text which replacing the selection with `text` would insert,
+    /// truncated to fit in `maxlength`.
+    fn insertable<'a>(&self, text: &'a str) -> &'a str {
+        let Some(max_length) = self.max_length() else {
+            return text;
+        };
+        let len = self.value().encode_utf16().count();
+        let selected = self.selection().map_or(0, |(start, end)| end - start);
+        let mut available = max_length.saturating_sub(len - selected);
+        let end = text
+            .char_indices()
+            .find(|(_, char)| {
+                let fits = char.len_utf16() <= available;
+                available = available.saturating_sub(char.len_utf16());
+                !fits
+            })
+            .map_or(text.len(), |(idx, _)| idx);
+        &text[..end]
+    }
+
+    /// Returns the selected text.
+    fn selected_text(&self) -> String {
+        match self {
+            Self::Input(_) | Self::TextArea(_) => {
+                let Some((start, end)) = self.selection() else {
+                    return String::new();
+                };
+                let value = self.value().encode_utf16().collect::<Vec<_>>();
+                let end = end.min(value.len());
+                String::from_utf16_lossy(&value[start.min(end)..end])
+            }
+            Self::Editable(elem) => selected_text(elem),
+        }
+    }
+
+    /// Replaces the selected text with `text` and collapses the selection after it.
+    fn replace_selection(&self, text: &str) {
+        match self {
+            Self::Input(_) | Self::TextArea(_) => {
+                let value = self.value().encode_utf16().collect::<Vec<_>>();
+                let (start, end) = self
+                    .selection()
+                    .map(|(start, end)| (start.min(value.len()), end.min(value.len())))
+                    .unwrap_or((value.len(), value.len()));
+                let mut replaced = value[..start].to_vec();
+                replaced.extend(text.encode_utf16());
+                let caret = replaced.len() as u32;
+                replaced.extend(&value[end..]);
+                self.set_value(&String::from_utf16_lossy(&replaced));
+                self.set_selection_range(caret, caret);
+            }
+            Self::Editable(elem) => {
+                let range = editable_range(elem);
+                range.delete_contents().unwrap();
+                if !text.is_empty() {
+                    let node = document().create_text_node(text);
+                    range.insert_node(&node).unwrap();
+                    range.set_start_after(&node).unwrap();
+                    range.collapse_with_to_start(true);
+                }
+                select_range(&range);
+            }
+        }
+    }
+}
+
+/// Returns the selected range if it is inside of `elem`, or an empty range at its end.
+fn editable_range(elem: &HtmlElement) -> Range {
+    let selected = window()
+        .get_selection()
+        .unwrap()
+        .filter(|selection| selection.range_count() > 0)
+        .and_then(|selection| selection.get_range_at(0).ok())
+        .filter(|range| elem.contains(range.common_ancestor_container().ok().as_ref()));
+    selected.unwrap_or_else(|| {
+        let range = document().create_range().unwrap();
+        range.select_node_contents(elem).unwrap();
+        range.collapse_with_to_start(false);
+        range
+    })
+}
+
+fn select_range(range: &Range) {
+    if let Some(selection) = window().get_selection().unwrap() {
+        selection.remove_all_ranges().unwrap();
+        selection.add_range(range).unwrap();
+    }
+}
+
+/// Focuses the element, placing the caret at the end of its text if it wasn't focused before.
+///
+/// Returns the focused field, if the element has editable text.
+fn focus_for_typing(elem: &HtmlElement) -> Option<TextField> {
+    let focused = document().active_element().as_deref() == Some(elem.as_ref());
+    elem.focus().unwrap();
+    let field = TextField::new(elem)?;
+    if !focused {
+        field.move_caret_to_end();
+    }
+    Some(field)
+}
+
+/// Modifier keys held while pressing other keys.
+#[derive(Copy, Clone, Debug, Default)]
+struct Modifiers {
+    ctrl: bool,
+    shift: bool,
+    alt: bool,
+    meta: bool,
+}
+
+impl Modifiers {
+    /// Returns the flag of a modifier key, or [`None`] if the key isn't a modifier.
+    fn flag(&mut self, key: &str) -> Option<&mut bool> {
+        match key {
+            "Control" => Some(&mut self.ctrl),
+            "Shift" => Some(&mut self.shift),
+            "Alt" => Some(&mut self.alt),
+            "Meta" => Some(&mut self.meta),
+            _ => None,
+        }
+    }
+
+    /// Returns whether a shortcut modifier is held, in which case characters aren't typed.
+    fn is_shortcut(&self) -> bool {
+        self.ctrl || self.alt || self.meta
+    }
+}
+
+/// Returns the [`code`] of the physical key producing given `key` on a US keyboard,
+/// or an empty string if it isn't known.
+///
+/// [`code`]: https://developer.mozilla.org/en-US/docs/Web/API/KeyboardEvent/code
+fn key_code(key: &str) -> String {
+    let mut chars = key.chars();
+    match (chars.next(), chars.next()) {
+        (Some(char), None) if char.is_ascii_alphabetic() => {
+            format!("Key{}", char.to_ascii_uppercase())
+        }
+        (Some(char), None) if char.is_ascii_digit() => format!("Digit{}", char),
+        (Some(' '), None) => String::from("Space"),
+        (Some(_), None) => String::new(),
+        _ if matches!(key, "Control" | "Shift" | "Alt" | "Meta") => format!("{}Left", key),
+        _ => String::from(key),
+    }
+}
+
+/// Dispatches a keyboard event, returning `false` if it was cancelled.
+fn dispatch_keyboard(elem: &HtmlElement, type_: &str, key: &str, modifiers: Modifiers) -> bool {
+    let init = KeyboardEventInit::new();
+    init.set_bubbles(true);
+    init.set_cancelable(true);
+    init.set_composed(true);
+    init.set_key(key);
+    init.set_code(&key_code(key));
+    init.set_ctrl_key(modifiers.ctrl);
+    init.set_shift_key(modifiers.shift);
+    init.set_alt_key(modifiers.alt);
+    init.set_meta_key(modifiers.meta);
+    let event = KeyboardEvent::new_with_keyboard_event_init_dict(type_, &init).unwrap();
+    elem.dispatch_event(&event).unwrap()
+}
+
+/// Dispatches `beforeinput` and, unless it was cancelled, replaces the selected text with `data`
+/// and dispatches `input`.
+///
+/// Nothing is dispatched if the field isn't writable or `data` doesn't fit in its `maxlength`.
+fn dispatch_input(field: &TextField, elem: &HtmlElement, input_type: &str, data: Option<&str>) {
+    let text = field.insertable(data.unwrap_or_default());
+    if !field.is_writable() || (data.is_some() && text.is_empty()) {
+        return;
+    }
+    let init = InputEventInit::new();
+    init.set_bubbles(true);
+    init.set_composed(true);
+    init.set_input_type(input_type);
+    init.set_data(data.map(|_| text));
+    init.set_cancelable(true);
+    let before = InputEvent::new_with_event_init_dict("beforeinput", &init).unwrap();
+    if !elem.dispatch_event(&before).unwrap() {
+        return;
+    }
+    field.replace_selection(text);
+    init.set_cancelable(false);
+    let input = InputEvent::new_with_event_init_dict("input", &init).unwrap();
+    elem.dispatch_event(&input).unwrap();
+}
+
+/// Presses and releases a key on the focused `elem`, performing its default action
+/// unless it is cancelled.
+///
+/// Characters are typed into the `field`, Enter activates buttons and links, inserts a line break
+/// or submits the form of an input, Space activates buttons on release and Backspace deletes.
+fn press_key(elem: &HtmlElement, field: Option<&TextField>, key: &str, modifiers: Modifiers) {
+    let typed = key.chars().count() == 1 && !modifiers.is_shortcut();
+    let pressed = dispatch_keyboard(elem, "keydown", key, modifiers);
+    if pressed && (typed || key == "Enter") && dispatch_keyboard(elem, "keypress", key, modifiers) {
+        match (key, field) {
+            ("Enter", Some(field @ TextField::TextArea(_))) => {
+                dispatch_input(field, elem, "insertLineBreak", Some("\n"));
+            }
+            ("Enter", Some(TextField::Input(input))) => submit_implicitly(input),
+            ("Enter", _) if elem.matches(ACTIVATED_BY_ENTER).unwrap() => elem.click(),
+            ("Enter", _) => {}
+            (_, Some(field)) => dispatch_input(field, elem, "insertText", Some(key)),
+            (_, None) => {}
+        }
+    }
+    if pressed && key == "Backspace" {
+        if let Some(field) = field.filter(|field| field.select_previous_char()) {
+            dispatch_input(field, elem, "deleteContentBackward", None);
+        }
+    }
+    let released = dispatch_keyboard(elem, "keyup", key, modifiers);
+    if pressed && released && key == " " && elem.matches(ACTIVATED_BY_SPACE).unwrap() {
+        elem.click();
+    }
+}
+
+/// Elements clicked when Enter is pressed on them.
+const ACTIVATED_BY_ENTER: &str = "button, input[type=button], input[type=submit], \
+                                  input[type=reset], input[type=image], a[href], summary";
+
+/// Elements clicked when Space is released on them.
+const ACTIVATED_BY_SPACE: &str = "button, input[type=button], input[type=submit], \
+                                  input[type=reset], input[type=checkbox], input[type=radio], summary";
+
+/// Submits the form of an input like pressing Enter in it does, by clicking its default button.
+///
+/// Forms without a submit button are submitted directly, and forms with a disabled one aren't.
+fn submit_implicitly(input: &HtmlInputElement) {
+    let Some(form) = input.form() else {
+        return;
+    };
+    let default_button = form
+        .query_selector(
+            "button:not([type]), button[type=submit], input[type=submit], input[type=image]",
+        )
+        .unwrap();
+    match default_button {
+        Some(button) if button.matches(":disabled").unwrap() => {}
+        Some(button) => button.unchecked_into::<HtmlElement>().click(),
+        // Fails only for forms not connected to the document
+        None => form.request_submit().unwrap_or_default(),
+    }
+}
+
+/// Types `text` into the focused `elem`, character by character.
+///
+/// Microtasks are flushed after every character, so components are re-rendered between them,
+/// as they would be between keystrokes of a user.
+async fn type_chars(elem: &HtmlElement, field: Option<&TextField>, text: &str) {
+    for char in text.chars() {
+        press_key(elem, field, &char.to_string(), Modifiers::default());
+        crate::flush().await;
+    }
+}
+
+/// Focuses a writable text field and deletes its whole content, see [`clear`].
+fn clear_field(elem: &HtmlElement) -> Result<TextField, InteractionError> {
+    let Some(field) = TextField::new(elem) else {
+        return Err(InteractionError::NotEditable(elem.clone()));
+    };
+    if elem.matches(":disabled").unwrap() {
+        return Err(InteractionError::Disabled(elem.clone()));
+    }
+    if !field.is_writable() {
+        return Err(InteractionError::ReadOnly(elem.clone()));
+    }
+    elem.focus().unwrap();
+    field.select_all();
+    if !field.value().is_empty() {
+        dispatch_input(&field, elem, "deleteContentBackward", None);
+    }
+    Ok(field)
+}
+
+/// Clears a text field the way a user would, the same way [`userEvent.clear`] does.
+///
+/// Focuses the element, selects its whole content and deletes it, dispatching `beforeinput`
+/// and `input` with `inputType` of `"deleteContentBackward"`, so handlers of controlled components
+/// see the empty value. Nothing is dispatched if it is already empty, and the content stays if
+/// `beforeinput` is cancelled. Works for `<input>`, `<textarea>` and `contenteditable` elements.
+/// Awaits a [`tick`] at the end.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::interaction::clear;
+/// use frontest::prelude::*;
+/// use web_sys::HtmlInputElement;
+///
+/// # async fn clear_name() {
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<label>Name <input type="text" value="Alice" /></label>
+///     <label>Id <input type="text" value="42" readonly /></label>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// let name = div.get_as::<HtmlInputElement, _>(&HasLabel("Name")).unwrap();
+/// clear(&name).await.unwrap();
+/// assert_eq!(name.value(), "");
+///
+/// let id = div.get(&HasLabel("Id")).unwrap();
+/// assert!(clear(&id).await.is_err());
+///
+/// body().remove_child(&div).unwrap();
+/// # }
+/// ```
+///
+/// # Errors:
+/// If the element has no editable text, or it is disabled or read-only.
+///
+/// [`userEvent.clear`]: https://testing-library.com/docs/user-event/utility#clear
+/// [`tick`]: crate::tick
+pub async fn clear(elem: &HtmlElement) -> Result<(), InteractionError> {
+    clear_field(elem)?;
+    crate::tick().await;
+    Ok(())
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_clear() {
+    use crate::query::{HasLabel, Query};
+    use gloo::utils::body;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<label>Name <input type="text" value="Alice" /></label>
+        <label>Id <input type="text" value="42" readonly /></label>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let name = div
+        .get_as::<HtmlInputElement, _>(&HasLabel("Name"))
+        .unwrap();
+    clear(&name).await.unwrap();
+    assert_eq!(name.value(), "");
+
+    let id = div.get(&HasLabel("Id")).unwrap();
+    assert!(clear(&id).await.is_err());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn clear_reports_elements_users_cannot_clear() {
+    use crate::query::{HasLabel, HasText, MatchesSelector, Query};
+    use gloo::utils::body;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<label>Bio <textarea>Hello</textarea></label>
+        <label>Age <input type="text" value="30" disabled /></label>
+        <label>Id <input type="text" value="42" readonly /></label>
+        <div contenteditable="true"><b>Dear</b> Bob</div>
+        <p>Static</p>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let bio = div
+        .get_as::<HtmlTextAreaElement, _>(&HasLabel("Bio"))
+        .unwrap();
+    clear(&bio).await.unwrap();
+    assert_eq!(bio.value(), "");
+    // Clearing an empty field is fine
+    assert_eq!(clear(&bio).await, Ok(()));
+
+    let letter = div.get(&MatchesSelector("[contenteditable]")).unwrap();
+    clear(&letter).await.unwrap();
+    assert_eq!(letter.text_content().unwrap(), "");
+
+    let age = div.get(&HasLabel("Age")).unwrap();
+    let err = clear(&age).await.unwrap_err();
+    assert_eq!(err, InteractionError::Disabled(age.clone()));
+    assert_eq!(err.to_string(), format!("{} is disabled", describe(&age)));
+    let id = div.get(&HasLabel("Id")).unwrap();
+    assert_eq!(
+        clear(&id).await.unwrap_err(),
+        InteractionError::ReadOnly(id.clone())
+    );
+    assert!(clear(&id)
+        .await
+        .unwrap_err()
+        .to_string()
+        .ends_with("is read-only"));
+    let text = div.get(&HasText("Static")).unwrap();
+    assert_eq!(
+        clear(&text).await.unwrap_err(),
+        InteractionError::NotEditable(text)
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(all(test, feature = "yew"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn clear_empties_controlled_input() {
+    use crate::query::{HasRole, HasText, Query};
+    use crate::yew::render;
+    use yew::prelude::*;
+
+    #[function_component(Search)]
+    fn search() -> Html {
+        let query = use_state(|| String::from("rust"));
+        let oninput = {
+            let query = query.clone();
+            Callback::from(move |event: InputEvent| {
+                let value = event.target_unchecked_into::<HtmlInputElement>().value();
+                query.set(value);
+            })
+        };
+        html! {
+            <>
+                <input type="search" value={(*query).clone()} {oninput} />
+                if query.is_empty() { <p>{ "Type to search" }</p> }
+            </>
+        }
+    }
+
+    let mount = render(html! { <Search /> }).await;
+    let input = mount.get(&HasRole("searchbox")).unwrap();
+    assert!(mount.get(&HasText("Type to search")).is_none());
+
+    clear(&input).await.unwrap();
+    assert!(mount.get(&HasText("Type to search")).is_some());
+
+    crate::cleanup();
+}
+
+/// Replaces the value of a text field the way a user would, like [`userEvent.clear`]
+/// followed by [`userEvent.type`].
+///
+/// Clears the element like [`clear`], then types `text` like [`type_text`] does.
+/// Like [`click`], it awaits a [`tick`] at the end, so the page is already updated.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::interaction::replace_value;
+/// use frontest::prelude::*;
+/// use web_sys::HtmlInputElement;
+///
+/// # async fn rename() {
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<input type="text" value="Draft" />"#);
+/// body().append_child(&div).unwrap();
+///
+/// let input = div.get_as::<HtmlInputElement, _>(&HasRole("textbox")).unwrap();
+/// replace_value(&input, "Final").await.unwrap();
+/// assert_eq!(input.value(), "Final");
+///
+/// body().remove_child(&div).unwrap();
+/// # }
+/// ```
+///
+/// # Errors:
+/// In the same cases as [`clear`].
+///
+/// [`userEvent.clear`]: https://testing-library.com/docs/user-event/utility#clear
+/// [`userEvent.type`]: https://testing-library.com/docs/user-event/utility#type
+/// [`tick`]: crate::tick
+pub async fn replace_value(elem: &HtmlElement, text: &str) -> Result<(), InteractionError> {
+    let field = clear_field(elem)?;
+    crate::flush().await;
+    type_chars(elem, Some(&field), text).await;
+    crate::tick().await;
+    Ok(())
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_replace_value() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<input type="text" value="Draft" />"#);
+    body().append_child(&div).unwrap();
+
+    let input = div
+        .get_as::<HtmlInputElement, _>(&HasRole("textbox"))
+        .unwrap();
+    replace_value(&input, "Final").await.unwrap();
+    assert_eq!(input.value(), "Final");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(all(test, feature = "yew"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn replace_value_updates_controlled_input() {
+    use crate::query::{HasRole, HasText, Query};
+    use crate::yew::render;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use yew::prelude::*;
+
+    #[derive(Properties, PartialEq)]
+    struct Props {
+        seen: Rc<RefCell<Vec<String>>>,
+    }
+
+    #[function_component(Name)]
+    fn name(props: &Props) -> Html {
+        let name = use_state(|| String::from("Draft"));
+        let oninput = {
+            let name = name.clone();
+            let seen = props.seen.clone();
+            Callback::from(move |event: InputEvent| {
+                let value = event.target_unchecked_into::<HtmlInputElement>().value();
+                seen.borrow_mut().push(value.clone());
+                name.set(value);
+            })
+        };
+        html! {
+            <>
+                <input type="text" value={(*name).clone()} {oninput} />
+                <p>{ format!("Name: {}", *name) }</p>
+            </>
+        }
+    }
+
+    let seen = Rc::new(RefCell::new(vec![]));
+    let mount = render(html! { <Name seen={seen.clone()} /> }).await;
+    let input = mount.get(&HasRole("textbox")).unwrap();
+
+    replace_value(&input, "Final").await.unwrap();
+    assert!(mount.get(&HasText("Name: Final")).is_some());
+    assert_eq!(input.unchecked_into::<HtmlInputElement>().value(), "Final");
+    // Cleared first, then typed character by character
+    assert_eq!(*seen.borrow(), ["", "F", "Fi", "Fin", "Fina", "Final"]);
+
+    crate::cleanup();
+}
+
+/// Types text into an element character by character, the same way [`userEvent.type`] does.
+///
+/// Focuses the element, placing the caret at the end of its text unless it was already focused,
+/// in which case the current selection is replaced. Then for every character dispatches `keydown`,
+/// `keypress`, `beforeinput`, `input` with the typed `data` and `keyup`, updating the value before
+/// the `input` event, so handlers of controlled components, like `oninput` of [`yew`], see every
+/// intermediate value. Components are re-rendered between the characters, like between keystrokes
+/// of a user. Works for `<input>`, `<textarea>` and `contenteditable` elements.
+///
+/// Like in the browser, the value isn't changed if the element is read-only, or if the character
+/// doesn't fit in its `maxlength`, and a cancelled `keydown`, `keypress` or `beforeinput` prevents
+/// the change. Disabled elements get no events at all. Awaits a [`tick`] at the end.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::interaction::type_text;
+/// use frontest::prelude::*;
+/// use web_sys::HtmlInputElement;
+///
+/// # async fn type_name() {
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<label>Code <input type="text" maxlength="4" /></label>"#);
+/// body().append_child(&div).unwrap();
+///
+/// let code = div.get_as::<HtmlInputElement, _>(&HasLabel("Code")).unwrap();
+/// type_text(&code, "1234567").await;
+/// assert_eq!(code.value(), "1234");
+///
+/// body().remove_child(&div).unwrap();
+/// # }
+/// ```
+///
+/// [`userEvent.type`]: https://testing-library.com/docs/user-event/utility#type
+/// [`yew`]: ::yew
+/// [`tick`]: crate::tick
+pub async fn type_text(elem: &HtmlElement, text: &str) {
+    if elem.matches(":disabled").unwrap() {
+        return;
+    }
+    let field = focus_for_typing(elem);
+    type_chars(elem, field.as_ref(), text).await;
+    crate::tick().await;
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_type_text() {
+    use crate::query::{HasLabel, Query};
+    use gloo::utils::body;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<label>Code <input type="text" maxlength="4" /></label>"#);
+    body().append_child(&div).unwrap();
+
+    let code = div
+        .get_as::<HtmlInputElement, _>(&HasLabel("Code"))
+        .unwrap();
+    type_text(&code, "1234567").await;
+    assert_eq!(code.value(), "1234");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn type_text_respects_caret_and_restrictions() {
+    use crate::query::{HasLabel, HasText, MatchesSelector, Query};
+    use gloo::utils::body;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<label>Name <input type="text" value="Ad" /></label>
+        <label>Bio <textarea maxlength="5">Hi</textarea></label>
+        <label>Id <input type="text" value="42" readonly /></label>
+        <label>Age <input type="text" disabled /></label>
+        <div contenteditable="true">Dear </div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    // Appended, as the caret is put at the end when focusing
+    let name = div
+        .get_as::<HtmlInputElement, _>(&HasLabel("Name"))
+        .unwrap();
+    type_text(&name, "am").await;
+    assert_eq!(name.value(), "Adam");
+    // Inserted at the caret, or in place of the selection, of a focused element
+    name.set_selection_range(1, 3).unwrap();
+    type_text(&name, "li").await;
+    assert_eq!(name.value(), "Alim");
+    assert_eq!(name.selection_start().unwrap(), Some(3));
+
+    // Every code unit counts, like in the browser
+    let bio = div
+        .get_as::<HtmlTextAreaElement, _>(&HasLabel("Bio"))
+        .unwrap();
+    type_text(&bio, "!🦀🦀").await;
+    assert_eq!(bio.value(), "Hi!🦀");
+
+    let id = div.get_as::<HtmlInputElement, _>(&HasLabel("Id")).unwrap();
+    type_text(&id, "7").await;
+    assert_eq!(id.value(), "42");
+    assert_eq!(document().active_element(), Some(id.into()));
+    let age = div.get_as::<HtmlInputElement, _>(&HasLabel("Age")).unwrap();
+    type_text(&age, "7").await;
+    assert_eq!(age.value(), "");
+
+    let letter = div.get(&MatchesSelector("[contenteditable]")).unwrap();
+    type_text(&letter, "Bob").await;
+    assert_eq!(letter.text_content().unwrap(), "Dear Bob");
+    assert!(div.get(&HasText("Dear Bob")).is_some());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(all(test, feature = "yew"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn type_text_updates_controlled_input() {
+    use crate::query::{HasRole, HasText, Query};
+    use crate::yew::render;
+    use yew::prelude::*;
+
+    #[function_component(Search)]
+    fn search() -> Html {
+        let query = use_state(String::new);
+        let oninput = {
+            let query = query.clone();
+            Callback::from(move |event: InputEvent| {
+                let value = event.target_unchecked_into::<HtmlInputElement>().value();
+                query.set(value.to_uppercase());
+            })
+        };
+        html! {
+            <>
+                <input type="search" value={(*query).clone()} {oninput} />
+                <p>{ format!("Searching for: {}", *query) }</p>
+            </>
+        }
+    }
+
+    let mount = render(html! { <Search /> }).await;
+    let input = mount
+        .get_as::<HtmlInputElement, _>(&HasRole("searchbox"))
+        .unwrap();
+
+    type_text(&input, "rust").await;
+    assert!(mount.get(&HasText("Searching for: RUST")).is_some());
+    assert_eq!(input.value(), "RUST");
+
+    crate::cleanup();
+}
+
+/// A step of a key sequence given to [`keyboard`].
+enum KeyStep {
+    Press(String),
+    Hold(String),
+    Release(String),
+}
+
+/// Parses a key sequence in the syntax of [`keyboard`], returning [`None`] if it is invalid.
+fn parse_keys(sequence: &str) -> Option<Vec<KeyStep>> {
+    let mut steps = vec![];
+    let mut chars = sequence.chars().peekable();
+    while let Some(char) = chars.next() {
+        if char != '{' {
+            steps.push(KeyStep::Press(char.to_string()));
+            continue;
+        }
+        if chars.next_if_eq(&'{').is_some() {
+            steps.push(KeyStep::Press(String::from("{")));
+            continue;
+        }
+        let mut name = String::new();
+        loop {
+            match chars.next()? {
+                '}' => break,
+                char => name.push(char),
+            }
+        }
+        let step = if let Some(key) = name.strip_prefix('/') {
+            KeyStep::Release(key.to_owned())
+        } else if let Some(key) = name.strip_suffix('>') {
+            KeyStep::Hold(key.to_owned())
+        } else {
+            KeyStep::Press(name.clone())
+        };
+        match &step {
+            KeyStep::Press(key) | KeyStep::Hold(key) | KeyStep::Release(key) if key.is_empty() => {
+                return None
+            }
+            _ => steps.push(step),
+        }
+    }
+    Some(steps)
+}
+
+/// Presses keys on an element, using the syntax of [`userEvent.keyboard`] for special keys.
+///
+/// Characters are typed one by one, like with [`type_text`]. Other keys are given by their [`key`]
+/// value in braces, like `"{Enter}"`, `"{Escape}"`, `"{Tab}"` or `"{ArrowDown}"`. A key can be held
+/// with `"{Shift>}"` until it is released with `"{/Shift}"`, which allows chords like
+/// `"{Control>}a{/Control}"`. Keys still held at the end are released. A literal brace is typed with `"{{"`.
+///
+/// The element is focused first, and every key dispatches `keydown`, `keyup` and, for characters
+/// and Enter, `keypress`, with the `key`, `code` and modifier flags of held keys. Characters aren't
+/// typed while Control, Alt or Meta is held. Unless the events are cancelled, the default action
+/// of the browser is simulated: Enter clicks a focused button or link, inserts a line break into
+/// a `<textarea>` and submits the form of an `<input>`, Space clicks a focused button or checkbox,
+/// and Backspace deletes text. Other keys, like Tab or the arrows, only dispatch the events.
+/// Components are re-rendered after every key, and a [`tick`] is awaited at the end.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// # use gloo::events::{EventListener, EventListenerOptions};
+/// use frontest::interaction::keyboard;
+/// use frontest::prelude::*;
+/// use frontest::wait::{next_event, WaitOptions};
+///
+/// # async fn search() {
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<form role="search"><input type="search" /></form>"#);
+/// body().append_child(&div).unwrap();
+///
+/// let form = div.get(&HasRole("search")).unwrap();
+/// let options = EventListenerOptions::enable_prevent_default();
+/// let _submit = EventListener::new_with_options(&form, "submit", options, |event| {
+///     event.prevent_default()
+/// });
+/// let submitted = next_event(&form, "submit", WaitOptions::current());
+///
+/// let input = div.get(&HasRole("searchbox")).unwrap();
+/// keyboard(&input, "rust{Enter}").await;
+/// assert!(submitted.await.is_ok());
+///
+/// body().remove_child(&div).unwrap();
+/// # }
+/// ```
+///
+/// # Panics:
+/// If the sequence is invalid, eg. a brace isn't closed or a key is released without being held.
+///
+/// [`userEvent.keyboard`]: https://testing-library.com/docs/user-event/keyboard
+/// [`key`]: https://developer.mozilla.org/en-US/docs/Web/API/UI_Events/Keyboard_event_key_values
+/// [`tick`]: crate::tick
+pub async fn keyboard(target: &HtmlElement, sequence: &str) {
+    let Some(steps) = parse_keys(sequence) else {
+        panic!("Invalid key sequence: {:?}", sequence);
+    };
+    if target.matches(":disabled").unwrap() {
+        return;
+    }
+    let field = focus_for_typing(target);
+    let mut modifiers = Modifiers::default();
+    let mut held = vec![];
+    for step in steps {
+        match step {
+            KeyStep::Press(key) if modifiers.flag(&key).is_some() => {
+                *modifiers.flag(&key).unwrap() = true;
+                dispatch_keyboard(target, "keydown", &key, modifiers);
+                *modifiers.flag(&key).unwrap() = false;
+                dispatch_keyboard(target, "keyup", &key, modifiers);
+            }
+            KeyStep::Press(key) => press_key(target, field.as_ref(), &key, modifiers),
+            KeyStep::Hold(key) => {
+                if let Some(flag) = modifiers.flag(&key) {
+                    *flag = true;
+                }
+                dispatch_keyboard(target, "keydown", &key, modifiers);
+                held.push(key);
+            }
+            KeyStep::Release(key) => {
+                let Some(idx) = held.iter().position(|held| *held == key) else {
+                    panic!("Invalid key sequence: {:?}, {} is not held", sequence, key);
+                };
+                release(target, &held.remove(idx), &mut modifiers);
+            }
+        }
+        crate::flush().await;
+    }
+    for key in held.iter().rev() {
+        release(target, key, &mut modifiers);
+    }
+    crate::tick().await;
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_keyboard() {
+    use crate::query::{HasRole, Query};
+    use crate::wait::{next_event, WaitOptions};
+    use gloo::events::{EventListener, EventListenerOptions};
+    use gloo::utils::body;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<form role="search"><input type="search" /></form>"#);
+    body().append_child(&div).unwrap();
+
+    let form = div.get(&HasRole("search")).unwrap();
+    let options = EventListenerOptions::enable_prevent_default();
+    let _submit =
+        EventListener::new_with_options(&form, "submit", options, |event| event.prevent_default());
+    let submitted = next_event(&form, "submit", WaitOptions::current());
+
+    let input = div.get(&HasRole("searchbox")).unwrap();
+    keyboard(&input, "rust{Enter}").await;
+    assert!(submitted.await.is_ok());
+    assert_eq!(input.unchecked_into::<HtmlInputElement>().value(), "rust");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn keyboard_dispatches_keys_and_default_actions() {
+    use crate::query::{HasLabel, HasRole, Query};
+    use gloo::events::{EventListener, EventListenerOptions};
+    use gloo::utils::body;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<form>
+            <label>Name <input type="text" value="Bobby" /></label>
+            <label>Notes <textarea></textarea></label>
+            <button type="submit" disabled>Save</button>
+        </form>
+        <button type="button">Like</button>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let events = Rc::new(RefCell::new(vec![]));
+    let record = |target: &HtmlElement, type_: &'static str| {
+        let events = events.clone();
+        let options = EventListenerOptions::enable_prevent_default();
+        EventListener::new_with_options(target, type_, options, move |event| {
+            if let Some(event) = event.dyn_ref::<KeyboardEvent>() {
+                let ctrl = if event.ctrl_key() { "ctrl+" } else { "" };
+                let key = format!("{} {}{} {}", type_, ctrl, event.key(), event.code());
+                events.borrow_mut().push(key);
+            } else {
+                // Submitting would leave the page of the tests
+                event.prevent_default();
+                events.borrow_mut().push(String::from(type_));
+            }
+        })
+    };
+
+    let name = div
+        .get_as::<HtmlInputElement, _>(&HasLabel("Name"))
+        .unwrap();
+    let _listeners = ["keydown", "keyup", "submit"].map(|type_| record(&name, type_));
+    keyboard(&name, "{Backspace}{Backspace}{Control>}a{/Control}{Enter}").await;
+    assert_eq!(name.value(), "Bob");
+    // Characters aren't typed with Control, and a disabled default button blocks submitting
+    assert_eq!(
+        *events.borrow(),
+        [
+            "keydown Backspace Backspace",
+            "keyup Backspace Backspace",
+            "keydown Backspace Backspace",
+            "keyup Backspace Backspace",
+            "keydown ctrl+Control ControlLeft",
+            "keydown ctrl+a KeyA",
+            "keyup ctrl+a KeyA",
+            "keyup Control ControlLeft",
+            "keydown Enter Enter",
+            "keyup Enter Enter",
+        ]
+    );
+
+    let notes = div
+        .get_as::<HtmlTextAreaElement, _>(&HasLabel("Notes"))
+        .unwrap();
+    keyboard(&notes, "{{a}{Enter}b{Shift>}").await;
+    assert_eq!(notes.value(), "{a}\nb");
+
+    let like = div.get(&HasRole("button").named("Like")).unwrap();
+    events.borrow_mut().clear();
+    let _clicks = record(&like, "click");
+    keyboard(&like, "{Enter} ").await;
+    assert_eq!(*events.borrow(), ["click", "click"]);
+    assert_eq!(document().active_element(), Some(like.into()));
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+#[should_panic(expected = "Invalid key sequence: \"{Shift>}a{/Control}\", Control is not held")]
+async fn keyboard_panics_on_releasing_not_held_key() {
+    let input = document().create_element("input").unwrap();
+    keyboard(input.unchecked_ref(), "{Shift>}a{/Control}").await;
+}
+
+#[cfg(all(test, feature = "yew"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn keyboard_navigates_dropdown() {
+    use crate::query::{HasRole, HasText, Query};
+    use crate::yew::render;
+    use yew::prelude::*;
+
+    const FRUITS: [&str; 3] = ["Apple", "Banana", "Cherry"];
+
+    #[function_component(Dropdown)]
+    fn dropdown() -> Html {
+        let active = use_state(|| 0);
+        let selected = use_state(|| None);
+        let onkeydown = {
+            let active = active.clone();
+            let selected = selected.clone();
+            Callback::from(move |event: KeyboardEvent| match event.key().as_str() {
+                "ArrowDown" => active.set((*active + 1).min(FRUITS.len() - 1)),
+                "ArrowUp" => active.set(active.saturating_sub(1)),
+                "Enter" => selected.set(Some(FRUITS[*active])),
+                _ => {}
+            })
+        };
+        html! {
+            <>
+                <ul role="listbox" tabindex="0" {onkeydown}>
+                    { for FRUITS.iter().enumerate().map(|(idx, fruit)| html! {
+                        <li role="option" aria-selected={(idx == *active).to_string()}>{ fruit }</li>
+                    }) }
+                </ul>
+                <p>{ format!("Selected: {}", selected.unwrap_or("nothing")) }</p>
+            </>
+        }
+    }
+
+    let mount = render(html! { <Dropdown /> }).await;
+    let listbox = mount.get(&HasRole("listbox")).unwrap();
+
+    keyboard(&listbox, "{ArrowDown}{ArrowDown}{ArrowDown}{ArrowUp}").await;
+    assert!(mount.get(&HasText("Selected: nothing")).is_some());
+    keyboard(&listbox, "{Enter}").await;
+    assert!(mount.get(&HasText("Selected: Banana")).is_some());
+
+    crate::cleanup();
+}
+
+/// Releases a held key.
+fn release(target: &HtmlElement, key: &str, modifiers: &mut Modifiers) {
+    if let Some(flag) = modifiers.flag(key) {
+        *flag = false;
+    }
+    dispatch_keyboard(target, "keyup", key, *modifiers);
+}
+
+/// Identifies an option of a `<select>` for [`select_option`], [`select_options`]
+/// and [`deselect_options`].
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum OptionSelector<'a> {
+    /// The option with given text, as the user sees it.
+    ///
+    /// It is compared exactly, after normalizing whitespace, like [`HasSelectedOption`] does.
+    ///
+    /// [`HasSelectedOption`]: crate::query::HasSelectedOption
+    Label(&'a str),
+    /// The option with given `value`.
+    Value(&'a str),
+    /// The option at given index, counting all options of the select, also those inside `<optgroup>`s.
+    Index(u32),
+}
+
+impl std::fmt::Display for OptionSelector<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Label(label) => write!(f, "label {:?}", label),
+            Self::Value(value) => write!(f, "value {:?}", value),
+            Self::Index(idx) => write!(f, "index {}", idx),
+        }
+    }
+}
+
+/// Returns all options of a select, in order, including those inside `<optgroup>`s.
+fn options_of(select: &HtmlSelectElement) -> Vec<HtmlOptionElement> {
+    (0..select.length())
+        .filter_map(|idx| select.item(idx))
+        .map(JsCast::unchecked_into)
+        .collect()
+}
+
+/// Returns the text of an option, as compared by [`OptionSelector::Label`].
+fn option_label(option: &HtmlOptionElement) -> String {
+    normalize_whitespace(&option.text_content().unwrap_or_default())
+}
+
+/// Finds the options to change in a select, making sure the user could change them all.
+fn resolve_options(
+    elem: &HtmlElement,
+    selectors: &[OptionSelector<'_>],
+) -> Result<(HtmlSelectElement, Vec<HtmlOptionElement>), InteractionError> {
+    let Some(select) = elem.dyn_ref::<HtmlSelectElement>() else {
+        return Err(InteractionError::NotSelect(elem.clone()));
+    };
+    if select.disabled() {
+        return Err(InteractionError::Disabled(elem.clone()));
+    }
+    if selectors.len() > 1 && !select.multiple() {
+        return Err(InteractionError::NotMultiple(elem.clone()));
+    }
+    let options = options_of(select);
+    let mut found = vec![];
+    for selector in selectors {
+        let option = match selector {
+            OptionSelector::Label(label) => {
+                let label = normalize_whitespace(label);
+                options.iter().find(|option| option_label(option) == label)
+            }
+            OptionSelector::Value(value) => options.iter().find(|option| option.value() == *value),
+            OptionSelector::Index(idx) => options.get(*idx as usize),
+        };
+        let Some(option) = option else {
+            return Err(InteractionError::NoOption {
+                select: elem.clone(),
+                option: selector.to_string(),
+                available: options.iter().map(option_label).collect(),
+            });
+        };
+        // Options are disabled by themselves or by their `<optgroup>`
+        if option.matches(":disabled").unwrap() {
+            return Err(InteractionError::Disabled(option.clone().into()));
+        }
+        found.push(option.clone());
+    }
+    Ok((select.clone(), found))
+}
+
+/// Dispatches `input` and `change`, as browsers do when a user changes the value of a form control.
+fn dispatch_change(elem: &HtmlElement) {
+    let init = EventInit::new();
+    init.set_bubbles(true);
+    init.set_composed(true);
+    let input = Event::new_with_event_init_dict("input", &init).unwrap();
+    elem.dispatch_event(&input).unwrap();
+    init.set_composed(false);
+    let change = Event::new_with_event_init_dict("change", &init).unwrap();
+    elem.dispatch_event(&change).unwrap();
+}
+
+/// Changes the selectedness of options, dispatching `input` and `change` for every changed one.
+async fn set_selected(elem: &HtmlElement, options: &[HtmlOptionElement], selected: bool) {
+    click_sync(elem);
+    for option in options {
+        if option.selected() == selected {
+            continue;
+        }
+        option.set_selected(selected);
+        dispatch_change(elem);
+        crate::flush().await;
+    }
+    crate::tick().await;
+}
+
+/// Selects an option of a `<select>` the way a user would, like [`userEvent.selectOptions`].
+///
+/// Clicks the select and selects the option, dispatching `input` and `change`, so handlers
+/// of components see the new value. Nothing is dispatched if the option is already selected.
+/// Options inside `<optgroup>`s can be selected as well. In a `<select multiple>` the option
+/// is selected in addition to those already selected, see [`select_options`] to select more at once.
+/// Like [`click`], it awaits a [`tick`] at the end.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::interaction::{select_option, OptionSelector};
+/// use frontest::prelude::*;
+///
+/// # async fn pick_fruit() {
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<label>Fruit
+///         <select>
+///             <option value="apple">Apple</option>
+///             <option value="pear">Pear</option>
+///         </select>
+///     </label>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// let select = div.get(&HasLabel("Fruit")).unwrap();
+/// select_option(&select, &OptionSelector::Label("Pear")).await.unwrap();
+/// assert!(div.get(&HasSelectedOption::new("Pear")).is_some());
+/// select_option(&select, &OptionSelector::Value("apple")).await.unwrap();
+/// assert!(div.get(&HasSelectedOption::new("Apple")).is_some());
+///
+/// let err = select_option(&select, &OptionSelector::Label("Kiwi")).await.unwrap_err();
+/// assert!(err.to_string().ends_with(r#"available options: ["Apple", "Pear"]"#));
+///
+/// body().remove_child(&div).unwrap();
+/// # }
+/// ```
+///
+/// # Errors:
+/// If the element isn't a `<select>`, there is no such option, or the select or the option is disabled.
+///
+/// [`userEvent.selectOptions`]: https://testing-library.com/docs/user-event/utility#selectoptions-deselectoptions
+/// [`tick`]: crate::tick
+pub async fn select_option(
+    select: &HtmlElement,
+    option: &OptionSelector<'_>,
+) -> Result<(), InteractionError> {
+    select_options(select, std::slice::from_ref(option)).await
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_select_option() {
+    use crate::query::{HasLabel, HasSelectedOption, Query};
+    use gloo::utils::body;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<label>Fruit
+            <select>
+                <option value="apple">Apple</option>
+                <option value="pear">Pear</option>
+            </select>
+        </label>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let select = div.get(&HasLabel("Fruit")).unwrap();
+    select_option(&select, &OptionSelector::Label("Pear"))
+        .await
+        .unwrap();
+    assert!(div.get(&HasSelectedOption::new("Pear")).is_some());
+    select_option(&select, &OptionSelector::Value("apple"))
+        .await
+        .unwrap();
+    assert!(div.get(&HasSelectedOption::new("Apple")).is_some());
+
+    let err = select_option(&select, &OptionSelector::Label("Kiwi"))
+        .await
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .ends_with(r#"available options: ["Apple", "Pear"]"#));
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Selects options of a `<select multiple>`, in addition to those already selected.
+///
+/// Works like [`select_option`] for every option. All options are found first, so nothing is
+/// changed if any of them is missing.
+///
+/// # Errors:
+/// In the same cases as [`select_option`], and if more than one option is given for a select
+/// without the `multiple` attribute.
+pub async fn select_options(
+    select: &HtmlElement,
+    options: &[OptionSelector<'_>],
+) -> Result<(), InteractionError> {
+    let (_, options) = resolve_options(select, options)?;
+    set_selected(select, &options, true).await;
+    Ok(())
+}
+
+/// Deselects options of a `<select multiple>`, the opposite of [`select_options`].
+///
+/// Nothing is dispatched for options which aren't selected.
+///
+/// # Errors:
+/// In the same cases as [`select_option`], and if the select doesn't have the `multiple` attribute,
+/// as users can't deselect options of other selects.
+pub async fn deselect_options(
+    select: &HtmlElement,
+    options: &[OptionSelector<'_>],
+) -> Result<(), InteractionError> {
+    let (elem, options) = resolve_options(select, options)?;
+    if !elem.multiple() {
+        return Err(InteractionError::NotMultiple(select.clone()));
+    }
+    set_selected(select, &options, false).await;
+    Ok(())
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn select_options_reports_options_users_cannot_change() {
+    use crate::query::{HasLabel, HasText, Query};
+    use gloo::utils::body;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<label>Size
+            <select>
+                <option>Small</option>
+                <option disabled>Medium</option>
+                <optgroup label="Custom" disabled><option>Huge</option></optgroup>
+            </select>
+        </label>
+        <label>Color <select disabled><option>Red</option></select></label>
+        <p>Static</p>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let size = div.get(&HasLabel("Size")).unwrap();
+    let err = select_option(&size, &OptionSelector::Index(3))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err,
+        InteractionError::NoOption {
+            select: size.clone(),
+            option: String::from("index 3"),
+            available: vec!["Small".into(), "Medium".into(), "Huge".into()],
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        format!(
+            r#"{} has no option with index 3, available options: ["Small", "Medium", "Huge"]"#,
+            describe(&size)
+        )
+    );
+    let medium = div.get(&HasText("Medium")).unwrap();
+    assert_eq!(
+        select_option(&size, &OptionSelector::Label("Medium")).await,
+        Err(InteractionError::Disabled(medium))
+    );
+    assert!(matches!(
+        select_option(&size, &OptionSelector::Index(2)).await,
+        Err(InteractionError::Disabled(_))
+    ));
+    assert_eq!(
+        select_options(&size, &[OptionSelector::Index(0), OptionSelector::Index(0)]).await,
+        Err(InteractionError::NotMultiple(size.clone()))
+    );
+    assert_eq!(
+        deselect_options(&size, &[OptionSelector::Label("Small")]).await,
+        Err(InteractionError::NotMultiple(size.clone()))
+    );
+    let color = div.get(&HasLabel("Color")).unwrap();
+    assert_eq!(
+        select_option(&color, &OptionSelector::Label("Red")).await,
+        Err(InteractionError::Disabled(color))
+    );
+    let text = div.get(&HasText("Static")).unwrap();
+    assert_eq!(
+        select_option(&text, &OptionSelector::Index(0)).await,
+        Err(InteractionError::NotSelect(text))
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(all(test, feature = "yew"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn select_options_notify_onchange() {
+    use crate::query::{HasLabel, HasText, Query};
+    use crate::yew::render;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use yew::prelude::*;
+
+    #[derive(Properties, PartialEq)]
+    struct Props {
+        changes: Rc<RefCell<Vec<String>>>,
+    }
+
+    #[function_component(Order)]
+    fn order(props: &Props) -> Html {
+        let dish = use_state(String::new);
+        let extras = use_state(Vec::new);
+        let onchange = {
+            let dish = dish.clone();
+            let changes = props.changes.clone();
+            Callback::from(move |event: Event| {
+                let value = event.target_unchecked_into::<HtmlSelectElement>().value();
+                changes.borrow_mut().push(value.clone());
+                dish.set(value);
+            })
+        };
+        let onchange_extras = {
+            let extras = extras.clone();
+            Callback::from(move |event: Event| {
+                let select = event.target_unchecked_into::<HtmlSelectElement>();
+                let selected = select.selected_options();
+                let values = (0..selected.length())
+                    .filter_map(|idx| selected.item(idx))
+                    .map(|option| option.text_content().unwrap())
+                    .collect::<Vec<_>>();
+                extras.set(values);
+            })
+        };
+        html! {
+            <>
+                <label>{ "Dish" }
+                    <select {onchange}>
+                        <option value="">{ "Pick one" }</option>
+                        <optgroup label="Pasta">
+                            <option value="carbonara">{ "Carbonara" }</option>
+                            <option value="pesto">{ "Pesto" }</option>
+                        </optgroup>
+                        <optgroup label="Pizza">
+                            <option value="margherita">{ "Margherita" }</option>
+                        </optgroup>
+                    </select>
+                </label>
+                <label>{ "Extras" }
+                    <select multiple=true onchange={onchange_extras}>
+                        <option>{ "Cheese" }</option>
+                        <option>{ "Olives" }</option>
+                        <option>{ "Basil" }</option>
+                    </select>
+                </label>
+                <p>{ format!("Dish: {}", *dish) }</p>
+                <p>{ format!("Extras: {}", extras.join(", ")) }</p>
+            </>
+        }
+    }
+
+    let changes = Rc::new(RefCell::new(vec![]));
+    let mount = render(html! { <Order changes={changes.clone()} /> }).await;
+    let dish = mount.get(&HasLabel("Dish")).unwrap();
+
+    select_option(&dish, &OptionSelector::Label("Pesto"))
+        .await
+        .unwrap();
+    assert!(mount.get(&HasText("Dish: pesto")).is_some());
+    select_option(&dish, &OptionSelector::Index(3))
+        .await
+        .unwrap();
+    assert!(mount.get(&HasText("Dish: margherita")).is_some());
+    // Selecting the selected option again changes nothing
+    select_option(&dish, &OptionSelector::Value("margherita"))
+        .await
+        .unwrap();
+    assert_eq!(*changes.borrow(), ["pesto", "margherita"]);
+    assert_eq!(document().active_element(), Some(dish.into()));
+
+    let extras = mount.get(&HasLabel("Extras")).unwrap();
+    select_options(
+        &extras,
+        &[
+            OptionSelector::Label("Basil"),
+            OptionSelector::Label("Cheese"),
+        ],
+    )
+    .await
+    .unwrap();
+    assert!(mount.get(&HasText("Extras: Cheese, Basil")).is_some());
+    select_option(&extras, &OptionSelector::Label("Olives"))
+        .await
+        .unwrap();
+    assert!(mount
+        .get(&HasText("Extras: Cheese, Olives, Basil"))
+        .is_some());
+    deselect_options(
+        &extras,
+        &[OptionSelector::Label("Cheese"), OptionSelector::Index(2)],
+    )
+    .await
+    .unwrap();
+    assert!(mount.get(&HasText("Extras: Olives")).is_some());
+
+    crate::cleanup();
+}
+
+/// Returns whether an element is a radio and whether it is checked, or [`None`] if its state is mixed.
+///
+/// Native checkboxes and radios, as well as elements with role `checkbox`, `switch` or `radio`
+/// are supported.
+fn checked_state(elem: &HtmlElement) -> Result<(bool, Option<bool>), InteractionError> {
+    let radio = if HasRole("radio").matches(elem) {
+        true
+    } else if HasRole("checkbox").matches(elem) || HasRole("switch").matches(elem) {
+        false
+    } else {
+        return Err(InteractionError::NotCheckable(elem.clone()));
+    };
+    let state =
+        crate::query::checked_state(elem).or_else(|| match elem.dyn_ref::<HtmlInputElement>() {
+            // Clicking an indeterminate checkbox toggles its `checked` like any other
+            Some(input) => Some(input.checked()),
+            // Missing `aria-checked` means unchecked, unlike `mixed`
+            None => (elem.get_attribute("aria-checked").as_deref().map(str::trim) != Some("mixed"))
+                .then_some(false),
+        });
+    Ok((radio, state))
+}
+
+/// Clicks a checkable element unless it is already in the `checked` state, see [`check`].
+async fn set_checked(elem: &HtmlElement, checked: bool) -> Result<(), InteractionError> {
+    let (radio, state) = checked_state(elem)?;
+    if elem.matches(":disabled").unwrap()
+        || elem.get_attribute("aria-disabled").as_deref() == Some("true")
+    {
+        return Err(InteractionError::Disabled(elem.clone()));
+    }
+    if radio && !checked {
+        return Err(InteractionError::CannotUncheck(elem.clone()));
+    }
+    if state != Some(checked) {
+        click(elem).await;
+    }
+    Ok(())
+}
+
+/// Checks a checkbox, a radio or a switch the way a user would, unless it is already checked.
+///
+/// Unlike clicking, this reaches a known state regardless of the initial one. The element is
+/// [`click`]ed only if it isn't checked yet, including when its state is mixed. Elements with role
+/// `checkbox`, `switch` or `radio` are supported as well, but their `aria-checked` is never changed
+/// directly: the click has to be handled by the page, as it is for the user.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::interaction::{check, uncheck};
+/// use frontest::prelude::*;
+/// use web_sys::HtmlInputElement;
+///
+/// # async fn accept_terms() {
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<label><input type="checkbox" /> I accept the terms</label>"#);
+/// body().append_child(&div).unwrap();
+///
+/// let terms = div.get_as::<HtmlInputElement, _>(&HasRole("checkbox")).unwrap();
+/// check(&terms).await.unwrap();
+/// check(&terms).await.unwrap();
+/// assert!(terms.checked());
+/// uncheck(&terms).await.unwrap();
+/// assert!(!terms.checked());
+///
+/// body().remove_child(&div).unwrap();
+/// # }
+/// ```
+///
+/// # Errors:
+/// If the element isn't a checkbox, a radio or a switch, or it is disabled.
+pub async fn check(elem: &HtmlElement) -> Result<(), InteractionError> {
+    set_checked(elem, true).await
+}
+
+/// Unchecks a checkbox or a switch the way a user would, unless it is already unchecked.
+///
+/// See [`check`] for details.
+///
+/// # Errors:
+/// In the same cases as [`check`], and for radios, which users can only uncheck
+/// by checking another radio of the group.
+pub async fn uncheck(elem: &HtmlElement) -> Result<(), InteractionError> {
+    set_checked(elem, false).await
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_check() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::body;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<label><input type="checkbox" /> I accept the terms</label>"#);
+    body().append_child(&div).unwrap();
+
+    let terms = div
+        .get_as::<HtmlInputElement, _>(&HasRole("checkbox"))
+        .unwrap();
+    check(&terms).await.unwrap();
+    check(&terms).await.unwrap();
+    assert!(terms.checked());
+    uncheck(&terms).await.unwrap();
+    assert!(!terms.checked());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn check_reaches_state_of_native_controls() {
+    use crate::query::{HasLabel, HasText, Query};
+    use gloo::events::EventListener;
+    use gloo::utils::body;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<label><input type="checkbox" checked /> Newsletter</label>
+        <label><input type="checkbox" disabled /> Premium</label>
+        <fieldset>
+            <label><input type="radio" name="size" checked /> Small</label>
+            <label><input type="radio" name="size" /> Large</label>
+        </fieldset>
+        <p>Static</p>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let newsletter = div
+        .get_as::<HtmlInputElement, _>(&HasLabel("Newsletter"))
+        .unwrap();
+    let changes = Rc::new(Cell::new(0));
+    let _listener = EventListener::new(&newsletter, "change", {
+        let changes = changes.clone();
+        move |_| changes.set(changes.get() + 1)
+    });
+    check(&newsletter).await.unwrap();
+    assert_eq!(changes.get(), 0);
+    uncheck(&newsletter).await.unwrap();
+    uncheck(&newsletter).await.unwrap();
+    assert!(!newsletter.checked());
+    assert_eq!(changes.get(), 1);
+
+    let premium = div.get(&HasLabel("Premium")).unwrap();
+    assert_eq!(
+        check(&premium).await,
+        Err(InteractionError::Disabled(premium))
+    );
+
+    let small = div
+        .get_as::<HtmlInputElement, _>(&HasLabel("Small"))
+        .unwrap();
+    let large = div
+        .get_as::<HtmlInputElement, _>(&HasLabel("Large"))
+        .unwrap();
+    check(&large).await.unwrap();
+    assert!(large.checked() && !small.checked());
+    let err = uncheck(&large).await.unwrap_err();
+    assert_eq!(err, InteractionError::CannotUncheck(large.clone().into()));
+    assert!(err
+        .to_string()
+        .ends_with("is a radio, which can't be unchecked"));
+    assert!(large.checked());
+
+    let text = div.get(&HasText("Static")).unwrap();
+    assert_eq!(
+        check(&text).await,
+        Err(InteractionError::NotCheckable(text))
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn check_resolves_roles_like_queries() {
+    use crate::query::{register_custom_role, HasText, Query};
+    use gloo::events::EventListener;
+    use gloo::utils::body;
+
+    register_custom_role("toggle-switch", &["switch"]);
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<toggle-switch aria-checked="false">Dark mode</toggle-switch>
+        <div role="checkbox" aria-checked="mixed">All</div>
+        <div role="checkbox">None</div>"#,
+    );
+    body().append_child(&div).unwrap();
+    let _listener = EventListener::new(&div, "click", |event| {
+        let target = event.target().unwrap().unchecked_into::<Element>();
+        let checked = target.get_attribute("aria-checked").as_deref() == Some("true");
+        target
+            .set_attribute("aria-checked", if checked { "false" } else { "true" })
+            .unwrap();
+    });
+
+    // Registered implicit roles are checkable too
+    let dark = div.get(&HasText("Dark mode")).unwrap();
+    check(&dark).await.unwrap();
+    check(&dark).await.unwrap();
+    assert_eq!(dark.get_attribute("aria-checked").unwrap(), "true");
+
+    // Mixed is clicked, a missing `aria-checked` is unchecked
+    let all = div.get(&HasText("All")).unwrap();
+    check(&all).await.unwrap();
+    assert_eq!(all.get_attribute("aria-checked").unwrap(), "true");
+    let none = div.get(&HasText("None")).unwrap();
+    uncheck(&none).await.unwrap();
+    assert!(!none.has_attribute("aria-checked"));
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(all(test, feature = "yew"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn check_toggles_switch_through_its_handler() {
+    use crate::query::{HasRole, HasText, Query};
+    use crate::yew::render;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use yew::prelude::*;
+
+    #[derive(Properties, PartialEq)]
+    struct Props {
+        clicks: Rc<Cell<u32>>,
+    }
+
+    #[function_component(DarkMode)]
+    fn dark_mode(props: &Props) -> Html {
+        let on = use_state(|| false);
+        let onclick = {
+            let on = on.clone();
+            let clicks = props.clicks.clone();
+            Callback::from(move |_| {
+                clicks.set(clicks.get() + 1);
+                on.set(!*on);
+            })
+        };
+        html! {
+            <>
+                <button role="switch" aria-checked={on.to_string()} {onclick}>{ "Dark mode" }</button>
+                <p>{ if *on { "Theme: dark" } else { "Theme: light" } }</p>
+            </>
+        }
+    }
+
+    let clicks = Rc::new(Cell::new(0));
+    let mount = render(html! { <DarkMode clicks={clicks.clone()} /> }).await;
+    let switch = mount.get(&HasRole("switch")).unwrap();
+
+    check(&switch).await.unwrap();
+    check(&switch).await.unwrap();
+    assert!(mount.get(&HasText("Theme: dark")).is_some());
+    assert_eq!(switch.get_attribute("aria-checked").unwrap(), "true");
+    uncheck(&switch).await.unwrap();
+    assert!(mount.get(&HasText("Theme: light")).is_some());
+    assert_eq!(clicks.get(), 2);
+
+    crate::cleanup();
+}
+
+/// Options of [`tab`] and [`shift_tab`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TabOptions {
+    /// Moves the focus only between elements in the subtree of given element, like a focus trap does.
+    ///
+    /// By default all elements of the document are in the tab order.
+    pub within: Option<Element>,
+}
+
+/// Returns whether a radio is reachable with Tab: the checked one of its group or, if there
+/// is none, the first one.
+fn is_tabbable_radio(input: &HtmlInputElement, candidates: &[HtmlElement]) -> bool {
+    let group = candidates
+        .iter()
+        .filter_map(|elem| elem.dyn_ref::<HtmlInputElement>())
+        .filter(|other| {
+            other.type_() == "radio" && other.name() == input.name() && other.form() == input.form()
+        })
+        .collect::<Vec<_>>();
+    match group.iter().find(|radio| radio.checked()) {
+        Some(checked) => *checked == input,
+        None => group.first() == Some(&input),
+    }
+}
+
+/// Returns the elements reachable with Tab in the subtree of `root`, in the tab order.
+///
+/// Those are focusable elements which aren't disabled, hidden or inert and don't have
+/// a negative `tabindex`. Elements with a positive `tabindex` come first, in its ascending order,
+/// followed by the others in the document order.
+fn tab_order(root: &Element) -> Vec<HtmlElement> {
+    let focusable = root.query_selector_all(FOCUSABLE).unwrap();
+    let candidates = (0..focusable.length())
+        .filter_map(|idx| focusable.item(idx))
+        .map(JsCast::unchecked_into::<HtmlElement>)
+        .filter(|elem| is_focusable(elem) && crate::debug::hidden_reason(elem).is_none())
+        .collect::<Vec<_>>();
+    let mut tabbable = candidates
+        .iter()
+        .filter(|elem| match elem.dyn_ref::<HtmlInputElement>() {
+            Some(input) if input.type_() == "radio" && !input.name().is_empty() => {
+                is_tabbable_radio(input, &candidates)
+            }
+            _ => true,
+        })
+        .map(|elem| {
+            // Elements focusable by themselves are tabbable without the attribute
+            let idx = if elem.has_attribute("tabindex") {
+                elem.tab_index()
+            } else {
+                0
+            };
+            (idx, elem.clone())
+        })
+        .filter(|(idx, _)| *idx >= 0)
+        .collect::<Vec<_>>();
+    tabbable.sort_by_key(|(idx, _)| (*idx == 0, *idx));
+    tabbable.into_iter().map(|(_, elem)| elem).collect()
+}
+
+/// Returns the focused element, or the body if there is none.
+fn focused() -> HtmlElement {
+    document()
+        .active_element()
+        .and_then(|elem| elem.dyn_into().ok())
+        .unwrap_or_else(|| document().body().unwrap())
+}
+
+/// Moves the focus in the tab order, forward or backward.
+async fn move_focus(options: TabOptions, backward: bool) {
+    let modifiers = Modifiers {
+        shift: backward,
+        ..Default::default()
+    };
+    let from = focused();
+    if dispatch_keyboard(&from, "keydown", "Tab", modifiers) {
+        let root = options
+            .within
+            .unwrap_or_else(|| document().document_element().unwrap());
+        let order = tab_order(&root);
+        let next = match order.iter().position(|elem| *elem == from) {
+            Some(idx) if backward => order.get(idx.wrapping_sub(1)).or(order.last()),
+            Some(idx) => order.get(idx + 1).or(order.first()),
+            None if backward => order.last(),
+            None => order.first(),
+        };
+        if let Some(next) = next {
+            next.focus().unwrap();
+            // Text of inputs is selected when they are focused from the keyboard
+            if let Some(field @ TextField::Input(_)) = TextField::new(next) {
+                field.select_all();
+            }
+        }
+    }
+    dispatch_keyboard(&focused(), "keyup", "Tab", modifiers);
+    crate::tick().await;
+}
+
+/// Moves the focus to the next element in the tab order, the way pressing Tab does,
+/// like [`userEvent.tab`].
+///
+/// Browsers don't move the focus on synthetic key events, so the tab order is computed:
+/// focusable elements which aren't disabled, hidden or [`inert`], without a negative `tabindex`.
+/// Elements with a positive `tabindex` come first, in its ascending order, followed by the rest
+/// in the document order. Of a group of radios, only the checked one, or the first one if none
+/// is checked, is reachable. After the last element the focus wraps around to the first one.
+/// If the focused element isn't in the tab order, eg. nothing is focused, the first element is focused.
+///
+/// Dispatches `keydown` of Tab on the focused element and, unless it is cancelled, moves the focus,
+/// which makes the browser dispatch `blur`, `focusout`, `focus` and `focusin`. The value of a focused
+/// text input is selected. Then `keyup` is dispatched on the newly focused element. Like [`click`],
+/// it awaits a [`tick`] at the end.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::interaction::{shift_tab, tab, TabOptions};
+/// use frontest::prelude::*;
+///
+/// # async fn fill_form() {
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<input aria-label="Name" />
+///     <input aria-label="Email" />"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// tab(TabOptions::default()).await;
+/// assert_eq!(document().active_element(), div.get(&HasLabel("Name")).map(Into::into));
+/// tab(TabOptions::default()).await;
+/// assert_eq!(document().active_element(), div.get(&HasLabel("Email")).map(Into::into));
+/// shift_tab(TabOptions::default()).await;
+/// assert_eq!(document().active_element(), div.get(&HasLabel("Name")).map(Into::into));
+///
+/// body().remove_child(&div).unwrap();
+/// # }
+/// ```
+///
+/// [`userEvent.tab`]: https://testing-library.com/docs/user-event/convenience#tab
+/// [`inert`]: https://developer.mozilla.org/en-US/docs/Web/HTML/Global_attributes/inert
+/// [`tick`]: crate::tick
+pub async fn tab(options: TabOptions) {
+    move_focus(options, false).await;
+}
+
+/// Moves the focus to the previous element in the tab order, the way pressing Shift+Tab does.
+///
+/// Works like [`tab`] in the opposite direction. Before the first element the focus wraps around
+/// to the last one.
+pub async fn shift_tab(options: TabOptions) {
+    move_focus(options, true).await;
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_tab() {
+    use crate::query::{HasLabel, Query};
+    use gloo::utils::body;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<input aria-label="Name" />
+        <input aria-label="Email" />"#,
+    );
+    body().append_child(&div).unwrap();
+
+    tab(TabOptions::default()).await;
+    assert_eq!(
+        document().active_element(),
+        div.get(&HasLabel("Name")).map(Into::into)
+    );
+    tab(TabOptions::default()).await;
+    assert_eq!(
+        document().active_element(),
+        div.get(&HasLabel("Email")).map(Into::into)
+    );
+    shift_tab(TabOptions::default()).await;
+    assert_eq!(
+        document().active_element(),
+        div.get(&HasLabel("Name")).map(Into::into)
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn tab_walks_form_in_tab_order() {
+    use crate::query::{HasLabel, HasText, Query};
+    use gloo::events::{EventListener, EventListenerOptions};
+    use gloo::utils::body;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<form>
+            <input aria-label="Name" value="Alice" />
+            <input aria-label="Email" tabindex="1" />
+            <button type="button" disabled>Skip</button>
+            <input aria-label="Secret" style="display: none;" />
+            <label><input type="radio" name="plan" /> Free</label>
+            <label><input type="radio" name="plan" checked /> Pro</label>
+            <span tabindex="-1">Not tabbable</span>
+            <a href="/terms">Terms</a>
+            <button>Send</button>
+        </form>"#,
+    );
+    body().append_child(&div).unwrap();
+    let within = TabOptions {
+        within: Some(div.clone()),
+    };
+
+    let events = Rc::new(RefCell::new(vec![]));
+    let _listeners = ["focusin", "keyup"].map(|type_| {
+        let events = events.clone();
+        EventListener::new(&div, type_, move |event| {
+            let target = event.target().unwrap().unchecked_into::<HtmlElement>();
+            let name = target
+                .get_attribute("aria-label")
+                .unwrap_or_else(|| target.text_content().unwrap().trim().to_string());
+            events
+                .borrow_mut()
+                .push(format!("{} {}", event.type_(), name));
+        })
+    });
+
+    let mut sequence = vec![];
+    for _ in 0..6 {
+        tab(within.clone()).await;
+        sequence.push(focused());
+    }
+    let names = sequence
+        .iter()
+        .map(|elem| {
+            elem.get_attribute("aria-label")
+                .unwrap_or_else(|| elem.inner_text())
+        })
+        .collect::<Vec<_>>();
+    // Only the checked radio of the group is reachable
+    assert_eq!(names, ["Email", "Name", "", "Terms", "Send", "Email"]);
+    assert_eq!(sequence[2], div.get(&HasLabel("Pro")).unwrap());
+    assert_eq!(
+        events.borrow()[..4],
+        ["focusin Email", "keyup Email", "focusin Name", "keyup Name"]
+    );
+
+    shift_tab(within.clone()).await;
+    assert_eq!(focused(), div.get(&HasText("Send")).unwrap());
+    shift_tab(within.clone()).await;
+    assert_eq!(focused(), div.get(&HasText("Terms")).unwrap());
+
+    // The value of a text input is selected when it is focused with Tab
+    let name = div
+        .get_as::<HtmlInputElement, _>(&HasLabel("Name"))
+        .unwrap();
+    name.focus().unwrap();
+    shift_tab(within.clone()).await;
+    tab(within.clone()).await;
+    assert_eq!(focused(), *name);
+    assert_eq!(
+        (
+            name.selection_start().unwrap(),
+            name.selection_end().unwrap()
+        ),
+        (Some(0), Some(5))
+    );
+
+    // Cancelling the keydown keeps the focus
+    let options = EventListenerOptions::enable_prevent_default();
+    let _trap =
+        EventListener::new_with_options(&name, "keydown", options, |event| event.prevent_default());
+    tab(within).await;
+    assert_eq!(focused(), *name);
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Returns the text selected inside of an element, which isn't a form control.
+fn selected_text(elem: &HtmlElement) -> String {
+    let range = editable_range(elem);
+    String::from(range.unchecked_ref::<js_sys::Object>().to_string())
+}
+
+/// Data of the clipboard, pasted with [`paste`] or returned by [`copy`] and [`cut`].
+///
+/// It is carried by the [`DataTransfer`] of the dispatched [`ClipboardEvent`].
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct DataTransferLike {
+    /// The `text/plain` data.
+    pub text: String,
+    /// The `text/html` data, if there is any.
+    pub html: Option<String>,
+}
+
+impl DataTransferLike {
+    /// Creates data with given plain text only.
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            html: None,
+        }
+    }
+
+    /// Adds the `text/html` representation of the data, eg. as copied from a web page.
+    pub fn html(mut self, html: impl Into<String>) -> Self {
+        self.html = Some(html.into());
+        self
+    }
+
+    fn to_data_transfer(&self) -> DataTransfer {
+        let transfer = DataTransfer::new().unwrap();
+        transfer.set_data("text/plain", &self.text).unwrap();
+        if let Some(html) = &self.html {
+            transfer.set_data("text/html", html).unwrap();
+        }
+        transfer
+    }
+
+    fn from_data_transfer(transfer: &DataTransfer) -> Self {
+        Self {
+            text: transfer.get_data("text/plain").unwrap_or_default(),
+            html: transfer
+                .get_data("text/html")
+                .ok()
+                .filter(|html| !html.is_empty()),
+        }
+    }
+}
+
+/// Dispatches a clipboard event carrying `transfer`.
+///
+/// Returns `false` if the event was cancelled.
+fn dispatch_clipboard(elem: &HtmlElement, type_: &str, transfer: &DataTransfer) -> bool {
+    let init = ClipboardEventInit::new();
+    init.set_bubbles(true);
+    init.set_cancelable(true);
+    init.set_composed(true);
+    init.set_clipboard_data(Some(transfer));
+    let event = ClipboardEvent::new_with_event_init_dict(type_, &init).unwrap();
+    elem.dispatch_event(&event).unwrap()
+}
+
+/// Pastes data into an element the way a user would, like [`userEvent.paste`].
+///
+/// Focuses the element, placing the caret at the end if it wasn't focused before, and dispatches
+/// `paste` with the data in its [`clipboardData`], so handlers can read and transform it.
+/// Unless the event is cancelled, the plain text replaces the selected text of an `<input>`,
+/// `<textarea>` or `contenteditable` element, dispatching `beforeinput` and `input` with `inputType`
+/// of `"insertFromPaste"`. Line breaks are removed when pasting into an `<input>`, which can't
+/// contain them. Disabled elements get no events. Like [`click`], it awaits a [`tick`] at the end.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::interaction::{paste, DataTransferLike};
+/// use frontest::prelude::*;
+/// use web_sys::HtmlInputElement;
+///
+/// # async fn paste_name() {
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<input type="text" aria-label="Name" value="Dr. " />"#);
+/// body().append_child(&div).unwrap();
+///
+/// let name = div.get_as::<HtmlInputElement, _>(&HasLabel("Name")).unwrap();
+/// paste(&name, &DataTransferLike::new("Alice")).await;
+/// assert_eq!(name.value(), "Dr. Alice");
+///
+/// body().remove_child(&div).unwrap();
+/// # }
+/// ```
+///
+/// [`userEvent.paste`]: https://testing-library.com/docs/user-event/clipboard#paste
+/// [`clipboardData`]: https://developer.mozilla.org/en-US/docs/Web/API/ClipboardEvent/clipboardData
+/// [`tick`]: crate::tick
+pub async fn paste(elem: &HtmlElement, data: &DataTransferLike) {
+    if elem.matches(":disabled").unwrap() {
+        return;
+    }
+    let field = focus_for_typing(elem);
+    if dispatch_clipboard(elem, "paste", &data.to_data_transfer()) {
+        if let Some(field) = &field {
+            let text = match field {
+                TextField::Input(_) => data.text.replace(['\r', '\n'], ""),
+                _ => data.text.clone(),
+            };
+            dispatch_input(field, elem, "insertFromPaste", Some(&text));
+        }
+    }
+    crate::tick().await;
+}
+
+/// Dispatches `copy` or `cut` and returns what got into the clipboard.
+fn copy_selection(elem: &HtmlElement, type_: &str) -> (DataTransferLike, bool) {
+    let field = TextField::new(elem);
+    let selected = match &field {
+        Some(field) => field.selected_text(),
+        None => selected_text(elem),
+    };
+    let transfer = DataTransfer::new().unwrap();
+    if !dispatch_clipboard(elem, type_, &transfer) {
+        return (DataTransferLike::from_data_transfer(&transfer), false);
+    }
+    (DataTransferLike::new(selected), true)
+}
+
+/// Copies the selected text of an element the way a user would, like [`userEvent.copy`].
+///
+/// Dispatches `copy` and returns the selected text of the element: the selection of an `<input>`
+/// or `<textarea>`, or the part of the document selection inside of other elements. If a handler
+/// cancels the event, the data it set with [`setData`] is returned instead, as it is what would
+/// get into the clipboard. The system clipboard is never accessed. Like [`click`], it awaits
+/// a [`tick`] at the end.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::interaction::copy;
+/// use frontest::prelude::*;
+/// use web_sys::HtmlInputElement;
+///
+/// # async fn copy_word() {
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<input type="text" value="Hello world" />"#);
+/// body().append_child(&div).unwrap();
+///
+/// let input = div.get_as::<HtmlInputElement, _>(&HasRole("textbox")).unwrap();
+/// input.set_selection_range(6, 11).unwrap();
+/// assert_eq!(copy(&input).await.text, "world");
+///
+/// body().remove_child(&div).unwrap();
+/// # }
+/// ```
+///
+/// [`userEvent.copy`]: https://testing-library.com/docs/user-event/clipboard#copy
+/// [`setData`]: https://developer.mozilla.org/en-US/docs/Web/API/DataTransfer/setData
+/// [`tick`]: crate::tick
+pub async fn copy(elem: &HtmlElement) -> DataTransferLike {
+    let (data, _) = copy_selection(elem, "copy");
+    crate::tick().await;
+    data
+}
+
+/// Cuts the selected text of an element the way a user would, like [`userEvent.cut`].
+///
+/// Works like [`copy`], dispatching `cut` instead. Unless the event is cancelled, the selected text
+/// of a writable text field is then deleted, dispatching `beforeinput` and `input` with `inputType`
+/// of `"deleteByCut"`.
+///
+/// [`userEvent.cut`]: https://testing-library.com/docs/user-event/clipboard#cut
+pub async fn cut(elem: &HtmlElement) -> DataTransferLike {
+    let (data, cut) = copy_selection(elem, "cut");
+    if let Some(field) = TextField::new(elem).filter(|_| cut && !data.text.is_empty()) {
+        dispatch_input(&field, elem, "deleteByCut", None);
+    }
+    crate::tick().await;
+    data
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_paste() {
+    use crate::query::{HasLabel, Query};
+    use gloo::utils::body;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<input type="text" aria-label="Name" value="Dr. " />"#);
+    body().append_child(&div).unwrap();
+
+    let name = div
+        .get_as::<HtmlInputElement, _>(&HasLabel("Name"))
+        .unwrap();
+    paste(&name, &DataTransferLike::new("Alice")).await;
+    assert_eq!(name.value(), "Dr. Alice");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_copy() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::body;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<input type="text" value="Hello world" />"#);
+    body().append_child(&div).unwrap();
+
+    let input = div
+        .get_as::<HtmlInputElement, _>(&HasRole("textbox"))
+        .unwrap();
+    input.set_selection_range(6, 11).unwrap();
+    assert_eq!(copy(&input).await.text, "world");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn clipboard_default_actions_and_handlers() {
+    use crate::query::{HasLabel, HasText, Query};
+    use gloo::events::{EventListener, EventListenerOptions};
+    use gloo::utils::body;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<label>Title <input type="text" value="Hello world" /></label>
+        <label>Notes <textarea maxlength="12">Hi</textarea></label>
+        <label>Code <input type="text" disabled /></label>
+        <p>Read <b>only</b> text</p>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let title = div
+        .get_as::<HtmlInputElement, _>(&HasLabel("Title"))
+        .unwrap();
+    let inputs = Rc::new(RefCell::new(vec![]));
+    let _listener = EventListener::new(&div, "input", {
+        let inputs = inputs.clone();
+        move |event| {
+            let event = event.dyn_ref::<InputEvent>().unwrap();
+            inputs.borrow_mut().push(event.input_type());
+        }
+    });
+
+    title.set_selection_range(0, 5).unwrap();
+    assert_eq!(cut(&title).await, DataTransferLike::new("Hello"));
+    assert_eq!(title.value(), " world");
+    // Pasting replaces the selection, without line breaks in an input
+    title.focus().unwrap();
+    title.set_selection_range(0, 0).unwrap();
+    paste(&title, &DataTransferLike::new("Big\nwide")).await;
+    assert_eq!(title.value(), "Bigwide world");
+    assert_eq!(*inputs.borrow(), ["deleteByCut", "insertFromPaste"]);
+
+    let notes = div
+        .get_as::<HtmlTextAreaElement, _>(&HasLabel("Notes"))
+        .unwrap();
+    paste(&notes, &DataTransferLike::new("\nthere\nfriend")).await;
+    assert_eq!(notes.value(), "Hi\nthere\nfri");
+
+    let code = div
+        .get_as::<HtmlInputElement, _>(&HasLabel("Code"))
+        .unwrap();
+    paste(&code, &DataTransferLike::new("42")).await;
+    assert_eq!(code.value(), "");
+
+    // A handler decides what is copied
+    let text = div.get(&HasText("Read")).unwrap();
+    let range = document().create_range().unwrap();
+    range.select_node_contents(&text).unwrap();
+    select_range(&range);
+    assert_eq!(copy(&text).await.text, "Read only text");
+    let options = EventListenerOptions::enable_prevent_default();
+    let _copy = EventListener::new_with_options(&text, "copy", options, |event| {
+        let event = event.dyn_ref::<ClipboardEvent>().unwrap();
+        let data = event.clipboard_data().unwrap();
+        data.set_data("text/plain", "READ").unwrap();
+        data.set_data("text/html", "<b>READ</b>").unwrap();
+        event.prevent_default();
+    });
+    assert_eq!(
+        copy(&text).await,
+        DataTransferLike::new("READ").html("<b>READ</b>")
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(all(test, feature = "yew"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn paste_handler_transforms_pasted_text() {
+    use crate::query::{HasLabel, HasRole, Query};
+    use crate::yew::render;
+    use yew::prelude::*;
+
+    #[function_component(Tags)]
+    fn tags() -> Html {
+        let tags = use_state(Vec::<String>::new);
+        let onpaste = {
+            let tags = tags.clone();
+            Callback::from(move |event: Event| {
+                let event = event.dyn_into::<ClipboardEvent>().unwrap();
+                event.prevent_default();
+                let text = event
+                    .clipboard_data()
+                    .unwrap()
+                    .get_data("text/plain")
+                    .unwrap();
+                let mut pasted = (*tags).clone();
+                pasted.extend(
+                    text.split([',', '\n'])
+                        .map(str::trim)
+                        .filter(|tag| !tag.is_empty())
+                        .map(str::to_lowercase),
+                );
+                tags.set(pasted);
+            })
+        };
+        html! {
+            <>
+                <input type="text" aria-label="Tags" {onpaste} />
+                <ul>
+                    { for tags.iter().map(|tag| html! { <li>{ tag }</li> }) }
+                </ul>
+            </>
+        }
+    }
+
+    let mount = render(html! { <Tags /> }).await;
+    let input = mount
+        .get_as::<HtmlInputElement, _>(&HasLabel("Tags"))
+        .unwrap();
+
+    paste(
+        &input,
+        &DataTransferLike::new("Rust, WASM\nTesting").html("<p>Rust, WASM</p><p>Testing</p>"),
+    )
+    .await;
+    let tags = mount
+        .get_all(&HasRole("listitem"))
+        .iter()
+        .map(|tag| tag.inner_text())
+        .collect::<Vec<_>>();
+    assert_eq!(tags, ["rust", "wasm", "testing"]);
+    // The handler cancelled the paste, so nothing was inserted
+    assert_eq!(input.value(), "");
+    assert_eq!(document().active_element(), Some(input.into()));
+
+    crate::cleanup();
+}
+
+/// A file to [`upload`] or [`drop_files`], turned into a [`File`] for the page.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct TestFile {
+    /// The name of the file, eg. `"avatar.png"`.
+    pub name: String,
+    /// The MIME type of the file, eg. `"image/png"`.
+    pub mime: String,
+    /// The content of the file.
+    pub bytes: Vec<u8>,
+}
+
+impl TestFile {
+    /// Creates a file with given name, MIME type and content.
+    pub fn new(
+        name: impl Into<String>,
+        mime: impl Into<String>,
+        bytes: impl Into<Vec<u8>>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            mime: mime.into(),
+            bytes: bytes.into(),
+        }
+    }
+
+    fn to_file(&self) -> File {
+        let bytes = js_sys::Array::of1(&js_sys::Uint8Array::from(self.bytes.as_slice()));
+        let options = FilePropertyBag::new();
+        options.set_type(&self.mime);
+        File::new_with_u8_array_sequence_and_options(&bytes, &self.name, &options).unwrap()
+    }
+
+    /// Returns whether the file satisfies the `accept` attribute of a file input: one of its
+    /// extensions, like `.png`, or MIME types, like `image/png` or `image/*`.
+    fn is_accepted(&self, accept: &str) -> bool {
+        let name = self.name.to_ascii_lowercase();
+        let mime = self.mime.to_ascii_lowercase();
+        let mut tokens = accept
+            .split(',')
+            .map(|token| token.trim().to_ascii_lowercase())
+            .filter(|token| !token.is_empty())
+            .peekable();
+        tokens.peek().is_none()
+            || tokens.any(|token| match token.strip_suffix("/*") {
+                _ if token.starts_with('.') => name.ends_with(&token),
+                Some(kind) => mime.split('/').next() == Some(kind),
+                None => token == mime,
+            })
+    }
+}
+
+/// Returns a [`DataTransfer`] carrying the files.
+fn transfer_files<'a>(files: impl IntoIterator<Item = &'a TestFile>) -> DataTransfer {
+    let transfer = DataTransfer::new().unwrap();
+    for file in files {
+        transfer.items().add_with_file(&file.to_file()).unwrap();
+    }
+    transfer
+}
+
+/// Options of [`upload_with`].
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct UploadOptions {
+    /// Upload also files not satisfying the `accept` attribute of the input.
+    ///
+    /// By default they are skipped, as a user can't pick them in the file chooser either.
+    pub ignore_accept: bool,
+}
+
+/// Uploads files with an `<input type="file">` the way a user picking them would,
+/// like [`userEvent.upload`].
+///
+/// Scripts can't set the value of a file input, so the files are converted into [`File`]s and
+/// assigned to its [`files`] through a [`DataTransfer`]. The input is focused and gets `input`
+/// and `change` events, so handlers can read the files. Files which don't satisfy the `accept`
+/// attribute are skipped, unless [`UploadOptions::ignore_accept`] is set with [`upload_with`].
+/// Then, for an input without the `multiple` attribute, only the first remaining file is picked.
+/// If no file is left, nothing happens, as if the user cancelled the file chooser. Like [`click`],
+/// it awaits a [`tick`] at the end.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::interaction::{upload, TestFile};
+/// use frontest::prelude::*;
+///
+/// # async fn upload_avatar() {
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<label>Avatar <input type="file" accept="image/*" /></label>"#);
+/// body().append_child(&div).unwrap();
+///
+/// let avatar = div.get_input(&HasLabel("Avatar")).unwrap();
+/// upload(&avatar, &[TestFile::new("me.png", "image/png", [137, 80, 78, 71])])
+///     .await
+///     .unwrap();
+/// assert_eq!(avatar.files().unwrap().get(0).unwrap().name(), "me.png");
+///
+/// body().remove_child(&div).unwrap();
+/// # }
+/// ```
+///
+/// # Errors:
+/// If the element isn't a file input or it is disabled.
+///
+/// [`userEvent.upload`]: https://testing-library.com/docs/user-event/utility#upload
+/// [`files`]: https://developer.mozilla.org/en-US/docs/Web/API/HTMLInputElement/files
+/// [`tick`]: crate::tick
+pub async fn upload(input: &HtmlElement, files: &[TestFile]) -> Result<(), InteractionError> {
+    upload_with(input, files, UploadOptions::default()).await
+}
+
+/// Same as [`upload`], with the given [`UploadOptions`].
+///
+/// # Errors:
+/// In the same cases as [`upload`].
+pub async fn upload_with(
+    input: &HtmlElement,
+    files: &[TestFile],
+    options: UploadOptions,
+) -> Result<(), InteractionError> {
+    let Some(file_input) = input
+        .dyn_ref::<HtmlInputElement>()
+        .filter(|file_input| file_input.type_() == "file")
+    else {
+        return Err(InteractionError::NotFileInput(input.clone()));
+    };
+    if file_input.disabled() {
+        return Err(InteractionError::Disabled(input.clone()));
+    }
+    let accept = file_input.accept();
+    let mut accepted = files
+        .iter()
+        .filter(|file| options.ignore_accept || file.is_accepted(&accept))
+        .collect::<Vec<_>>();
+    if !file_input.multiple() {
+        accepted.truncate(1);
+    }
+    if !accepted.is_empty() {
+        input.focus().unwrap();
+        file_input.set_files(transfer_files(accepted).files().as_ref());
+        dispatch_change(input);
+    }
+    crate::tick().await;
+    Ok(())
+}
+
+/// Dispatches a drag event carrying `transfer` in the center of an element.
+///
+/// Returns `false` if the event was cancelled.
+fn dispatch_drag(elem: &HtmlElement, type_: &str, transfer: &DataTransfer) -> bool {
+    let (x, y) = center(elem);
+    let init = DragEventInit::new();
+    init.set_bubbles(true);
+    init.set_cancelable(type_ != "dragleave");
+    init.set_composed(true);
+    init.set_client_x(x);
+    init.set_client_y(y);
+    init.set_data_transfer(Some(transfer));
+    let event = DragEvent::new_with_event_init_dict(type_, &init).unwrap();
+    elem.dispatch_event(&event).unwrap()
+}
+
+/// Drops files onto an element, like dragging them from the system file manager does.
+///
+/// Dispatches `dragenter` and `dragover` with the files in the [`dataTransfer`]. As in the browser,
+/// the files are dropped only if the element accepts them by cancelling `dragover`, then `drop`
+/// is dispatched. Otherwise the drag ends with `dragleave`. Like [`click`], it awaits a [`tick`]
+/// at the end.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// # use gloo::events::{EventListener, EventListenerOptions};
+/// use frontest::interaction::{drop_files, TestFile};
+/// use frontest::prelude::*;
+///
+/// # async fn drop_report() {
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<div data-testid="dropzone">Drop files here</div>"#);
+/// body().append_child(&div).unwrap();
+///
+/// let dropzone = div.get(&HasTestId("dropzone")).unwrap();
+/// let options = EventListenerOptions::enable_prevent_default();
+/// let _accept = EventListener::new_with_options(&dropzone, "dragover", options, |event| {
+///     event.prevent_default()
+/// });
+/// drop_files(&dropzone, &[TestFile::new("report.txt", "text/plain", "Done")]).await;
+///
+/// body().remove_child(&div).unwrap();
+/// # }
+/// ```
+///
+/// [`dataTransfer`]: https://developer.mozilla.org/en-US/docs/Web/API/DragEvent/dataTransfer
+/// [`tick`]: crate::tick
+pub async fn drop_files(target: &HtmlElement, files: &[TestFile]) {
+    let transfer = transfer_files(files);
+    dispatch_drag(target, "dragenter", &transfer);
+    if dispatch_drag(target, "dragover", &transfer) {
+        dispatch_drag(target, "dragleave", &transfer);
+    } else {
+        dispatch_drag(target, "drop", &transfer);
+    }
+    crate::tick().await;
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_upload() {
+    use crate::query::{HasLabel, Query};
+    use gloo::utils::body;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<label>Avatar <input type="file" accept="image/*" /></label>"#);
+    body().append_child(&div).unwrap();
+
+    let avatar = div.get_input(&HasLabel("Avatar")).unwrap();
+    upload(
+        &avatar,
+        &[TestFile::new("me.png", "image/png", [137, 80, 78, 71])],
+    )
+    .await
+    .unwrap();
+    assert_eq!(avatar.files().unwrap().get(0).unwrap().name(), "me.png");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn upload_respects_accept_and_multiple() {
+    use crate::query::{HasLabel, HasText, Query};
+    use gloo::utils::body;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<label>Photos <input type="file" accept=".JPG, image/png" multiple /></label>
+        <label>Resume <input type="file" accept="application/pdf" /></label>
+        <label>Backup <input type="file" disabled /></label>
+        <p>Static</p>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let photos = div.get_input(&HasLabel("Photos")).unwrap();
+    let files = [
+        TestFile::new("beach.jpg", "image/jpeg", "jpg"),
+        TestFile::new("notes.txt", "text/plain", "txt"),
+        TestFile::new("map.png", "image/png", "png"),
+    ];
+    upload(&photos, &files).await.unwrap();
+    let names = |input: &HtmlInputElement| {
+        let files = input.files().unwrap();
+        (0..files.length())
+            .map(|idx| files.get(idx).unwrap().name())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(names(&photos), ["beach.jpg", "map.png"]);
+    let options = UploadOptions {
+        ignore_accept: true,
+    };
+    upload_with(&photos, &files, options).await.unwrap();
+    assert_eq!(names(&photos), ["beach.jpg", "notes.txt", "map.png"]);
+
+    let resume = div.get_input(&HasLabel("Resume")).unwrap();
+    // Nothing can be picked, so the input stays empty
+    upload(&resume, &files[..1]).await.unwrap();
+    assert_eq!(resume.value(), "");
+    // Only the first accepted file is picked without `multiple`
+    let documents = [
+        TestFile::new("notes.txt", "text/plain", "txt"),
+        TestFile::new("cv.pdf", "application/pdf", "pdf"),
+        TestFile::new("old.pdf", "application/pdf", "pdf"),
+    ];
+    upload(&resume, &documents).await.unwrap();
+    assert_eq!(names(&resume), ["cv.pdf"]);
+
+    let backup = div.get(&HasLabel("Backup")).unwrap();
+    assert_eq!(
+        upload(&backup, &files[..1]).await,
+        Err(InteractionError::Disabled(backup))
+    );
+    let text = div.get(&HasText("Static")).unwrap();
+    let err = upload(&text, &files[..1]).await.unwrap_err();
+    assert_eq!(err, InteractionError::NotFileInput(text));
+    assert!(err.to_string().ends_with("is not a file input"));
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(all(test, feature = "yew"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn uploaded_files_reach_handlers() {
+    use crate::query::{HasLabel, HasTestId, HasText, Query};
+    use crate::yew::render;
+    use web_sys::FileList;
+    use yew::prelude::*;
+
+    fn describe_files(files: Option<FileList>) -> Vec<String> {
+        let files = files.unwrap();
+        (0..files.length())
+            .filter_map(|idx| files.get(idx))
+            .map(|file| format!("{} ({} bytes)", file.name(), file.size()))
+            .collect()
+    }
+
+    #[function_component(Uploader)]
+    fn uploader() -> Html {
+        let uploaded = use_state(Vec::<String>::new);
+        let onchange = {
+            let uploaded = uploaded.clone();
+            Callback::from(move |event: Event| {
+                let input = event.target_unchecked_into::<HtmlInputElement>();
+                uploaded.set(describe_files(input.files()));
+            })
+        };
+        let ondragover = Callback::from(|event: DragEvent| event.prevent_default());
+        let ondrop = {
+            let uploaded = uploaded.clone();
+            Callback::from(move |event: DragEvent| {
+                event.prevent_default();
+                uploaded.set(describe_files(event.data_transfer().unwrap().files()));
+            })
+        };
+        html! {
+            <>
+                <label>{ "Attachments" }<input type="file" multiple=true {onchange} /></label>
+                <div data-testid="dropzone" {ondragover} {ondrop}>{ "Drop files here" }</div>
+                <ul>
+                    { for uploaded.iter().map(|file| html! { <li>{ file }</li> }) }
+                </ul>
+            </>
+        }
+    }
+
+    let mount = render(html! { <Uploader /> }).await;
+    let input = mount.get(&HasLabel("Attachments")).unwrap();
+
+    upload(
+        &input,
+        &[
+            TestFile::new("a.txt", "text/plain", "Hello"),
+            TestFile::new("b.csv", "text/csv", "1,2,3\n"),
+        ],
+    )
+    .await
+    .unwrap();
+    assert!(mount.get(&HasText("a.txt (5 bytes)")).is_some());
+    assert!(mount.get(&HasText("b.csv (6 bytes)")).is_some());
+
+    let dropzone = mount.get(&HasTestId("dropzone")).unwrap();
+    drop_files(
+        &dropzone,
+        &[TestFile::new("photo.jpg", "image/jpeg", vec![0; 1024])],
+    )
+    .await;
+    assert!(mount.get(&HasText("photo.jpg (1024 bytes)")).is_some());
+    assert!(mount.get(&HasText("a.txt")).is_none());
+
+    crate::cleanup();
+}
@@ -52,6 +52,7 @@
 //! - [`HasText`] Matches the text in a way that it is presented to the user.
 //!   All css rules applies eg. elements with `visibility: hidden;` won't be ever matched.
 //!   Can be used to select non-interactive components or further restrict other queries.
+//!   A bare string is a shorthand for it, eg. `div.get("Save")`, matching the visible text and never labels.
 //!
 //! # Matchers:
 //!
@@ -59,7 +60,12 @@
 //! or [`false`] otherwise.
 //!
 //! Using the matcher [`Not`] and methods from [`Joinable`] trait it is possible to combine multiple matchers into
-//! a logical expression.
+//! a logical expression. Longer lists of matchers can be combined with [`all!`] and [`any!`], and a tuple
+//! of matchers matches elements matched by all of them. The most common ones can be
+//! written as `key = value` pairs with [`get_by!`], eg. `get_by!(mount, role = "button", text = "Save")`.
+//!
+//! Built-in matchers accept both borrowed and owned strings, so they can also be built from strings
+//! created at runtime, eg. `HasText(format!("{} items", count))`, and returned from functions.
 //!
 //! ## You can easily implement your own `Matcher`s.
 //!
@@ -127,7 +133,7 @@
 //!     let button = mount.get(&HasRole("button")).unwrap();
 //!
 //!     assert_eq!("Value: 0", value.inner_text());
-//!     button.click();
+//!     click(&button).await;
 //!     assert_eq!("Value: 1", value.inner_text());
 //!
 //!     body().remove_child(&mount).unwrap();
@@ -135,12 +141,16 @@
 //! # }
 //! ```
 //!
+//! Rendered elements can also be queried with [`screen`], which searches the whole body of the document,
+//! so there is no need to pass the mount-point around, eg. `screen().get(&HasRole("button"))`.
+//!
 //! ## Warning:
 //!
 //! [`wasm-bindgen-test`] runs all tests sequentially and let them manipulate real DOM.
 //! However it doesn't recreate full DOM for each test, so things done in one test may impact others.
 //! Always make sure you are doing a proper cleanup of DOM after your tests eg. remove mounted child element.
-//! Hopefully in future this library will provide some kind of RAII for running tests.
+//! Elements mounted with `frontest` helpers like [`render`] or [`mount_html`] are tracked,
+//! so calling [`cleanup`] at the end of a test removes all of them.
 //!
 //! [`dom-testing-library`]: https://testing-library.com/docs/dom-testing-library/intro
 //! [`react-testing-library`]: https://testing-library.com/docs/react-testing-library/intro
@@ -156,7 +166,7 @@
 //! [`Not`]: query::Not
 //! [`Query`]: query::Query
 //! [`Joinable`]: query::Joinable
-use gloo::timers::future::sleep;
+use std::cell::RefCell;
 use std::time::Duration;
 
 #[cfg(test)]
@@ -164,13 +174,41 @@ wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
 
 /// A convenient imports for testing.
 pub mod prelude {
-    pub use crate::query::{And, Not, Or};
-    pub use crate::query::{HasLabel, HasPlaceholder, HasRole, HasText};
+    pub use crate::assertions::{assert_has_role, assert_single, assert_text_eq};
+    pub use crate::interaction::click;
+    pub use crate::query::{All, And, Any, ExactlyOne, Not, Or, Xor};
+    pub use crate::query::{
+        Controls, HasAriaValueNow, HasDataAttribute, HasLabel, HasLegend, HasPlaceholder, HasRole,
+        HasRoleWith, HasSelectedOption, HasTestId, HasText, HasVisibleName, HasXPath, IsBusy,
+        IsHiddenUntilFound, IsInvalid, IsLiveRegion, IsPresentational, IsValid, MatchesSelector,
+        WithinRole,
+    };
+
+    pub use crate::query::{Joinable, Matcher, Query, QueryExt, QueryOptions};
 
-    pub use crate::query::{Joinable, Matcher, Query};
+    pub use crate::snapshot::snapshot_html;
+    pub use crate::wait::{find, wait_for};
+    pub use crate::{
+        all, any, cleanup, configure, find_by, get_all_by, get_by, mount_html, portal, screen,
+        within,
+    };
 }
+/// Assert properties of already selected elements.
+pub mod assertions;
+/// Investigate why queries don't find what was expected.
+pub mod debug;
+/// Build matchers from textual expressions.
+pub mod dsl;
+/// Simulate user interactions with elements.
+pub mod interaction;
 /// Find various elements across the website as the user would.
 pub mod query;
+/// Serialize DOM into a deterministic form for snapshot testing.
+pub mod snapshot;
+/// Control timers of the page to test time-dependent components without waiting.
+pub mod time;
+/// Wait for elements and conditions that are met asynchronously.
+pub mod wait;
 
 #[cfg(test)]
 #[wasm_bindgen_test::wasm_bindgen_test]
@@ -211,6 +249,7 @@ async fn doctest_basic_usage() {
 #[cfg(feature = "yew")]
 pub mod yew {
     use ::yew::prelude::*;
+    use ::yew::AppHandle;
     use web_sys::Element;
 
     #[derive(Properties, PartialEq)]
@@ -225,6 +264,9 @@ pub mod yew {
 
     /// Render arbitrary output of [`html`] macro, mount it into body and return mount-point [`Element`]
     ///
+    /// The mount-point is removed by [`cleanup`]. Content rendered with [`create_portal`] is outside
+    /// of the mount-point, so query it with [`screen`] or [`portal`] instead.
+    ///
     /// # Example:
     /// ```no_run
     /// # use yew::prelude::*;
@@ -256,7 +298,7 @@ pub mod yew {
     ///     let button = mount.get(&HasRole("button")).unwrap();
     ///
     ///     assert_eq!("Value: 0", value.inner_text());
-    ///     button.click();
+    ///     click(&button).await;
     ///     assert_eq!("Value: 1", value.inner_text());
     ///
     ///     body().remove_child(&mount).unwrap();
@@ -265,14 +307,224 @@ pub mod yew {
     ///
     /// [`html`]: ::yew::html!
     /// [`element`]: web_sys::Element
+    /// [`cleanup`]: crate::cleanup
+    /// [`create_portal`]: ::yew::create_portal
+    /// [`screen`]: crate::screen
+    /// [`portal`]: crate::portal
     pub async fn render(content: Html) -> Element {
-        let div = gloo::utils::document().create_element("div").unwrap();
-        gloo::utils::body().append_child(&div).unwrap();
-        let res = div.clone();
-        ::yew::Renderer::<Wrapper>::with_root_and_props(div, WrapperProps { content }).render();
-        ::yew::platform::time::sleep(std::time::Duration::ZERO).await;
+        let (_, mount) = render_app::<Wrapper>(WrapperProps { content }).await;
+        mount
+    }
 
-        res
+    /// Render a component with given properties, mount it into body and return
+    /// its [`AppHandle`] together with the mount-point [`Element`]
+    ///
+    /// The handle allows re-rendering the component with new properties, eg. to simulate an update
+    /// from a parent component. Like with any other interaction, the new content is rendered
+    /// when the scheduler is yielded, so [`tick`] has to be awaited after [`AppHandle::update`].
+    /// The mount-point is removed by [`cleanup`].
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use yew::prelude::*;
+    /// #[derive(Properties, PartialEq)]
+    /// struct GreetingProps {
+    ///     name: String,
+    /// }
+    ///
+    /// #[function_component(Greeting)]
+    /// fn greeting(props: &GreetingProps) -> Html {
+    ///     html! { <p>{ format!("Hello, {}!", props.name) }</p> }
+    /// }
+    ///
+    /// # use wasm_bindgen_test::wasm_bindgen_test;
+    /// # use gloo::utils::body;
+    /// use frontest::prelude::*;
+    /// use frontest::tick;
+    /// use frontest::yew::render_app;
+    ///
+    /// #[wasm_bindgen_test]
+    /// async fn greeting_follows_props() {
+    ///     let name = String::from("Alice");
+    ///     let (mut app, mount) = render_app::<Greeting>(GreetingProps { name }).await;
+    ///     assert!(mount.get(&HasText("Hello, Alice!")).is_some());
+    ///
+    ///     app.update(GreetingProps { name: String::from("Bob") });
+    ///     tick().await;
+    ///     assert!(mount.get(&HasText("Hello, Bob!")).is_some());
+    ///
+    ///     body().remove_child(&mount).unwrap();
+    /// }
+    /// ```
+    ///
+    /// [`AppHandle`]: ::yew::AppHandle
+    /// [`AppHandle::update`]: ::yew::AppHandle::update
+    /// [`tick`]: crate::tick
+    /// [`cleanup`]: crate::cleanup
+    pub async fn render_app<COMP: BaseComponent>(
+        props: COMP::Properties,
+    ) -> (AppHandle<COMP>, Element) {
+        let div = crate::mount_point();
+        let mount = div.clone();
+        let app = ::yew::Renderer::<COMP>::with_root_and_props(div, props).render();
+        // Rendering is scheduled as a microtask, which doesn't depend on timers of the environment
+        crate::flush().await;
+
+        (app, mount)
+    }
+
+    #[cfg(test)]
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn doctest_render_app() {
+        use crate::query::{HasText, Query};
+        use crate::tick;
+        use gloo::utils::body;
+
+        #[derive(Properties, PartialEq)]
+        struct GreetingProps {
+            name: String,
+        }
+
+        #[function_component(Greeting)]
+        fn greeting(props: &GreetingProps) -> Html {
+            html! { <p>{ format!("Hello, {}!", props.name) }</p> }
+        }
+
+        let name = String::from("Alice");
+        let (mut app, mount) = render_app::<Greeting>(GreetingProps { name }).await;
+        assert!(mount.get(&HasText("Hello, Alice!")).is_some());
+
+        app.update(GreetingProps {
+            name: String::from("Bob"),
+        });
+        tick().await;
+        assert!(mount.get(&HasText("Hello, Alice!")).is_none());
+        assert!(mount.get(&HasText("Hello, Bob!")).is_some());
+
+        body().remove_child(&mount).unwrap();
+    }
+
+    /// Prepares the document with `setup`, then renders the output of [`html`] like [`render`] does.
+    ///
+    /// Useful for components relying on the document around them, like global styles or a container
+    /// for portals. Elements added to the document by `setup`, eg. `<style>` in the `<head>`,
+    /// are removed by [`cleanup`] together with the mount-point.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use yew::prelude::*;
+    /// # use wasm_bindgen_test::wasm_bindgen_test;
+    /// use frontest::prelude::*;
+    /// use frontest::yew::render_with_setup;
+    ///
+    /// #[wasm_bindgen_test]
+    /// async fn warning_is_red() {
+    ///     let setup = |document: &web_sys::Document| {
+    ///         let style = document.create_element("style").unwrap();
+    ///         style.set_text_content(Some(".warning { color: rgb(255, 0, 0); }"));
+    ///         document.head().unwrap().append_child(&style).unwrap();
+    ///     };
+    ///     let mount = render_with_setup(setup, html! { <p class="warning">{ "Unsaved" }</p> }).await;
+    ///
+    ///     let warning = mount.get(&HasText("Unsaved")).unwrap();
+    ///     let style = gloo::utils::window().get_computed_style(&warning).unwrap().unwrap();
+    ///     assert_eq!(style.get_property_value("color").unwrap(), "rgb(255, 0, 0)");
+    ///
+    ///     cleanup();
+    /// }
+    /// ```
+    ///
+    /// [`html`]: ::yew::html!
+    /// [`cleanup`]: crate::cleanup
+    pub async fn render_with_setup(
+        setup: impl FnOnce(&web_sys::Document),
+        content: Html,
+    ) -> Element {
+        crate::track_added(|| setup(&gloo::utils::document()));
+        render(content).await
+    }
+
+    #[cfg(test)]
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn portaled_dialog_is_found_outside_of_mount() {
+        use crate::interaction::click;
+        use crate::query::{HasRole, Query};
+        use crate::{cleanup, portal, screen};
+
+        #[function_component(DeleteButton)]
+        fn delete_button() -> Html {
+            let open = use_state(|| false);
+            let onclick = {
+                let open = open.clone();
+                Callback::from(move |_| open.set(true))
+            };
+            let dialog = html! { <div role="dialog">{ "Are you sure?" }</div> };
+            html! {
+                <>
+                    <button {onclick}>{ "Delete" }</button>
+                    if *open { { create_portal(dialog, gloo::utils::body().into()) } }
+                </>
+            }
+        }
+
+        let setup = |document: &web_sys::Document| {
+            let toasts = document.create_element("div").unwrap();
+            toasts.set_attribute("data-portal", "toasts").unwrap();
+            toasts.set_inner_html(r#"<p role="status">Saved</p>"#);
+            document.body().unwrap().append_child(&toasts).unwrap();
+        };
+        let mount = render_with_setup(setup, html! { <DeleteButton /> }).await;
+        click(&mount.get(&HasRole("button")).unwrap()).await;
+
+        assert!(mount.get(&HasRole("dialog")).is_none());
+        let dialog = screen().get(&HasRole("dialog")).unwrap();
+        assert!(!mount.contains(Some(&dialog)));
+        assert!(portal("toasts").get(&HasRole("status")).is_some());
+        assert!(portal("toasts").get(&HasRole("dialog")).is_none());
+
+        cleanup();
+        dialog.remove();
+    }
+
+    #[cfg(test)]
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn render_with_setup_prepares_and_cleans_up_document() {
+        use crate::query::{HasRole, HasText, Query};
+        use crate::{cleanup, screen};
+        use gloo::utils::{document, window};
+
+        #[function_component(Dialog)]
+        fn dialog() -> Html {
+            let host = document().get_element_by_id("modal-root").unwrap();
+            create_portal(
+                html! { <div role="dialog" class="warning">{ "Unsaved changes" }</div> },
+                host,
+            )
+        }
+
+        let setup = |document: &web_sys::Document| {
+            let style = document.create_element("style").unwrap();
+            style.set_id("warning-style");
+            style.set_text_content(Some(".warning { color: rgb(255, 0, 0); }"));
+            document.head().unwrap().append_child(&style).unwrap();
+            let modal_root = document.create_element("div").unwrap();
+            modal_root.set_id("modal-root");
+            modal_root.set_inner_html("<p>Modals</p>");
+            document.body().unwrap().append_child(&modal_root).unwrap();
+        };
+        let mount = render_with_setup(setup, html! { <Dialog /> }).await;
+
+        // Rendered outside of the mount-point, and styled by the setup
+        assert!(mount.get(&HasRole("dialog")).is_none());
+        let dialog = screen().get(&HasRole("dialog")).unwrap();
+        let style = window().get_computed_style(&dialog).unwrap().unwrap();
+        assert_eq!(style.get_property_value("color").unwrap(), "rgb(255, 0, 0)");
+
+        cleanup();
+        assert!(!mount.is_connected());
+        assert!(document().get_element_by_id("modal-root").is_none());
+        assert!(screen().get(&HasText("Unsaved changes")).is_none());
+        assert!(document().get_element_by_id("warning-style").is_none());
     }
 
     #[cfg(test)]
@@ -294,6 +546,7 @@ pub mod yew {
             }
         }
 
+        use crate::interaction::click;
         use crate::query::{HasRole, HasText, Query};
         use crate::yew::render;
         use ::yew::html;
@@ -307,21 +560,599 @@ pub mod yew {
         let button = mount.get(&HasRole("button")).unwrap();
 
         assert_eq!("Value: 0", value.inner_text());
-        button.click();
-        // Events are handled when the scheduler is yielded. So we need to add this after any interaction with the DOM.
-        ::yew::platform::time::sleep(std::time::Duration::ZERO).await;
+        click(&button).await;
         assert_eq!("Value: 1", value.inner_text());
 
         body().remove_child(&mount).unwrap();
         // }
     }
+
+    #[cfg(test)]
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn render_is_flushed_without_timers() {
+        use crate::query::{HasText, Query};
+        use gloo::utils::body;
+
+        #[function_component(Loaded)]
+        fn loaded() -> Html {
+            let loaded = use_state(|| false);
+            {
+                let loaded = loaded.clone();
+                use_effect_with((), move |_| loaded.set(true));
+            }
+            html! { <p>{ if *loaded { "Loaded" } else { "Loading" } }</p> }
+        }
+
+        // Queried right after rendering, including the re-render triggered by the effect
+        let mount = render(html! { <Loaded /> }).await;
+        assert!(mount.get(&HasText("Loaded")).is_some());
+
+        body().remove_child(&mount).unwrap();
+    }
+
+    #[cfg(test)]
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn screen_queries_mounted_component() {
+        use crate::query::{HasRole, HasText, Joinable, Query};
+        use crate::screen;
+        use gloo::utils::body;
+
+        #[function_component(Greeting)]
+        fn greeting() -> Html {
+            html! {
+                <section>
+                    <h1>{ "Hello from screen" }</h1>
+                    <button>{ "Wave" }</button>
+                </section>
+            }
+        }
+
+        let mount = render(html! { <Greeting /> }).await;
+
+        let heading = screen().get(&HasRole("heading")).unwrap();
+        assert_eq!("Hello from screen", heading.inner_text());
+        assert!(screen()
+            .get(&HasRole("button").and(HasText("Wave")))
+            .is_some());
+
+        body().remove_child(&mount).unwrap();
+    }
+
+    #[cfg(test)]
+    #[wasm_bindgen_test::wasm_bindgen_test]
+    async fn rendered_content_is_reachable_from_document_and_screen() {
+        use crate::query::{HasRole, HasText, Query};
+        use crate::screen;
+        use gloo::utils::{body, document};
+
+        let mount = render(html! { <button>{ "Rendered" }</button> }).await;
+
+        let from_document = document().get(&HasRole("button")).unwrap();
+        let from_screen = screen().get(&HasText("Rendered")).unwrap();
+        assert_eq!(from_document, from_screen);
+
+        body().remove_child(&mount).unwrap();
+    }
+}
+
+/// Returns a [`Query`] over the whole body of the document.
+///
+/// It is a global entry point for queries, so there is no need to keep the mount-point around.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<button>Click me</button>"#);
+/// body().append_child(&div).unwrap();
+///
+/// assert!(screen().get(&HasRole("button")).is_some());
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+///
+/// [`Query`]: query::Query
+pub fn screen() -> query::Screen {
+    query::Screen
+}
+
+/// Returns a [`Query`] scoped to the subtree of a given element.
+///
+/// It accepts anything that can be referenced as an [`Element`], including results of previous queries.
+/// Failure messages of queries performed with it contain the chain of scopes, see [`Within`].
+///
+/// [`Query`]: query::Query
+/// [`Element`]: web_sys::Element
+/// [`Within`]: query::Within
+pub fn within(elem: impl AsRef<web_sys::Element>) -> query::Within {
+    query::Within::new(elem)
+}
+
+/// Returns a [`Query`] scoped to a container of portals, found by its `id` or `data-portal` attribute.
+///
+/// Portals render content, like modals or tooltips, outside of the subtree of the component, usually
+/// into a dedicated container in the body. Such content isn't found by queries on the mount-point,
+/// but it is found by [`screen`], which searches the whole body. When the container is known,
+/// this narrows the query to it, so eg. an element with the same text in the page isn't matched.
+///
+/// # Example:
+/// ```no_run
+/// use frontest::prelude::*;
+///
+/// let page = mount_html(r#"<button>Delete</button>"#);
+/// let modals = mount_html(
+///     r#"<div data-portal="modals">
+///         <div role="dialog">Are you sure? <button>Delete</button></div>
+///     </div>"#,
+/// );
+///
+/// assert!(page.get(&HasRole("dialog")).is_none());
+/// assert!(screen().get(&HasRole("dialog")).is_some());
+/// assert!(portal("modals").get(&HasRole("button")).is_some());
+///
+/// cleanup();
+/// ```
+///
+/// # Panics:
+/// If there is no element with such `id` or `data-portal` attribute in the document.
+///
+/// [`Query`]: query::Query
+#[track_caller]
+pub fn portal(name: &str) -> query::Within {
+    let document = gloo::utils::document();
+    let selector = format!("[data-portal={}]", query::css_string(name));
+    let container = document
+        .get_element_by_id(name)
+        .or_else(|| document.query_selector(&selector).unwrap());
+    match container {
+        Some(container) => within(container),
+        None => panic!(
+            "{}: no portal container with id or data-portal {:?}",
+            std::panic::Location::caller(),
+            name
+        ),
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_portal() {
+    use crate::query::{HasRole, Query};
+
+    let page = mount_html(r#"<button>Delete</button>"#);
+    let _modals = mount_html(
+        r#"<div data-portal="modals">
+            <div role="dialog">Are you sure? <button>Delete</button></div>
+        </div>"#,
+    );
+
+    assert!(page.get(&HasRole("dialog")).is_none());
+    assert!(screen().get(&HasRole("dialog")).is_some());
+    assert!(portal("modals").get(&HasRole("button")).is_some());
+
+    cleanup();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+#[should_panic(expected = "no portal container with id or data-portal \"toasts\"")]
+fn portal_panics_without_container() {
+    portal("toasts");
+}
+
+thread_local! {
+    static MOUNTED: RefCell<Vec<web_sys::Element>> = const { RefCell::new(vec![]) };
+}
+
+/// Creates an empty `<div>`, appends it to the body and tracks it for [`cleanup`].
+fn mount_point() -> web_sys::Element {
+    let div = gloo::utils::document().create_element("div").unwrap();
+    gloo::utils::body().append_child(&div).unwrap();
+    MOUNTED.with(|mounted| mounted.borrow_mut().push(div.clone()));
+    div
+}
+
+/// Runs `f`, tracking for [`cleanup`] all elements it adds to the document.
+///
+/// Only the outermost added elements are tracked, as their descendants are removed with them.
+#[cfg(feature = "yew")]
+fn track_added(f: impl FnOnce()) {
+    use wasm_bindgen::JsCast;
+
+    let root = gloo::utils::document().document_element().unwrap();
+    let callback = wasm_bindgen::closure::Closure::<dyn FnMut()>::new(|| {});
+    let observer = web_sys::MutationObserver::new(callback.as_ref().unchecked_ref()).unwrap();
+    let init = web_sys::MutationObserverInit::new();
+    init.set_child_list(true);
+    init.set_subtree(true);
+    observer.observe_with_options(&root, &init).unwrap();
+    f();
+    let records = observer.take_records();
+    observer.disconnect();
+    let added = records
+        .iter()
+        .map(|record| record.unchecked_into::<web_sys::MutationRecord>())
+        .flat_map(|record| {
+            let nodes = record.added_nodes();
+            (0..nodes.length()).filter_map(move |n| nodes.item(n))
+        })
+        .filter_map(|node| node.dyn_into::<web_sys::Element>().ok())
+        .filter(|elem| elem.is_connected())
+        .collect::<Vec<_>>();
+    let outermost = added.iter().filter(|elem| {
+        !added
+            .iter()
+            .any(|other| other != *elem && other.contains(Some(elem)))
+    });
+    MOUNTED.with(|mounted| mounted.borrow_mut().extend(outermost.cloned()));
+}
+
+/// Mounts given html into body and returns the mount-point.
+///
+/// The html is wrapped with a `<div>`, which is removed by [`cleanup`].
+///
+/// # Example:
+/// ```no_run
+/// use frontest::prelude::*;
+///
+/// let mount = mount_html(r#"<button>Click me</button>"#);
+/// assert!(screen().get(&HasRole("button")).is_some());
+/// assert!(mount.get(&HasRole("button")).is_some());
+///
+/// cleanup();
+/// ```
+pub fn mount_html(html: &str) -> web_sys::Element {
+    let div = mount_point();
+    div.set_inner_html(html);
+    div
+}
+
+/// Removes all elements mounted by this crate, eg. with [`mount_html`] or [`render`],
+/// as well as elements added by the setup of [`render_with_setup`].
+///
+/// It should be called at the end of each test, so the DOM of one test doesn't interfere with others.
+/// Elements appended to the document by other means have to be removed manually.
+///
+/// [`render`]: yew::render
+/// [`render_with_setup`]: yew::render_with_setup
+pub fn cleanup() {
+    for elem in MOUNTED.with(|mounted| mounted.take()) {
+        elem.remove();
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn cleanup_removes_mounted_fixtures() {
+    use crate::query::{HasRole, HasText, Query};
+
+    let first = mount_html(r#"<button>First</button>"#);
+    let second = mount_html(r#"<a href="/second">Second</a>"#);
+    assert!(screen().get(&HasText("First")).is_some());
+    assert!(screen().get(&HasRole("link")).is_some());
+
+    cleanup();
+    assert!(!first.is_connected());
+    assert!(!second.is_connected());
+    assert!(screen().get(&HasText("First")).is_none());
+    assert!(screen().get(&HasRole("link")).is_none());
+    // Cleaning up twice is fine
+    cleanup();
+}
+
+/// Changes the [`QueryConfig`] of all subsequent queries, until the returned guard is dropped.
+///
+/// The configuration is global for the current thread, so keeping the guard alive for the duration
+/// of a test scopes the configuration to that test. Note that in the browser a panicking test
+/// doesn't run destructors, so the configuration isn't restored after a failed test.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let _config = configure(|config| config.panic_on_not_found(true));
+/// // Panics, pointing at this line, with: Found no element matching HasRole("button").
+/// screen().get(&HasRole("button"));
+/// ```
+///
+/// [`QueryConfig`]: query::QueryConfig
+pub fn configure(
+    config: impl FnOnce(query::QueryConfig) -> query::QueryConfig,
+) -> query::ConfigGuard {
+    query::ConfigGuard::set(config(query::QueryConfig::current()))
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+#[should_panic(expected = "src/lib.rs:")]
+fn query_panics_point_at_the_caller() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::document;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button>Ok</button><button>Cancel</button>"#);
+
+    div.get(&HasRole("button"));
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+#[should_panic(expected = "src/lib.rs:")]
+fn expect_count_panics_point_at_the_caller() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::document;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button>Ok</button>"#);
+
+    within(&div).expect_count(&HasRole("button"), 2);
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn configure_applies_until_guard_is_dropped() {
+    use crate::query::{HasRole, Query, QueryConfig};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button>Ok</button><button>Cancel</button>"#);
+    body().append_child(&div).unwrap();
+
+    let config = configure(|config| config.panic_on_ambiguous(false));
+    assert!(screen().get(&HasRole("button")).is_none());
+    assert!(within(&div).get(&HasRole("button")).is_none());
+    {
+        let _nested = configure(|config| config.panic_on_not_found(true));
+        assert_eq!(
+            QueryConfig::current(),
+            QueryConfig::default()
+                .panic_on_ambiguous(false)
+                .panic_on_not_found(true)
+        );
+    }
+    assert_eq!(
+        QueryConfig::current(),
+        QueryConfig::default().panic_on_ambiguous(false)
+    );
+    drop(config);
+    assert_eq!(QueryConfig::current(), QueryConfig::default());
+
+    body().remove_child(&div).unwrap();
 }
 
 /// Preempt execution of current task to let the js's main thread do things like re-render.
 ///
-/// # Warning:
-/// I'm currently unsure in which condition tick is required. Most cases should work without the need of using it.
-#[doc(hidden)]
+/// It awaits a single zero timeout, so everything scheduled before it, like event handlers
+/// or re-renders of [`yew`] components, is run before the test continues.
+///
+/// [`yew`]: ::yew
 pub async fn tick() {
-    sleep(Duration::ZERO).await;
+    time::real_sleep(Duration::ZERO).await;
+}
+
+/// Preempt execution of current task `count` times in a row, see [`tick`].
+///
+/// Some updates need more than one cycle of the scheduler to settle, eg. when an effect changes a state,
+/// which triggers another render. Prefer it when the number of cycles is known and stable, as it is
+/// deterministic and fast. When it depends on things like timers or network, wait for the expected
+/// state of the DOM instead.
+///
+/// # Example:
+/// ```no_run
+/// use frontest::prelude::*;
+/// use frontest::tick_n;
+/// use gloo::timers::callback::Timeout;
+///
+/// # async fn settle() {
+/// let mount = mount_html("<p>Loading</p>");
+/// let p = mount.get(&HasText("Loading")).unwrap();
+/// Timeout::new(0, move || {
+///     p.set_inner_text("Parsing");
+///     Timeout::new(0, move || p.set_inner_text("Done")).forget();
+/// })
+/// .forget();
+///
+/// tick_n(2).await;
+/// assert!(mount.get(&HasText("Done")).is_some());
+///
+/// cleanup();
+/// # }
+/// ```
+pub async fn tick_n(count: usize) {
+    for _ in 0..count {
+        tick().await;
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn tick_n_settles_chained_updates() {
+    use crate::query::{HasText, Query};
+    use gloo::timers::callback::Timeout;
+
+    let chain = |p: web_sys::HtmlElement| {
+        Timeout::new(0, move || {
+            p.set_inner_text("Parsing");
+            Timeout::new(0, move || p.set_inner_text("Done")).forget();
+        })
+        .forget();
+    };
+
+    let mount = mount_html("<p>Loading</p>");
+    chain(mount.get(&HasText("Loading")).unwrap());
+    tick().await;
+    assert!(mount.get(&HasText("Parsing")).is_some());
+    tick().await;
+    assert!(mount.get(&HasText("Done")).is_some());
+
+    let mount = mount_html("<p>Loading</p>");
+    chain(mount.get(&HasText("Loading")).unwrap());
+    tick_n(2).await;
+    assert!(mount.get(&HasText("Done")).is_some());
+
+    cleanup();
+}
+
+/// Limit of iterations of [`run_until_stalled`], so a page which never stalls doesn't hang the test.
+const MAX_STALL_ITERATIONS: usize = 100;
+
+/// Preempt execution of current task until the page stalls, ie. it has no more work scheduled right away.
+///
+/// Each iteration yields to the event loop with a sentinel timeout, which runs after all timeouts
+/// scheduled before it, and with the `yew` feature, to the scheduler of [`yew`] as well. Iterations
+/// are repeated until one passes without the page scheduling any new timeout without a delay.
+/// Prefer it over [`tick`] and [`tick_n`] when one update schedules another, eg. an effect changing
+/// a state which triggers another render, and the number of cycles is unknown or fragile.
+/// Updates waiting for something that takes time, like timers with a delay or network,
+/// are not awaited, for those use [`wait::wait_for`] or [`wait::find`].
+///
+/// # Example:
+/// ```no_run
+/// use frontest::prelude::*;
+/// use frontest::run_until_stalled;
+/// use gloo::timers::callback::Timeout;
+///
+/// # async fn settle() {
+/// let mount = mount_html("<p>Loading</p>");
+/// let p = mount.get(&HasText("Loading")).unwrap();
+/// Timeout::new(0, move || {
+///     p.set_inner_text("Parsing");
+///     Timeout::new(0, move || p.set_inner_text("Done")).forget();
+/// })
+/// .forget();
+///
+/// run_until_stalled().await;
+/// assert!(mount.get(&HasText("Done")).is_some());
+///
+/// cleanup();
+/// # }
+/// ```
+///
+/// # Panics:
+/// If the page doesn't stall after 100 iterations, eg. because a timeout keeps scheduling itself.
+///
+/// [`yew`]: ::yew
+pub async fn run_until_stalled() {
+    if !stall_within(MAX_STALL_ITERATIONS).await {
+        panic!(
+            "the page didn't stall after {} iterations, timeouts without a delay kept being scheduled",
+            MAX_STALL_ITERATIONS
+        );
+    }
+}
+
+/// Runs iterations of [`run_until_stalled`], returning whether the page stalled within `limit` of them.
+async fn stall_within(limit: usize) -> bool {
+    let timeouts = time::TimeoutCounter::new();
+    for _ in 0..limit {
+        // The sentinel uses the real timers, so it isn't stopped by `time::pause`
+        time::uncounted(tick()).await;
+        #[cfg(feature = "yew")]
+        if !time::is_paused() {
+            time::uncounted(::yew::platform::time::sleep(Duration::ZERO)).await;
+        }
+        if timeouts.take() == 0 {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_run_until_stalled() {
+    use crate::query::{HasText, Query};
+    use gloo::timers::callback::Timeout;
+
+    let mount = mount_html("<p>Loading</p>");
+    let p = mount.get(&HasText("Loading")).unwrap();
+    Timeout::new(0, move || {
+        p.set_inner_text("Parsing");
+        Timeout::new(0, move || p.set_inner_text("Done")).forget();
+    })
+    .forget();
+
+    run_until_stalled().await;
+    assert!(mount.get(&HasText("Done")).is_some());
+
+    cleanup();
+}
+
+#[cfg(all(test, feature = "yew"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn run_until_stalled_settles_dependent_updates() {
+    use crate::query::{HasText, Query};
+    use ::yew::prelude::*;
+    use gloo::timers::callback::Timeout;
+
+    #[function_component(Chain)]
+    fn chain() -> Html {
+        let step = use_state(|| 0);
+        {
+            let step = step.clone();
+            // Every step schedules the next one, after it is rendered
+            use_effect_with(*step, move |&current| {
+                if current < 3 {
+                    Timeout::new(0, move || step.set(current + 1)).forget();
+                }
+            });
+        }
+        html! { <p>{ format!("Step {}", *step) }</p> }
+    }
+
+    let mount = yew::render(html! { <Chain /> }).await;
+    tick().await;
+    assert!(mount.get(&HasText("Step 3")).is_none());
+
+    run_until_stalled().await;
+    assert!(mount.get(&HasText("Step 3")).is_some());
+    // Stalled already, so it returns after a single iteration
+    run_until_stalled().await;
+    assert!(mount.get(&HasText("Step 3")).is_some());
+
+    cleanup();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn run_until_stalled_gives_up_on_rescheduling_timeouts() {
+    use gloo::timers::callback::Timeout;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn reschedule(stop: Rc<Cell<bool>>) {
+        if !stop.get() {
+            Timeout::new(0, move || reschedule(stop)).forget();
+        }
+    }
+    let stop = Rc::new(Cell::new(false));
+    reschedule(stop.clone());
+
+    assert!(!stall_within(5).await);
+    stop.set(true);
+    assert!(stall_within(MAX_STALL_ITERATIONS).await);
+    // Timeouts with a delay don't prevent stalling
+    Timeout::new(0, || {
+        Timeout::new(10_000, || {}).forget();
+    })
+    .forget();
+    assert!(stall_within(1).await);
+}
+
+/// Let all microtasks queued so far run before continuing, without waiting for any timer.
+///
+/// Renders of [`yew`] components, as well as other futures spawned with [`spawn_local`], are run
+/// as microtasks, so this is enough for them to be flushed. Unlike [`tick`] it doesn't depend on
+/// how timers are scheduled by the environment, so it behaves the same in browsers and in node.
+/// Timers and events are not awaited though, for those use [`tick`].
+///
+/// [`yew`]: ::yew
+/// [`spawn_local`]: wasm_bindgen_futures::spawn_local
+pub async fn flush() {
+    let resolved = js_sys::Promise::resolve(&wasm_bindgen::JsValue::UNDEFINED);
+    // A resolved promise can't be rejected
+    let _ = wasm_bindgen_futures::JsFuture::from(resolved).await;
 }
@@ -1,10 +1,42 @@
-use gloo::utils::document;
-use wasm_bindgen::JsCast;
+use crate::debug::better_query;
+use crate::snapshot::snapshot_html;
+use crate::wait::WaitOptions;
+use gloo::utils::{body, document, window};
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::panic::Location;
+use std::rc::Rc;
+use wasm_bindgen::{JsCast, JsValue};
 use web_sys::{
-    Element, HtmlButtonElement, HtmlElement, HtmlInputElement, HtmlLabelElement, HtmlMeterElement,
-    HtmlOutputElement, HtmlProgressElement, HtmlSelectElement, HtmlTextAreaElement,
+    AssignedNodesOptions, Document, DocumentFragment, Element, HtmlButtonElement, HtmlCollection,
+    HtmlElement, HtmlIFrameElement, HtmlInputElement, HtmlLabelElement, HtmlMeterElement,
+    HtmlOutputElement, HtmlProgressElement, HtmlSelectElement, HtmlSlotElement,
+    HtmlTemplateElement, HtmlTextAreaElement, Node, NodeList, ShadowRoot,
 };
 
+const XHTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+
+/// Casts an element into an [`HtmlElement`] if it is one.
+///
+/// Unlike [`JsCast::dyn_into`] it doesn't rely on `instanceof`, so it also works for elements
+/// coming from other javascript realms, eg. from same-origin iframes.
+fn as_html_element(elem: Element) -> Option<HtmlElement> {
+    (elem.namespace_uri().as_deref() == Some(XHTML_NAMESPACE)).then(|| elem.unchecked_into())
+}
+
+/// Casts an element into a specific html element type if it has a matching tag name.
+///
+/// Works across javascript realms the same way as [`as_html_element`].
+fn html_cast<'e, T: JsCast>(elem: &'e HtmlElement, tag: &str) -> Option<&'e T> {
+    (elem.local_name() == tag).then(|| elem.unchecked_ref())
+}
+
+/// Returns the document an element belongs to, which differs from the global one for iframes.
+fn owner_document(elem: &Element) -> Document {
+    elem.owner_document().unwrap_or_else(document)
+}
+
 /// Returns the list of aria roles for a given [`HtmlElement`].
 ///
 /// Aria role is a semantic meaning of an element.
@@ -15,13 +47,14 @@ use web_sys::{
 /// |---------------------------------|-------------------|
 /// | `<article>`                     | article           |
 /// | `<button>`                      | button            |
-/// | `<td>`                          | cell, gridcell    |
+/// | `<td>`                          | cell              |
+/// | `<td>` in `<table role=grid>`   | gridcell          |
 /// | `<select>`                      | combobox, listbox |
 /// | `<menuitem>`                    | command, menuitem |
 /// | `<dd>`                          | definition        |
 /// | `<figure>`                      | figure            |
 /// | `<form>`                        | form              |
-/// | `<table>`                       | grid, table       |
+/// | `<table>`                       | table             |
 /// | `<fieldset>`                    | group             |
 /// | `<h1> <h2> <h3> <h4> <h5> <h6>` | heading           |
 /// | `<img>`                         | img               |
@@ -30,6 +63,7 @@ use web_sys::{
 /// | `<li>`                          | listitem          |
 /// | `<nav>`                         | navigation        |
 /// | `<option>`                      | option            |
+/// | `<output>`                      | status            |
 /// | `<frame>`                       | region            |
 /// | `<rel>`                         | roletype          |
 /// | `<tr>`                          | row               |
@@ -38,6 +72,8 @@ use web_sys::{
 /// | `<dt> <dfn>`                    | term              |
 /// | `<textarea>`                    | textbox           |
 /// | `<input type=button>`           | button            |
+/// | `<input type=submit>`           | button            |
+/// | `<input type=reset>`            | button            |
 /// | `<input type=checkbox>`         | checkbox          |
 /// | `<input type=radio>`            | radio             |
 /// | `<input type=search>`           | searchbox         |
@@ -45,18 +81,30 @@ use web_sys::{
 /// | `<th scope=row>`                | rowheader         |
 /// | `<th>`                          | columnheader      |
 ///
+/// Roles of table cells depend on their context. When the author overrides the role of a `<table>`
+/// with `role="grid"` or `role="treegrid"`, its cells become grid cells and are no longer exposed as `cell`.
+///
 /// [`accessibility`]: https://developer.mozilla.org/en-US/docs/Web/Accessibility
 pub fn element_to_aria_roles(elem: &HtmlElement) -> Vec<&'static str> {
     match elem.tag_name().to_lowercase().as_str() {
         "article" => vec!["article"],
         "button" => vec!["button"],
-        "td" => vec!["cell", "gridcell"],
+        "td" => {
+            let table_role = Element::closest(elem, "table")
+                .ok()
+                .flatten()
+                .and_then(|table| table.get_attribute("role"));
+            match table_role.as_deref() {
+                Some("grid") | Some("treegrid") => vec!["gridcell"],
+                _ => vec!["cell"],
+            }
+        }
         "select" => vec!["combobox", "listbox"],
         "menuitem" => vec!["command", "menuitem"],
         "dd" => vec!["definition"],
         "figure" => vec!["figure"],
         "form" => vec!["form"],
-        "table" => vec!["grid", "table"],
+        "table" => vec!["table"],
         "fieldset" => vec!["group"],
         "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => vec!["heading"],
         "img" => vec!["img"],
@@ -65,6 +113,7 @@ pub fn element_to_aria_roles(elem: &HtmlElement) -> Vec<&'static str> {
         "li" => vec!["listitem"],
         "nav" => vec!["navigation"],
         "option" => vec!["option"],
+        "output" => vec!["status"],
         "frame" => vec!["region"],
         "rel" => vec!["roletype"],
         "tr" => vec!["row"],
@@ -73,7 +122,7 @@ pub fn element_to_aria_roles(elem: &HtmlElement) -> Vec<&'static str> {
         "dt" | "dfn" => vec!["term"],
         "textarea" => vec!["textbox"],
         "input" => match elem.get_attribute("type").as_deref().unwrap_or("") {
-            "button" => vec!["button"],
+            "button" | "submit" | "reset" => vec!["button"],
             "checkbox" => vec!["checkbox"],
             "radio" => vec!["radio"],
             "search" => vec!["searchbox"],
@@ -84,8 +133,319 @@ pub fn element_to_aria_roles(elem: &HtmlElement) -> Vec<&'static str> {
             "row" => vec!["rowheader"],
             _ => vec!["columnheader"],
         },
-        _ => vec![],
+        tag => CUSTOM_ROLES.with(|custom| {
+            custom
+                .borrow()
+                .get(tag)
+                .map(|roles| roles.to_vec())
+                .unwrap_or_default()
+        }),
+    }
+}
+
+thread_local! {
+    static CUSTOM_ROLES: RefCell<HashMap<String, &'static [&'static str]>> = RefCell::new(HashMap::new());
+}
+
+/// Teaches [`element_to_aria_roles`], and so [`HasRole`], implicit roles of custom elements.
+///
+/// Custom elements, eg. components of a design system, don't have any implicit roles. When their
+/// role isn't set with the `role` attribute, they can be registered once to be matched
+/// like built-in elements. Registering the same tag again replaces its roles.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+/// use frontest::query::register_custom_role;
+///
+/// register_custom_role("my-button", &["button"]);
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<my-button>Save</my-button>"#);
+/// body().append_child(&div).unwrap();
+///
+/// assert!(div.get(&HasRole("button").named("Save")).is_some());
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+pub fn register_custom_role(tag: &str, roles: &'static [&'static str]) {
+    CUSTOM_ROLES.with(|custom| custom.borrow_mut().insert(tag.to_lowercase(), roles));
+}
+
+/// Returns a css selector of custom elements registered with given role.
+fn custom_role_selector(role: &str) -> Option<String> {
+    let tags = CUSTOM_ROLES.with(|custom| {
+        let mut tags = custom
+            .borrow()
+            .iter()
+            .filter(|(_, roles)| roles.contains(&role))
+            .map(|(tag, _)| tag.clone())
+            .collect::<Vec<_>>();
+        tags.sort();
+        tags
+    });
+    (!tags.is_empty()).then(|| tags.join(", "))
+}
+
+/// Returns a css selector of elements which may implicitly have given role.
+///
+/// It is a reverse of [`element_to_aria_roles`] and has to be kept in sync with it.
+/// The selector may match more elements than those actually having the role, but never less.
+fn implicit_role_selector(role: &str) -> Option<&'static str> {
+    Some(match role {
+        "article" => "article",
+        "button" => "button, input[type=button], input[type=submit], input[type=reset]",
+        "cell" | "gridcell" => "td",
+        "combobox" | "listbox" => "select",
+        "command" | "menuitem" => "menuitem",
+        "definition" => "dd",
+        "figure" => "figure",
+        "form" => "form",
+        "table" => "table",
+        "group" => "fieldset",
+        "heading" => "h1, h2, h3, h4, h5, h6",
+        "img" => "img",
+        "link" => "a, link",
+        "list" => "ol, ul",
+        "listitem" => "li",
+        "navigation" => "nav",
+        "option" => "option",
+        "status" => "output",
+        "region" => "frame",
+        "roletype" => "rel",
+        "row" => "tr",
+        "rowgroup" => "tbody, tfoot, thead",
+        "separator" => "hr",
+        "term" => "dt, dfn",
+        "textbox" => "textarea, input[type=text]",
+        "checkbox" => "input[type=checkbox]",
+        "radio" => "input[type=radio]",
+        "searchbox" => "input[type=search]",
+        "rowheader" | "columnheader" => "th",
+        _ => return None,
+    })
+}
+
+/// Quotes a value to be used as a css string, eg. in attribute selectors.
+pub(crate) fn css_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn element_to_aria_roles_respects_grid_override() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<div>
+            <table role="grid">
+                <tr><td>In grid</td></tr>
+            </table>
+            <table>
+                <tr><td>In table</td></tr>
+            </table>
+        </div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let gridcell = div.get(&HasRole("gridcell")).unwrap();
+    assert_eq!(gridcell.inner_text(), "In grid");
+    let cell = div.get(&HasRole("cell")).unwrap();
+    assert_eq!(cell.inner_text(), "In table");
+    assert_eq!(div.get_all(&HasRole("row")).len(), 2);
+    assert!(div.get(&HasRole("grid")).is_some());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_register_custom_role() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+
+    register_custom_role("my-button", &["button"]);
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<my-button>Save</my-button>"#);
+    body().append_child(&div).unwrap();
+
+    assert!(div.get(&HasRole("button").named("Save")).is_some());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn register_custom_role_extends_implicit_roles() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<ds-tab-list>
+            <ds-tab>Overview</ds-tab>
+            <ds-tab role="link">Docs</ds-tab>
+        </ds-tab-list>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    assert!(div.get(&HasRole("tablist")).is_none());
+    register_custom_role("DS-TAB-LIST", &["tablist"]);
+    register_custom_role("ds-tab", &["tab"]);
+
+    let list = div.get(&HasRole("tablist")).unwrap();
+    assert_eq!(element_to_aria_roles(&list), vec!["tablist"]);
+    let tabs = div.get_all(&HasRole("tab"));
+    assert_eq!(tabs.len(), 2);
+    assert_eq!(tabs[0].inner_text(), "Overview");
+    assert!(div.get(&HasRole("link")).is_some());
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Returns the labels associated with an element if it is one of the [`labelable`] elements.
+///
+/// [`labelable`]: https://developer.mozilla.org/en-US/docs/Web/HTML/Element/label
+fn labels_of(elem: &HtmlElement) -> Option<NodeList> {
+    let labels = if let Some(elem) = html_cast::<HtmlInputElement>(elem, "input") {
+        // input type="hidden" doesn't support labels
+        if elem.type_() == "hidden" {
+            return None;
+        }
+        return elem.labels();
+    } else if let Some(elem) = html_cast::<HtmlButtonElement>(elem, "button") {
+        elem.labels()
+    } else if let Some(elem) = html_cast::<HtmlMeterElement>(elem, "meter") {
+        elem.labels()
+    } else if let Some(elem) = html_cast::<HtmlOutputElement>(elem, "output") {
+        elem.labels()
+    } else if let Some(elem) = html_cast::<HtmlProgressElement>(elem, "progress") {
+        elem.labels()
+    } else if let Some(elem) = html_cast::<HtmlSelectElement>(elem, "select") {
+        elem.labels()
+    } else if let Some(elem) = html_cast::<HtmlTextAreaElement>(elem, "textarea") {
+        elem.labels()
+    } else {
+        return None;
+    };
+    Some(labels)
+}
+
+/// Collapses all whitespace sequences into single spaces and trims the result.
+pub(crate) fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Elements which can receive focus, unless they are disabled or inert.
+pub(crate) const FOCUSABLE: &str = "a[href], area[href], button, input, select, textarea, iframe, \
+                                    summary, [tabindex], [contenteditable]:not([contenteditable=false])";
+
+/// Returns whether an element can receive focus: it matches [`FOCUSABLE`] and isn't disabled or inert.
+///
+/// Visibility isn't checked, callers reaching elements without a pointer have to check it themselves.
+pub(crate) fn is_focusable(elem: &Element) -> bool {
+    elem.matches(FOCUSABLE).unwrap()
+        && !elem.matches(":disabled").unwrap()
+        && elem.closest("[inert]").unwrap().is_none()
+}
+
+/// Roles which take their accessible name from the element's content.
+const NAME_FROM_CONTENT_ROLES: &[&str] = &[
+    "button",
+    "cell",
+    "checkbox",
+    "columnheader",
+    "gridcell",
+    "heading",
+    "link",
+    "menuitem",
+    "option",
+    "radio",
+    "row",
+    "rowheader",
+    "switch",
+    "tab",
+    "tooltip",
+    "treeitem",
+];
+
+/// Returns the accessible name of a given [`HtmlElement`].
+///
+/// Accessible name is what assistive technology announces when an element is focused.
+/// This is a simplified version of the [`accname`] algorithm, which checks in order:
+/// - `aria-labelledby` attribute, joining text of all referenced elements,
+/// - `aria-label` attribute,
+/// - associated `<label>` elements, `alt` of images and `value` of input buttons,
+/// - text content for roles allowing naming from content, eg. `button` or `link`,
+/// - `title` and `placeholder` attributes.
+///
+/// Whitespace in the resulting name is normalized.
+///
+/// [`accname`]: https://www.w3.org/TR/accname-1.2/
+pub fn accessible_name(elem: &HtmlElement) -> String {
+    if let Some(ids) = elem.get_attribute("aria-labelledby") {
+        let name = ids
+            .split_whitespace()
+            .filter_map(|id| owner_document(elem).get_element_by_id(id))
+            .filter_map(|label| label.text_content())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let name = normalize_whitespace(&name);
+        if !name.is_empty() {
+            return name;
+        }
+    }
+    if let Some(label) = elem.get_attribute("aria-label") {
+        let name = normalize_whitespace(&label);
+        if !name.is_empty() {
+            return name;
+        }
+    }
+    if let Some(labels) = labels_of(elem) {
+        let name = (0..labels.length())
+            .filter_map(|idx| labels.get(idx))
+            .filter_map(|label| label.text_content())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let name = normalize_whitespace(&name);
+        if !name.is_empty() {
+            return name;
+        }
+    }
+    if let Some(value) = input_button_value(elem) {
+        let name = normalize_whitespace(&value);
+        if !name.is_empty() {
+            return name;
+        }
+    }
+    if elem.tag_name().eq_ignore_ascii_case("img") {
+        if let Some(alt) = elem.get_attribute("alt") {
+            let name = normalize_whitespace(&alt);
+            if !name.is_empty() {
+                return name;
+            }
+        }
+    }
+    let explicit_role = elem.get_attribute("role");
+    if element_to_aria_roles(elem)
+        .into_iter()
+        .chain(explicit_role.as_deref())
+        .any(|role| NAME_FROM_CONTENT_ROLES.contains(&role))
+    {
+        let name = normalize_whitespace(&elem.text_content().unwrap_or_default());
+        if !name.is_empty() {
+            return name;
+        }
     }
+    ["title", "placeholder"]
+        .iter()
+        .filter_map(|attr| elem.get_attribute(attr))
+        .map(|value| normalize_whitespace(&value))
+        .find(|name| !name.is_empty())
+        .unwrap_or_default()
 }
 
 /// Trait implemented by types that can be used as a predicate for [`HtmlElement`].
@@ -123,6 +483,32 @@ pub fn element_to_aria_roles(elem: &HtmlElement) -> Vec<&'static str> {
 pub trait Matcher {
     /// Returns `true` if the element was matched by [`Matcher`].
     fn matches(&self, elem: &HtmlElement) -> bool;
+
+    /// Returns a human readable description of the [`Matcher`] used in failure messages.
+    ///
+    /// Built-in matchers describe themselves the same way they are constructed, eg. `HasRole("button")`.
+    fn describe(&self) -> String {
+        String::from("<custom matcher>")
+    }
+
+    /// Returns `true` if the element would be matched if it, or its content, was visible.
+    ///
+    /// Used to explain why a query didn't find anything. Matchers depending on what is presented
+    /// to the user, like [`HasText`], should override it. By default it is the same as [`matches`].
+    ///
+    /// [`matches`]: Matcher::matches
+    fn matches_ignoring_visibility(&self, elem: &HtmlElement) -> bool {
+        self.matches(elem)
+    }
+
+    /// Returns a css selector which all elements matched by [`Matcher`] satisfy, if there is one.
+    ///
+    /// Queries use it to skip elements which can't be matched anyway. The selector may also be satisfied
+    /// by elements that aren't matched, but it must never exclude any element that would be.
+    /// By default there is no hint and all elements are checked.
+    fn selector_hint(&self) -> Option<String> {
+        None
+    }
 }
 
 #[cfg(test)]
@@ -156,409 +542,341 @@ async fn doctest_matcher() {
     body().remove_child(&div).unwrap();
 }
 
-/// Consumes a [`Matcher`] and returns a negation of it.
+/// Boxed matchers, including trait objects, are matchers too.
 ///
-/// Utility wrapper that performs a logical `not` operation on a matcher.
+/// It allows building matchers at runtime and still joining them with [`Joinable`] methods.
 ///
 /// # Example:
 ///
 /// ```no_run
 /// use gloo::utils::{body, document};
 /// use frontest::prelude::*;
-
+///
 /// let div = document().create_element("div").unwrap();
 /// div.set_inner_html(
 ///     r#"<div>
-///         <p>what</p>
-///         <a href="/foo">is</a>
-///         <button>this</button>
+///         <button>Save</button>
+///         <button>Cancel</button>
 ///     </div>"#,
 /// );
 /// body().append_child(&div).unwrap();
 ///
-/// let link = div.get(&HasText("is").and(Not(HasRole("button")))).unwrap();
-/// assert_eq!(&link.get_attribute("href").unwrap(), "/foo");
+/// let role: Box<dyn Matcher> = Box::new(HasRole("button"));
+/// let text: Box<dyn Matcher> = Box::new(HasText("Save"));
+/// assert!(div.get(&role.and(text)).is_some());
 ///
 /// body().remove_child(&div).unwrap();
 /// ```
-pub struct Not<M: Matcher>(pub M);
-
-impl<M: Matcher> Matcher for Not<M> {
+impl<M: Matcher + ?Sized> Matcher for Box<M> {
     fn matches(&self, elem: &HtmlElement) -> bool {
-        !self.0.matches(elem)
+        (**self).matches(elem)
     }
-}
 
-#[cfg(test)]
-#[wasm_bindgen_test::wasm_bindgen_test]
-async fn doctest_not() {
-    use crate::query::{HasRole, HasText, Joinable, Not, Query};
-    use gloo::utils::{body, document};
-    let div = document().create_element("div").unwrap();
-    div.set_inner_html(
-        r#"<div>
-            <p>what</p>
-            <a href="/foo">is</a>
-            <button>this</button>
-        </div>"#,
-    );
-    body().append_child(&div).unwrap();
+    fn describe(&self) -> String {
+        (**self).describe()
+    }
 
-    let link = div.get(&HasText("is").and(Not(HasRole("button")))).unwrap();
-    assert_eq!(&link.get_attribute("href").unwrap(), "/foo");
+    fn matches_ignoring_visibility(&self, elem: &HtmlElement) -> bool {
+        (**self).matches_ignoring_visibility(elem)
+    }
 
-    body().remove_child(&div).unwrap();
+    fn selector_hint(&self) -> Option<String> {
+        (**self).selector_hint()
+    }
 }
 
-/// Matches components that have visible text that contains given substring.
+/// Forwards to the referenced matcher.
 ///
-/// [`HasText`] uses [`inner_text`] under the hood and is case-sensitive.
-/// It will match elements by their content as presented for user.
-/// All css rules applies eg. those switching text content, case or visibility.
-/// Remember that for this experience you need to insert an element somewhere into DOM.
+/// It allows reusing a single matcher in multiple joined matchers, as [`Joinable`] methods consume them.
 ///
 /// # Example:
 ///
 /// ```no_run
-/// # use gloo::utils::{body, document};
+/// use gloo::utils::{body, document};
 /// use frontest::prelude::*;
 ///
 /// let div = document().create_element("div").unwrap();
-/// div.set_inner_html(
-///     r#"<div>
-///         <button>I am</button>
-///         <button style="visibility: hidden;">Blue</button>
-///     </div>"#,
-/// );
-/// // Without this line, the last assert will panic as css rules won't be applied.
+/// div.set_inner_html(r#"<button>Save</button><a href="/save">Save</a>"#);
 /// body().append_child(&div).unwrap();
 ///
-/// assert!(div.get(&HasText("I am")).is_some());
-/// assert!(div.get(&HasText("i am")).is_none());
-/// assert!(div.get(&HasText("Blue")).is_none());
+/// let save = HasText("Save");
+/// assert!(div.get(&(&save).and(HasRole("button"))).is_some());
+/// assert!(div.get(&(&save).and(HasRole("link"))).is_some());
 ///
 /// body().remove_child(&div).unwrap();
 /// ```
-/// [`inner_text`]: web_sys::HtmlElement::inner_text
-#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
-pub struct HasText<'a>(pub &'a str);
-
-impl<'a> Matcher for HasText<'a> {
+impl<M: Matcher + ?Sized> Matcher for &M {
     fn matches(&self, elem: &HtmlElement) -> bool {
-        elem.inner_text().contains(self.0) && {
-            let children_len = elem.children().length();
-            !(0..children_len)
-                .filter_map(|n| elem.children().item(n))
-                .filter_map(|child| child.dyn_into::<HtmlElement>().ok())
-                .any(|child| child.inner_text().contains(self.0))
-        }
+        (**self).matches(elem)
+    }
+
+    fn describe(&self) -> String {
+        (**self).describe()
+    }
+
+    fn matches_ignoring_visibility(&self, elem: &HtmlElement) -> bool {
+        (**self).matches_ignoring_visibility(elem)
+    }
+
+    fn selector_hint(&self) -> Option<String> {
+        (**self).selector_hint()
     }
 }
 
 #[cfg(test)]
 #[wasm_bindgen_test::wasm_bindgen_test]
-async fn doctest_has_text() {
-    use crate::query::{HasText, Query};
+async fn doctest_referenced_matcher() {
+    use crate::query::{HasRole, HasText, Joinable, Query};
     use gloo::utils::{body, document};
     let div = document().create_element("div").unwrap();
-    div.set_inner_html(
-        r#"<div>
-            <button>I am</button>
-            <button style="visibility: hidden;">Blue</button>
-        </div>"#,
-    );
-    // Without this line, the last assert will panic as css rules won't be applied.
+    div.set_inner_html(r#"<button>Save</button><a href="/save">Save</a>"#);
     body().append_child(&div).unwrap();
 
-    assert!(div.get(&HasText("I am")).is_some());
-    assert!(div.get(&HasText("i am")).is_none());
-    assert!(div.get(&HasText("Blue")).is_none());
+    let save = HasText("Save");
+    assert!(div.get(&(&save).and(HasRole("button"))).is_some());
+    assert!(div.get(&(&save).and(HasRole("link"))).is_some());
 
     body().remove_child(&div).unwrap();
 }
 
-/// Matches components that have given aria role.
-///
-/// This is by far the best method for finding components as it searches for elements in the [`accessibility tree`].
-/// You should always prefer something like `.get(&HasRole("button").and(HasText("Add")))` over the alternavies.
-/// Currently only supports user assigned roles and semantic tag to role deduction with [`element_to_aria_roles`].
-/// It currently doesn't support any of [`aria_attribute_types`] or implicit role deduction.
-/// Support for those is planned as much as it can be at this age of project.
-///
-/// # Example:
-///
-/// ```no_run
-/// # use gloo::utils::{body, document};
-/// use frontest::prelude::*;
-///
-/// let div = document().create_element("div").unwrap();
-/// div.set_inner_html(
-///     r#"<div>
-///         <button>Rust</button>
-///         <input type="button">Is</input>
-///         <div role="button">Fun</input>
-///     </div>"#,
-/// );
-/// body().append_child(&div).unwrap();
-///
-/// assert_eq!(div.get_all(&HasRole("button")).len(), 3);
-///
-/// body().remove_child(&div).unwrap();
-/// ```
-/// [`accessibility_tree`]: https://developer.mozilla.org/en-US/docs/Glossary/Accessibility_tree
-/// [`aria_attribute_types`]: https://developer.mozilla.org/en-US/docs/Web/Accessibility/ARIA/Attributes#aria_attribute_types
-#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
-pub struct HasRole<'a>(pub &'a str);
+#[cfg(test)]
+pub(crate) mod fixtures {
+    //! Matchers recording how queries use them, shared by the tests.
 
-impl<'a> Matcher for HasRole<'a> {
-    fn matches(&self, elem: &HtmlElement) -> bool {
-        if element_to_aria_roles(elem).contains(&self.0) {
-            true
-        } else if let Some(role) = elem.get_attribute("role") {
-            role == self.0
-        } else {
-            false
+    use super::Matcher;
+    use std::cell::{Cell, RefCell};
+    use web_sys::HtmlElement;
+
+    /// Counts elements checked by the wrapped [`Matcher`].
+    ///
+    /// It doesn't forward the selector hint, so queries check every element.
+    pub(crate) struct Counting<'a, M>(pub &'a Cell<usize>, pub M);
+
+    impl<M: Matcher> Matcher for Counting<'_, M> {
+        fn matches(&self, elem: &HtmlElement) -> bool {
+            self.0.set(self.0.get() + 1);
+            self.1.matches(elem)
+        }
+
+        fn describe(&self) -> String {
+            format!("Counting({})", self.1.describe())
+        }
+    }
+
+    /// Always returns the given result, logging its name whenever it is checked.
+    pub(crate) struct Logged<'a>(
+        pub &'static str,
+        pub bool,
+        pub &'a RefCell<Vec<&'static str>>,
+    );
+
+    impl Matcher for Logged<'_> {
+        fn matches(&self, _: &HtmlElement) -> bool {
+            self.2.borrow_mut().push(self.0);
+            self.1
+        }
+
+        fn describe(&self) -> String {
+            format!("Logged({:?})", self.0)
         }
     }
 }
 
 #[cfg(test)]
 #[wasm_bindgen_test::wasm_bindgen_test]
-async fn doctest_has_role() {
-    use crate::query::{HasRole, Query};
+async fn referenced_matcher_is_reused_across_queries() {
+    use crate::query::fixtures::Counting;
+    use crate::query::{HasRole, HasText, Joinable, Query};
     use gloo::utils::{body, document};
+    use std::cell::Cell;
+
     let div = document().create_element("div").unwrap();
     div.set_inner_html(
-        r#"<div>
-            <button>Rust</button>
-            <input type="button">Is</input>
-            <div role="button">Fun</input>
-        </div>"#,
+        r#"<button>Save</button>
+        <a href="/save">Save as</a>
+        <button>Cancel</button>"#,
     );
     body().append_child(&div).unwrap();
 
-    assert_eq!(div.get_all(&HasRole("button")).len(), 3);
+    let count = Cell::new(0);
+    let save = Counting(&count, MatchesSelector("button:first-child, a"));
+    assert_eq!(div.get_all(&(&save).and(HasRole("button"))).len(), 1);
+    assert!(div.get(&HasRole("link").and(&save)).is_some());
+    assert_eq!(div.get_all(&(&save).or(HasText("Cancel"))).len(), 3);
+    assert!(count.get() > 0);
+    // Boxed trait objects are matchers too, also behind references
+    let boxed: Box<dyn Matcher> = Box::new(HasRole("button"));
+    assert_eq!(div.get_all(&(&boxed).and_not(&save)).len(), 1);
+    assert_eq!(div.get_all(&boxed).len(), 2);
 
     body().remove_child(&div).unwrap();
 }
 
-/// Matches components that have given label.
-///
-/// This is also a great method for interacting with DOM in the way as a user would.
-/// Labels are not only visually connected with elements but also programatically.
-/// Screen readers will read out the labels when given component is selected as well as
-/// clicking on a label results in selecting labeled component.
-///
-/// [`Labeling'] is supported for input elements (except type="hidden"), button, meter,
-/// output, progress, select and text area.
-///
-/// # Example:
-///
-/// ```no_run
-/// # use gloo::utils::{body, document};
-/// use frontest::prelude::*;
-///
-/// let div = document().create_element("div").unwrap();
-/// div.set_inner_html(
-///     r#"<div>
-///         <!-- for id attributes -->
-///         <label for="best-language">Type rust</label>
-///         <input id="best-language" />
-///
-///         <!-- implicit labels -->
-///         <label>Type rust <meter /></label>
-///
-///         <!-- wrapped implicit labels -->
-///         <label>
-///           <span>Type rust</span>
-///           <button />
-///         </label>
-///
-///         <!-- aria-labelledby attributes -->
-///         <label id="best-language">Type rust</label>
-///         <input aria-labelledby="best-language" />
-///
-///         <!-- aria-label attributes are not supported as they are not visible to user -->
-///         <input aria-label="Type rust" />
-///     </div>"#,
-/// );
-/// body().append_child(&div).unwrap();
-///
-/// assert_eq!(div.get_all(&HasLabel("Type rust")).len(), 4);
-///
-/// body().remove_child(&div).unwrap();
-/// ```
-/// [`Labeling`]: https://developer.mozilla.org/en-US/docs/Web/HTML/Element/label
-#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
-pub struct HasLabel<'a>(pub &'a str);
+macro_rules! tuple_matcher {
+    ($(#[$attr:meta])* $($name:ident $idx:tt),+) => {
+        $(#[$attr])*
+        impl<$($name: Matcher),+> Matcher for ($($name,)+) {
+            fn matches(&self, elem: &HtmlElement) -> bool {
+                $(self.$idx.matches(elem))&&+
+            }
 
-impl<'a> Matcher for HasLabel<'a> {
-    fn matches(&self, elem: &HtmlElement) -> bool {
-        // Check if element is one of types that support labeling
-        // and if so, extract labels
-        // https://developer.mozilla.org/en-US/docs/Web/HTML/Element/label
-        let labels = if let Some(elem) = elem.dyn_ref::<HtmlInputElement>() {
-            // input type="hidden" doesn't support labels
-            if elem.type_() == "hidden" {
-                return false;
+            fn describe(&self) -> String {
+                let filters = [$(self.$idx.describe()),+];
+                format!("all of ({})", filters.join(", "))
             }
-            elem.labels().unwrap()
-        } else if let Some(elem) = elem.dyn_ref::<HtmlButtonElement>() {
-            elem.labels()
-        } else if let Some(elem) = elem.dyn_ref::<HtmlMeterElement>() {
-            elem.labels()
-        } else if let Some(elem) = elem.dyn_ref::<HtmlOutputElement>() {
-            elem.labels()
-        } else if let Some(elem) = elem.dyn_ref::<HtmlProgressElement>() {
-            elem.labels()
-        } else if let Some(elem) = elem.dyn_ref::<HtmlSelectElement>() {
-            elem.labels()
-        } else if let Some(elem) = elem.dyn_ref::<HtmlTextAreaElement>() {
-            elem.labels()
-        } else {
-            return false;
-        };
-        // Check if element is labeled by requested label
-        if (0..labels.length())
-            .filter_map(|idx| labels.get(idx))
-            .any(|label| label.text_content().as_deref() == Some(self.0))
-        {
-            return true;
-        }
-        // Check if element is implicitly wrapped with label
-        if let Some(parent) = elem.parent_element() {
-            if let Some(label) = parent.dyn_ref::<HtmlLabelElement>() {
-                let child_nodes = label.child_nodes();
-                if (0..child_nodes.length())
-                    .filter_map(|idx| child_nodes.get(idx))
-                    .filter(|child| Some(elem) != child.dyn_ref())
-                    .any(|child| child.text_content().as_deref().map(str::trim) == Some(self.0))
-                {
-                    return true;
-                }
+
+            fn matches_ignoring_visibility(&self, elem: &HtmlElement) -> bool {
+                $(self.$idx.matches_ignoring_visibility(elem))&&+
             }
-        }
-        // Check if element is aria-labelledby a label
-        if let Some(label) = elem.get_attribute("aria-labelledby") {
-            if document().get_element_by_id(&label).is_some() {
-                return true;
+
+            fn selector_hint(&self) -> Option<String> {
+                let hints = [$(self.$idx.selector_hint()),+]
+                    .into_iter()
+                    .flatten()
+                    .map(|hint| format!(":is({})", hint))
+                    .collect::<String>();
+                (!hints.is_empty()).then_some(hints)
             }
         }
-        false
-    }
+    };
 }
 
+tuple_matcher!(
+    /// Tuples of up to 5 matchers match elements matched by all of them, like [`All`].
+    ///
+    /// Matchers are checked in order, stopping at the first one that doesn't match.
+    ///
+    /// # Example:
+    ///
+    /// ```no_run
+    /// use gloo::utils::{body, document};
+    /// use frontest::prelude::*;
+    ///
+    /// let div = document().create_element("div").unwrap();
+    /// div.set_inner_html(r#"<button>Save</button><button>Cancel</button>"#);
+    /// body().append_child(&div).unwrap();
+    ///
+    /// assert!(div.get(&(HasRole("button"), HasText("Save"))).is_some());
+    ///
+    /// body().remove_child(&div).unwrap();
+    /// ```
+    A 0, B 1
+);
+tuple_matcher!(A 0, B 1, C 2);
+tuple_matcher!(A 0, B 1, C 2, D 3);
+tuple_matcher!(A 0, B 1, C 2, D 3, E 4);
+
 #[cfg(test)]
 #[wasm_bindgen_test::wasm_bindgen_test]
-async fn doctest_has_label() {
-    use crate::query::{HasLabel, Query};
+async fn doctest_tuple_matcher() {
+    use crate::query::{HasRole, HasText, Query};
     use gloo::utils::{body, document};
-
     let div = document().create_element("div").unwrap();
-    div.set_inner_html(
-        r#"<div>
-            <!-- for id attributes -->
-            <label for="best-language">Type rust</label>
-            <input id="best-language" />
+    div.set_inner_html(r#"<button>Save</button><button>Cancel</button>"#);
+    body().append_child(&div).unwrap();
 
-            <!-- implicit labels -->
-            <label>Type rust <meter /></label>
+    assert!(div.get(&(HasRole("button"), HasText("Save"))).is_some());
 
-            <!-- wrapped implicit labels -->
-            <label>
-              <span>Type rust</span>
-              <button />
-            </label>
+    body().remove_child(&div).unwrap();
+}
 
-            <!-- aria-labelledby attributes -->
-            <label id="best-language">Type rust</label>
-            <input aria-labelledby="best-language" />
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn tuple_matcher_matches_all_and_short_circuits() {
+    use crate::query::fixtures::Logged;
+    use crate::query::{HasRole, HasText, Query};
+    use gloo::utils::{body, document};
 
-            <!-- aria-label attributes are not supported as they are not visible to user -->
-            <input aria-label="Type rust" />
-        </div>"#,
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<button>Save</button>
+        <button disabled>Save</button>
+        <a href="/save">Save</a>"#,
     );
     body().append_child(&div).unwrap();
 
-    assert_eq!(div.get_all(&HasLabel("Type rust")).len(), 4);
+    let enabled_save = (
+        HasRole("button"),
+        HasText("Save"),
+        MatchesSelector(":enabled"),
+    );
+    assert_eq!(
+        enabled_save.describe(),
+        r#"all of (HasRole("button"), HasText("Save"), MatchesSelector(":enabled"))"#
+    );
+    assert_eq!(div.get_all(&enabled_save).len(), 1);
+    assert!(!div.get(&enabled_save).unwrap().has_attribute("disabled"));
+
+    let log = RefCell::new(vec![]);
+    let elem = div.get(&HasRole("link")).unwrap();
+    let logged = (
+        Logged("first", true, &log),
+        Logged("second", false, &log),
+        Logged("third", true, &log),
+    );
+    assert!(!logged.matches(&elem));
+    assert_eq!(*log.borrow(), ["first", "second"]);
 
     body().remove_child(&div).unwrap();
 }
 
-/// Matches components that have given placeholder text.
-///
-/// Placeholders are not a substitute for labels. If placeholder is the only identifier
-/// for an input, any assistive technology will not be able to identify them.
-/// It is still a better fallback than just using [`HasText`] for accessible elements.
-///
-/// # Example:
-///
-/// ```no_run
-/// # use gloo::utils::{body, document};
-/// use frontest::prelude::*;
-///
-/// let div = document().create_element("div").unwrap();
-/// div.set_inner_html(
-///     r#"<div>
-///         <button>tests rocks</button>
-///         <input placeholder="tests rocks" />
-///     </div>"#,
-/// );
-/// body().append_child(&div).unwrap();
-///
-/// assert_eq!(
-///     div.get(&HasPlaceholder("tests")).unwrap().tag_name(),
-///     "INPUT"
-/// );
-///
-/// body().remove_child(&div).unwrap();
-/// ```
-#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
-pub struct HasPlaceholder<'a>(pub &'a str);
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn tuple_matchers_combine_with_other_matchers() {
+    use crate::query::{HasRole, HasText, Joinable, Query};
+    use gloo::utils::{body, document};
 
-impl<'a> Matcher for HasPlaceholder<'a> {
-    fn matches(&self, elem: &HtmlElement) -> bool {
-        let placeholder = if let Some(elem) = elem.dyn_ref::<HtmlInputElement>() {
-            elem.placeholder()
-        } else if let Some(elem) = elem.dyn_ref::<HtmlTextAreaElement>() {
-            elem.placeholder()
-        } else {
-            return false;
-        };
-        placeholder.contains(self.0)
-    }
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<button>Save</button>
+        <button disabled>Save</button>
+        <button>Cancel</button>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let save = (HasRole("button"), HasText("Save"));
+    assert_eq!(div.get_all(&save).len(), 2);
+    let disabled = MatchesSelector(":disabled");
+    assert_eq!(div.get_all(&(&save).and_not(disabled)).len(), 1);
+    let enabled_save = (HasRole("button"), HasText("Save"), Not(&disabled));
+    assert_eq!(div.get_all(&enabled_save).len(), 1);
+    // Tuples nest and hold references, so existing matchers can be reused
+    let nested = (&save, (Not(&disabled), MatchesSelector("button")));
+    assert_eq!(div.get(&nested), div.get(&enabled_save));
+    assert!(div.get(&(HasRole("link"), HasText("Save"))).is_none());
+
+    body().remove_child(&div).unwrap();
 }
 
 #[cfg(test)]
 #[wasm_bindgen_test::wasm_bindgen_test]
-async fn doctest_has_placeholder() {
-    use crate::query::{HasPlaceholder, Query};
+async fn doctest_boxed_matcher() {
+    use crate::query::{HasRole, HasText, Joinable, Matcher, Query};
     use gloo::utils::{body, document};
-
     let div = document().create_element("div").unwrap();
     div.set_inner_html(
         r#"<div>
-            <button>tests rocks</button>
-            <input placeholder="tests rocks" />
+            <button>Save</button>
+            <button>Cancel</button>
         </div>"#,
     );
     body().append_child(&div).unwrap();
 
-    assert_eq!(
-        div.get(&HasPlaceholder("tests")).unwrap().tag_name(),
-        "INPUT"
-    );
+    let role: Box<dyn Matcher> = Box::new(HasRole("button"));
+    let text: Box<dyn Matcher> = Box::new(HasText("Save"));
+    let save = role.and(text);
+    assert_eq!(save.describe(), r#"HasRole("button").and(HasText("Save"))"#);
+    assert_eq!(div.get(&save).unwrap().inner_text(), "Save");
 
     body().remove_child(&div).unwrap();
 }
 
-/// A trait for joining multiple matchers.
+/// Consumes a [`Matcher`] and returns a negation of it.
 ///
-/// It is automatically implemented for all matchers.
-/// It allows for joining matchers using `or` and `and` methods that consume both matchers
-/// and returns a joined matcher. It can be chained with multiple calls.
+/// Utility wrapper that performs a logical `not` operation on a matcher.
 ///
 /// # Example:
+///
 /// ```no_run
 /// use gloo::utils::{body, document};
 /// use frontest::prelude::*;
@@ -566,137 +884,6631 @@ async fn doctest_has_placeholder() {
 /// let div = document().create_element("div").unwrap();
 /// div.set_inner_html(
 ///     r#"<div>
-///         <button>I eat cookies</button>
+///         <p>what</p>
+///         <a href="/foo">is</a>
+///         <button>this</button>
 ///     </div>"#,
 /// );
 /// body().append_child(&div).unwrap();
 ///
-/// assert!(div
-///     .get(
-///         &HasRole("button")
-///             .and(HasText("bananas").or(HasText("apples")))
-///             .or(HasText("cookies"))
-///     )
-///     .is_some());
+/// let link = div.get(&HasText("is").and(Not(HasRole("button")))).unwrap();
+/// assert_eq!(&link.get_attribute("href").unwrap(), "/foo");
 ///
 /// body().remove_child(&div).unwrap();
 /// ```
-pub trait Joinable {
-    /// Join two matchers by applying logical `and` operation.
-    fn and<'a, 'b, M>(self, other: M) -> And<'b>
-    where
-        'a: 'b,
-        Self: Sized + Matcher + 'a,
-        M: Matcher + 'a,
-    {
-        And {
-            filters: [Box::new(self), Box::new(other)],
-        }
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Not<M: Matcher>(pub M);
+
+impl<M: Matcher> Matcher for Not<M> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        !self.0.matches(elem)
     }
 
-    /// Join two matchers by applying logical `or` operation.
-    fn or<'a, 'b, M>(self, other: M) -> Or<'b>
-    where
-        'a: 'b,
-        Self: Sized + Matcher + 'a,
-        M: Matcher + 'a,
-    {
-        Or {
-            filters: [Box::new(self), Box::new(other)],
-        }
+    fn describe(&self) -> String {
+        format!("Not({})", self.0.describe())
     }
-}
 
-impl<M> Joinable for M where M: Matcher {}
+    fn matches_ignoring_visibility(&self, elem: &HtmlElement) -> bool {
+        // Negating the relaxed check would reject hidden elements which `matches` accepts
+        self.matches(elem)
+    }
+}
 
 #[cfg(test)]
 #[wasm_bindgen_test::wasm_bindgen_test]
-async fn doctest_joinable() {
-    use crate::query::{HasRole, HasText, Joinable, Query};
+async fn doctest_not() {
+    use crate::query::{HasRole, HasText, Joinable, Not, Query};
     use gloo::utils::{body, document};
     let div = document().create_element("div").unwrap();
     div.set_inner_html(
         r#"<div>
-            <button>I eat cookies</button>
+            <p>what</p>
+            <a href="/foo">is</a>
+            <button>this</button>
         </div>"#,
     );
     body().append_child(&div).unwrap();
 
-    assert!(div
-        .get(
-            &HasRole("button")
-                .and(HasText("bananas").or(HasText("apples")))
-                .or(HasText("cookies"))
-        )
-        .is_some());
+    let link = div.get(&HasText("is").and(Not(HasRole("button")))).unwrap();
+    assert_eq!(&link.get_attribute("href").unwrap(), "/foo");
 
     body().remove_child(&div).unwrap();
 }
 
-/// Result of joining two [`Matcher`]s by applyng a logical [`and`] operation on them.
-///
-/// [`and`]: Joinable::and
-pub struct And<'a> {
-    filters: [Box<dyn Matcher + 'a>; 2],
-}
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn not_ignoring_visibility_keeps_its_matches() {
+    use crate::query::{HasText, Not, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<p style="visibility: hidden;">Secret</p>"#);
+    body().append_child(&div).unwrap();
 
-impl<'a> Matcher for And<'a> {
-    fn matches(&self, elem: &HtmlElement) -> bool {
-        self.filters.iter().all(|f| f.matches(elem))
-    }
+    let secret = div.get(&MatchesSelector("p")).unwrap();
+    assert!(HasText("Secret").matches_ignoring_visibility(&secret));
+    assert!(Not(HasText("Secret")).matches(&secret));
+    assert!(Not(HasText("Secret")).matches_ignoring_visibility(&secret));
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn has_text_reads_slotted_content() {
+    use crate::query::{HasText, Query, QueryOptions};
+    use gloo::utils::{body, document};
+    use web_sys::{ShadowRootInit, ShadowRootMode};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<greeting-card>World<b slot="footer">Bye</b></greeting-card>
+        <greeting-card></greeting-card>
+        <greeting-card style="visibility: hidden;">Ghost</greeting-card>"#,
+    );
+    let hosts = div.children();
+    for host in (0..hosts.length()).filter_map(|idx| hosts.item(idx)) {
+        let shadow = host
+            .attach_shadow(&ShadowRootInit::new(ShadowRootMode::Open))
+            .unwrap();
+        shadow.set_inner_html(
+            r#"<p>Hello <slot>stranger</slot>!</p>
+            <footer><slot name="footer">See you</slot></footer>"#,
+        );
+    }
+    body().append_child(&div).unwrap();
+
+    let options = QueryOptions {
+        pierce_shadow: true,
+        ..Default::default()
+    };
+    let greetings = div.get_all_with(&HasText("Hello World!"), options);
+    assert_eq!(greetings.len(), 1);
+    assert_eq!(greetings[0].local_name(), "p");
+    assert!(div.get_with(&HasText("Hello stranger"), options).is_some());
+    // Slotted elements are matched themselves, not the slots or hosts rendering them
+    let bye = div.get_all_with(&HasText("Bye"), options);
+    assert_eq!(bye.len(), 1);
+    assert_eq!(bye[0].local_name(), "b");
+    assert_eq!(div.get(&HasText("Bye")).unwrap(), bye[0]);
+    assert_eq!(
+        div.get_with(&HasText("See you"), options)
+            .unwrap()
+            .local_name(),
+        "slot"
+    );
+    assert!(div.get_with(&HasText("Hello Ghost!"), options).is_none());
+    // Text rendered by shadow roots needs piercing them, otherwise hosts have their light DOM text
+    assert!(div.get(&HasText("Hello")).is_none());
+    assert_eq!(
+        div.get(&HasText("World")).unwrap().local_name(),
+        "greeting-card"
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Returns the `value` of `<input>`s displaying it as their label, like `<input type="submit">`.
+fn input_button_value(elem: &HtmlElement) -> Option<String> {
+    let input = html_cast::<HtmlInputElement>(elem, "input")?;
+    matches!(input.type_().as_str(), "button" | "submit" | "reset").then(|| input.value())
+}
+
+/// Returns whether the `visibility` css property hides the text of an element.
+fn visibility_hidden(elem: &Element) -> bool {
+    window()
+        .get_computed_style(elem)
+        .ok()
+        .flatten()
+        .and_then(|style| style.get_property_value("visibility").ok())
+        .is_some_and(|visibility| visibility == "hidden" || visibility == "collapse")
+}
+
+thread_local! {
+    static PIERCING_SHADOW: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Marks matching done by a query that descends into shadow roots, restoring the previous state when dropped.
+///
+/// Light DOM queries don't visit shadow trees, so text matchers read the light DOM text of shadow hosts
+/// there, as it is written in the markup, instead of the content rendered by their shadow roots.
+struct PiercingShadow(bool);
+
+impl PiercingShadow {
+    fn set(options: QueryOptions) -> Self {
+        Self(PIERCING_SHADOW.with(|piercing| piercing.replace(options.pierce_shadow)))
+    }
+
+    fn current() -> bool {
+        PIERCING_SHADOW.with(Cell::get)
+    }
+}
+
+impl Drop for PiercingShadow {
+    fn drop(&mut self) {
+        PIERCING_SHADOW.with(|piercing| piercing.set(self.0));
+    }
+}
+
+/// Returns whether the content rendered by an element depends on a shadow root or slots.
+fn renders_slots(elem: &Element) -> bool {
+    if elem.shadow_root().is_some() {
+        return true;
+    }
+    // Slots do nothing outside of shadow trees
+    let in_shadow = elem.get_root_node().dyn_ref::<ShadowRoot>().is_some();
+    in_shadow
+        && (elem.local_name() == "slot" || elem.query_selector("slot").ok().flatten().is_some())
+}
+
+/// Returns child nodes of an element in the composed tree, that is as they are rendered.
+///
+/// Those are the children of its shadow root for shadow hosts, nodes assigned to a slot
+/// (or its fallback content if none are assigned) for `<slot>`s and regular children otherwise.
+fn composed_child_nodes(elem: &Element) -> Vec<Node> {
+    let nodes = |nodes: NodeList| {
+        (0..nodes.length())
+            .filter_map(|idx| nodes.get(idx))
+            .collect()
+    };
+    if let Some(shadow) = elem.shadow_root() {
+        return nodes(shadow.child_nodes());
+    }
+    if elem.local_name() == "slot" {
+        let options = AssignedNodesOptions::new();
+        options.set_flatten(true);
+        let assigned = elem
+            .unchecked_ref::<HtmlSlotElement>()
+            .assigned_nodes_with_options(&options);
+        if assigned.length() > 0 {
+            return assigned.iter().map(JsCast::unchecked_into).collect();
+        }
+    }
+    nodes(elem.child_nodes())
+}
+
+/// Returns child elements of an element in the composed tree, see [`composed_child_nodes`].
+fn composed_children(elem: &Element) -> Vec<Element> {
+    composed_child_nodes(elem)
+        .into_iter()
+        .filter(|node| node.node_type() == Node::ELEMENT_NODE)
+        .map(JsCast::unchecked_into)
+        .collect()
+}
+
+/// Returns the text of an element as it is rendered, including content of shadow roots and slots.
+///
+/// If `visible` is set, the text of elements not rendering any slots is read with [`inner_text`],
+/// otherwise with [`text_content`]. Whitespace is collapsed.
+///
+/// [`inner_text`]: web_sys::HtmlElement::inner_text
+/// [`text_content`]: web_sys::Node::text_content
+fn composed_text(elem: &Element, visible: bool) -> String {
+    if !renders_slots(elem) {
+        let text = match as_html_element(elem.clone()) {
+            Some(elem) if visible => elem.inner_text(),
+            _ => elem.text_content().unwrap_or_default(),
+        };
+        return normalize_whitespace(&text);
+    }
+    let text = composed_child_nodes(elem)
+        .iter()
+        .map(|node| match node.node_type() {
+            Node::ELEMENT_NODE => composed_text(node.unchecked_ref(), visible),
+            Node::TEXT_NODE => node.text_content().unwrap_or_default(),
+            _ => String::new(),
+        })
+        .collect::<String>();
+    normalize_whitespace(&text)
+}
+
+/// Returns the whole text of an element as it is presented to the user, the text [`HasText`] searches.
+///
+/// See [`composed_text`] for the meaning of `visible`.
+pub(crate) fn displayed_text(elem: &HtmlElement, visible: bool) -> String {
+    input_button_value(elem).unwrap_or_else(|| composed_text(elem, visible))
+}
+
+/// Matches components that have visible text that contains given substring.
+///
+/// [`HasText`] uses [`inner_text`] under the hood and is case-sensitive.
+/// It will match elements by their content as presented for user.
+/// All css rules applies eg. those switching text content, case or visibility.
+/// Remember that for this experience you need to insert an element somewhere into DOM.
+///
+/// Inputs of type `button`, `submit` and `reset` display their `value` instead of the content,
+/// so the `value` is used as their text.
+///
+/// With [`QueryOptions::pierce_shadow`], the text inside web components is read from the composed
+/// tree, the way it is rendered: a `<slot>` contributes the light DOM content assigned to it and
+/// a shadow host contributes its shadow root. Without it, shadow trees aren't visited at all
+/// and shadow hosts are matched by their light DOM text, like any other element.
+///
+/// # Example:
+///
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<div>
+///         <button>I am</button>
+///         <button style="visibility: hidden;">Blue</button>
+///     </div>"#,
+/// );
+/// // Without this line, the last assert will panic as css rules won't be applied.
+/// body().append_child(&div).unwrap();
+///
+/// assert!(div.get(&HasText("I am")).is_some());
+/// assert!(div.get(&HasText("i am")).is_none());
+/// assert!(div.get(&HasText("Blue")).is_none());
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+/// [`inner_text`]: web_sys::HtmlElement::inner_text
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct HasText<S = &'static str>(pub S);
+
+impl<S: AsRef<str>> Matcher for HasText<S> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        // Browsers may still include it in `inner_text`, although it isn't rendered
+        if hidden_until_found(elem) {
+            return false;
+        }
+        if let Some(value) = input_button_value(elem) {
+            return !visibility_hidden(elem) && value.contains(self.0.as_ref());
+        }
+        if PiercingShadow::current() && renders_slots(elem) {
+            let contains = |elem: &Element| composed_text(elem, true).contains(self.0.as_ref());
+            return !visibility_hidden(elem)
+                && contains(elem)
+                && !composed_children(elem).iter().any(contains);
+        }
+        elem.inner_text().contains(self.0.as_ref()) && {
+            let children_len = elem.children().length();
+            !(0..children_len)
+                .filter_map(|n| elem.children().item(n))
+                .filter_map(as_html_element)
+                .any(|child| child.inner_text().contains(self.0.as_ref()))
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("HasText({:?})", self.0.as_ref())
+    }
+
+    fn matches_ignoring_visibility(&self, elem: &HtmlElement) -> bool {
+        if let Some(value) = input_button_value(elem) {
+            return value.contains(self.0.as_ref());
+        }
+        if PiercingShadow::current() && renders_slots(elem) {
+            let contains = |elem: &Element| composed_text(elem, false).contains(self.0.as_ref());
+            return contains(elem) && !composed_children(elem).iter().any(contains);
+        }
+        let contains = |elem: &Element| {
+            elem.text_content()
+                .is_some_and(|text| text.contains(self.0.as_ref()))
+        };
+        contains(elem) && {
+            let children_len = elem.children().length();
+            !(0..children_len)
+                .filter_map(|n| elem.children().item(n))
+                .any(|child| contains(&child))
+        }
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_has_text() {
+    use crate::query::{HasText, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<div>
+            <button>I am</button>
+            <button style="visibility: hidden;">Blue</button>
+        </div>"#,
+    );
+    // Without this line, the last assert will panic as css rules won't be applied.
+    body().append_child(&div).unwrap();
+
+    assert!(div.get(&HasText("I am")).is_some());
+    assert!(div.get(&HasText("i am")).is_none());
+    assert!(div.get(&HasText("Blue")).is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn has_text_matches_input_button_values() {
+    use crate::query::{HasRole, HasText, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<form>
+            <input type="text" value="Save" />
+            <input type="submit" value="Save" />
+            <input type="reset" value="Clear" style="visibility: hidden;" />
+        </form>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let save = div.get(&HasText("Save")).unwrap();
+    assert_eq!(save.get_attribute("type").unwrap(), "submit");
+    assert_eq!(div.get(&HasRole("button").named("Save")).unwrap(), save);
+    assert!(div.get(&HasText("Clear")).is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn matchers_can_own_their_strings() {
+    use crate::query::{HasRole, HasText, Joinable, Matcher, Query};
+    use gloo::utils::{body, document};
+
+    fn remove_button(item: &str) -> impl Matcher {
+        HasRole("button").named(format!("Remove {}", item))
+    }
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<ul>
+            <li>Apples <button>Remove apples</button></li>
+            <li>Pears <button>Remove pears</button></li>
+        </ul>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let matchers = {
+        let item = String::from("Pears");
+        let text = HasText(item.clone()).and(HasRole("listitem"));
+        (remove_button(&item.to_lowercase()), text)
+    };
+    assert_eq!(div.get(&matchers.0).unwrap().inner_text(), "Remove pears");
+    assert!(div
+        .get(&matchers.1)
+        .unwrap()
+        .inner_text()
+        .starts_with("Pears"));
+    assert_eq!(
+        matchers.0.describe(),
+        r#"HasRole("button").named("Remove pears")"#
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Matches components by what looks like their label: their text or, without any, a describing attribute.
+///
+/// The name of an element is its text, read the same way as by [`HasText`]. Elements without
+/// any text fall back to their `title`, then `alt` and then `aria-label` attribute, so an icon
+/// button with only a `title` is matched by it. Unlike [`HasText`], the name has to be equal,
+/// after normalizing whitespace, and elements hidden from the user are never matched.
+/// Of elements named by the same text, only the innermost one is matched.
+///
+/// It is a pragmatic alternative to [`HasRoleWith::named`], which computes the accessible name
+/// following the specification.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<button title="Close"><svg></svg></button>
+///     <button>Save</button>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// assert!(div.get(&HasVisibleName("Close")).is_some());
+/// assert!(div.get(&HasVisibleName("Save")).is_some());
+/// assert!(div.get(&HasText("Close")).is_none());
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct HasVisibleName<S = &'static str>(pub S);
+
+impl<S: AsRef<str>> HasVisibleName<S> {
+    fn matches_name(&self, elem: &HtmlElement, visible: bool) -> bool {
+        let expected = normalize_whitespace(self.0.as_ref());
+        let text = displayed_text(elem, visible);
+        if text.is_empty() {
+            return ["title", "alt", "aria-label"]
+                .into_iter()
+                .filter_map(|attr| elem.get_attribute(attr))
+                .map(|name| normalize_whitespace(&name))
+                .find(|name| !name.is_empty())
+                .is_some_and(|name| name == expected);
+        }
+        let children_len = elem.children().length();
+        text == expected
+            && !(0..children_len)
+                .filter_map(|n| elem.children().item(n))
+                .filter_map(as_html_element)
+                .any(|child| displayed_text(&child, visible) == expected)
+    }
+}
+
+impl<S: AsRef<str>> Matcher for HasVisibleName<S> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        crate::debug::hidden_reason(elem).is_none() && self.matches_name(elem, true)
+    }
+
+    fn describe(&self) -> String {
+        format!("HasVisibleName({:?})", self.0.as_ref())
+    }
+
+    fn matches_ignoring_visibility(&self, elem: &HtmlElement) -> bool {
+        self.matches_name(elem, false)
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_has_visible_name() {
+    use crate::query::{HasText, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<button title="Close"><svg></svg></button>
+        <button>Save</button>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    assert!(div.get(&HasVisibleName("Close")).is_some());
+    assert!(div.get(&HasVisibleName("Save")).is_some());
+    assert!(div.get(&HasText("Close")).is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn has_visible_name_falls_back_to_attributes() {
+    use crate::query::{HasRole, Joinable, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<div class="toolbar">
+            <button title="Delete"><svg><path d="M0 0"></path></svg></button>
+            <button title="Tooltip only" aria-label="Archive"></button>
+            <button aria-label="Share"></button>
+            <img alt="Logo" src="" />
+            <button title="Ignored">Print</button>
+            <button title="Gone" style="display: none;"></button>
+        </div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let delete = div.get(&HasVisibleName("Delete")).unwrap();
+    assert_eq!(delete.get_attribute("title").unwrap(), "Delete");
+    // Attributes are tried in order: title, alt and aria-label
+    assert!(div.get(&HasVisibleName("Tooltip only")).is_some());
+    assert!(div.get(&HasVisibleName("Archive")).is_none());
+    assert!(div
+        .get(&HasVisibleName("Share").and(HasRole("button")))
+        .is_some());
+    assert_eq!(
+        div.get(&HasVisibleName("Logo")).unwrap().local_name(),
+        "img"
+    );
+    // The text takes precedence, and only the innermost element is matched
+    assert!(div.get(&HasVisibleName("Ignored")).is_none());
+    assert_eq!(
+        div.get_all(&HasVisibleName("Print"))[0].local_name(),
+        "button"
+    );
+    assert_eq!(div.get_all(&HasVisibleName("Print")).len(), 1);
+    assert!(div.get(&HasVisibleName("Gone")).is_none());
+    let gone = div.query_selector("[title=Gone]").unwrap().unwrap();
+    assert!(HasVisibleName("Gone").matches_ignoring_visibility(gone.unchecked_ref()));
+
+    body().remove_child(&div).unwrap();
+}
+
+/// A bare string matches the same elements as [`HasText`] with it, that is by their visible text.
+///
+/// It is a shorthand for the most common query, which also works inside joined matchers.
+/// Note that it never matches labels, roles or any other attributes, only the text.
+///
+/// # Example:
+///
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<div>
+///         <button>I am</button>
+///         <a href="/blue" style="visibility: hidden;">Blue</a>
+///     </div>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// assert!(div.get("I am").is_some());
+/// assert!(div.get(&HasRole("button").and("I am")).is_some());
+/// assert!(div.get("Blue").is_none());
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+impl Matcher for str {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        HasText(self).matches(elem)
+    }
+
+    fn describe(&self) -> String {
+        format!("{:?}", self)
+    }
+
+    fn matches_ignoring_visibility(&self, elem: &HtmlElement) -> bool {
+        HasText(self).matches_ignoring_visibility(elem)
+    }
+}
+
+/// Same as the [`Matcher`] for [`str`], matching elements by their visible text.
+impl Matcher for String {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        HasText(self).matches(elem)
+    }
+
+    fn describe(&self) -> String {
+        self.as_str().describe()
+    }
+
+    fn matches_ignoring_visibility(&self, elem: &HtmlElement) -> bool {
+        HasText(self).matches_ignoring_visibility(elem)
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_str_matcher() {
+    use crate::query::{HasRole, Joinable, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<div>
+            <button>I am</button>
+            <a href="/blue" style="visibility: hidden;">Blue</a>
+        </div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    assert!(div.get("I am").is_some());
+    assert!(div.get(&HasRole("button").and("I am")).is_some());
+    assert!(div.get("Blue").is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn str_matcher_is_the_same_as_has_text() {
+    use crate::query::{HasLabel, HasRole, HasText, Joinable, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<label for="name">Name</label>
+        <input id="name" />
+        <button>Save</button>
+        <a href="/save">Save</a>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    assert_eq!(div.get_all("Save"), div.get_all(&HasText("Save")));
+    let text = String::from("Save");
+    assert_eq!(div.get_all(&text).len(), 2);
+    let link = HasRole("link").and(text.clone());
+    assert_eq!(link.describe(), r#"HasRole("link").and("Save")"#);
+    assert_eq!(div.get(&link).unwrap().local_name(), "a");
+    // Bare strings don't match labels
+    assert!(div.get(&HasLabel("Name")).is_some());
+    assert!(div.get(&(HasRole("textbox"), "Name")).is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Matches components that have given aria role.
+///
+/// This is by far the best method for finding components as it searches for elements in the [`accessibility tree`].
+/// You should always prefer something like `.get(&HasRole("button").and(HasText("Add")))` over the alternavies.
+/// Matches roles assigned with the `role` attribute as well as implicit roles of semantic elements,
+/// eg. `heading` of `<h1>` or `checkbox` of `<input type="checkbox">`. The supported implicit roles,
+/// including those of custom elements registered with [`register_custom_role`], are listed
+/// in [`element_to_aria_roles`]. Other elements, like `<section>` or `<input type="range">`,
+/// are only matched by an explicit `role`. Roles aren't deduced from any of [`aria_attribute_types`].
+///
+/// # Example:
+///
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<div>
+///         <button>Rust</button>
+///         <input type="button">Is</input>
+///         <div role="button">Fun</input>
+///     </div>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// assert_eq!(div.get_all(&HasRole("button")).len(), 3);
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+/// [`accessibility tree`]: https://developer.mozilla.org/en-US/docs/Glossary/Accessibility_tree
+/// [`aria_attribute_types`]: https://developer.mozilla.org/en-US/docs/Web/Accessibility/ARIA/Attributes#aria_attribute_types
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct HasRole<S = &'static str>(pub S);
+
+impl<S: AsRef<str>> Matcher for HasRole<S> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        if element_to_aria_roles(elem).contains(&self.0.as_ref()) {
+            true
+        } else if let Some(role) = elem.get_attribute("role") {
+            role == self.0.as_ref()
+        } else {
+            false
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("HasRole({:?})", self.0.as_ref())
+    }
+
+    fn selector_hint(&self) -> Option<String> {
+        let role = self.0.as_ref();
+        let selectors = [
+            implicit_role_selector(role).map(String::from),
+            custom_role_selector(role),
+            Some(format!("[role={}]", css_string(role))),
+        ];
+        Some(
+            selectors
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(", "),
+        )
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_has_role() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<div>
+            <button>Rust</button>
+            <input type="button">Is</input>
+            <div role="button">Fun</input>
+        </div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    assert_eq!(div.get_all(&HasRole("button")).len(), 3);
+
+    body().remove_child(&div).unwrap();
+}
+
+impl<S: AsRef<str>> HasRole<S> {
+    /// Creates a matcher for a given aria role.
+    ///
+    /// Equivalent to `HasRole(role)`, but reads better when followed by options like [`named`].
+    ///
+    /// [`named`]: HasRole::named
+    pub fn new(role: S) -> Self {
+        Self(role)
+    }
+
+    /// Additionally require the element to have given [`accessible_name`].
+    pub fn named<N: AsRef<str>>(self, name: N) -> HasRoleWith<S, N> {
+        HasRoleWith::<S>::from(self).named(name)
+    }
+
+    /// Additionally require the element to be checked or not, see [`HasRoleWith::checked`].
+    pub fn checked(self, checked: bool) -> HasRoleWith<S> {
+        HasRoleWith::from(self).checked(checked)
+    }
+
+    /// Additionally require the element to be pressed or not, see [`HasRoleWith::pressed`].
+    pub fn pressed(self, pressed: bool) -> HasRoleWith<S> {
+        HasRoleWith::from(self).pressed(pressed)
+    }
+
+    /// Additionally require the element to be expanded or not, see [`HasRoleWith::expanded`].
+    pub fn expanded(self, expanded: bool) -> HasRoleWith<S> {
+        HasRoleWith::from(self).expanded(expanded)
+    }
+
+    /// Additionally require the element to be at given level, see [`HasRoleWith::level`].
+    pub fn level(self, level: u32) -> HasRoleWith<S> {
+        HasRoleWith::from(self).level(level)
+    }
+}
+
+/// Matches components that have given aria role and satisfy additional options.
+///
+/// Created by calling one of option methods on [`HasRole`], eg. [`HasRole::named`].
+///
+/// # Example:
+///
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<div>
+///         <span role="img" aria-label="happy">😀</span>
+///         <span role="img" aria-label="sad">😢</span>
+///         <button>Add</button>
+///         <button aria-label="Remove">-</button>
+///     </div>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// assert!(div.get(&HasRole::new("img").named("happy")).is_some());
+/// assert!(div.get(&HasRole("button").named("Remove")).is_some());
+/// assert!(div.get(&HasRole("button").named("-")).is_none());
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct HasRoleWith<R = &'static str, N = &'static str> {
+    role: R,
+    name: Option<N>,
+    checked: Option<bool>,
+    pressed: Option<bool>,
+    expanded: Option<bool>,
+    level: Option<u32>,
+}
+
+impl<R: AsRef<str>, N: AsRef<str>> HasRoleWith<R, N> {
+    /// Require the element to have given [`accessible_name`].
+    ///
+    /// The name has to match exactly, after normalizing whitespace.
+    pub fn named<M: AsRef<str>>(self, name: M) -> HasRoleWith<R, M> {
+        HasRoleWith {
+            role: self.role,
+            name: Some(name),
+            checked: self.checked,
+            pressed: self.pressed,
+            expanded: self.expanded,
+            level: self.level,
+        }
+    }
+
+    /// Require the element to be checked, or not checked.
+    ///
+    /// The state is read from the `checked` property of checkboxes and radio buttons,
+    /// and from the `aria-checked` attribute of other elements. Elements without the state,
+    /// or with a mixed one, like indeterminate checkboxes, are never matched.
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.checked = Some(checked);
+        self
+    }
+
+    /// Require the element to be pressed, or not pressed, according to its `aria-pressed` attribute.
+    ///
+    /// Elements without the attribute, or with a mixed state, are never matched.
+    pub fn pressed(mut self, pressed: bool) -> Self {
+        self.pressed = Some(pressed);
+        self
+    }
+
+    /// Require the element to be expanded, or collapsed, according to its `aria-expanded` attribute.
+    ///
+    /// Elements without the attribute are never matched.
+    pub fn expanded(mut self, expanded: bool) -> Self {
+        self.expanded = Some(expanded);
+        self
+    }
+
+    /// Require the element to be at given level, eg. a heading of the second level.
+    ///
+    /// The level is read from the `aria-level` attribute, or from the tag of headings `<h1>`-`<h6>`.
+    /// Elements without a level are never matched.
+    pub fn level(mut self, level: u32) -> Self {
+        self.level = Some(level);
+        self
+    }
+}
+
+impl<R, N> From<HasRole<R>> for HasRoleWith<R, N> {
+    fn from(role: HasRole<R>) -> Self {
+        Self {
+            role: role.0,
+            name: None,
+            checked: None,
+            pressed: None,
+            expanded: None,
+            level: None,
+        }
+    }
+}
+
+/// Returns the value of a boolean aria state, or [`None`] if it isn't `"true"` nor `"false"`.
+fn aria_state(elem: &HtmlElement, attribute: &str) -> Option<bool> {
+    match elem.get_attribute(attribute)?.trim() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Returns the hierarchical level of the element, from `aria-level` or the tag of a heading.
+fn level(elem: &HtmlElement) -> Option<u32> {
+    let aria_level = elem
+        .get_attribute("aria-level")
+        .and_then(|level| level.trim().parse().ok())
+        .filter(|&level| level > 0);
+    aria_level.or_else(|| match elem.local_name().as_str() {
+        "h1" => Some(1),
+        "h2" => Some(2),
+        "h3" => Some(3),
+        "h4" => Some(4),
+        "h5" => Some(5),
+        "h6" => Some(6),
+        _ => None,
+    })
+}
+
+/// Returns whether the element is checked, or [`None`] if it can't be or its state is mixed.
+pub(crate) fn checked_state(elem: &HtmlElement) -> Option<bool> {
+    match html_cast::<HtmlInputElement>(elem, "input") {
+        Some(input) if matches!(input.type_().as_str(), "checkbox" | "radio") => {
+            (!input.indeterminate()).then(|| input.checked())
+        }
+        _ => aria_state(elem, "aria-checked"),
+    }
+}
+
+impl<R: AsRef<str>, N: AsRef<str>> Matcher for HasRoleWith<R, N> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        let states = [
+            (
+                self.checked,
+                checked_state as fn(&HtmlElement) -> Option<bool>,
+            ),
+            (self.pressed, |elem| aria_state(elem, "aria-pressed")),
+            (self.expanded, |elem| aria_state(elem, "aria-expanded")),
+        ];
+        HasRole(self.role.as_ref()).matches(elem)
+            && self
+                .level
+                .is_none_or(|expected| level(elem) == Some(expected))
+            && states.into_iter().all(|(expected, state)| {
+                expected.is_none_or(|expected| state(elem) == Some(expected))
+            })
+            && self
+                .name
+                .as_ref()
+                .is_none_or(|name| accessible_name(elem) == normalize_whitespace(name.as_ref()))
+    }
+
+    fn describe(&self) -> String {
+        let mut description = HasRole(self.role.as_ref()).describe();
+        if let Some(name) = &self.name {
+            description.push_str(&format!(".named({:?})", name.as_ref()));
+        }
+        let states = [
+            ("checked", self.checked),
+            ("pressed", self.pressed),
+            ("expanded", self.expanded),
+        ];
+        for (state, value) in states {
+            if let Some(value) = value {
+                description.push_str(&format!(".{}({})", state, value));
+            }
+        }
+        if let Some(level) = self.level {
+            description.push_str(&format!(".level({})", level));
+        }
+        description
+    }
+
+    fn selector_hint(&self) -> Option<String> {
+        HasRole(self.role.as_ref()).selector_hint()
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_has_role_with() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<div>
+            <span role="img" aria-label="happy">😀</span>
+            <span role="img" aria-label="sad">😢</span>
+            <button>Add</button>
+            <button aria-label="Remove">-</button>
+        </div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    assert!(div.get(&HasRole::new("img").named("happy")).is_some());
+    assert!(div.get(&HasRole("button").named("Remove")).is_some());
+    assert!(div.get(&HasRole("button").named("-")).is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn has_role_with_selects_by_state() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<div>
+            <input type="checkbox" aria-label="Apples" />
+            <input type="checkbox" aria-label="Pears" checked />
+            <div role="checkbox" aria-checked="true">Plums</div>
+            <div role="checkbox" aria-checked="mixed">All</div>
+            <button aria-expanded="false">Details</button>
+            <button aria-expanded="true">Shipping</button>
+            <button aria-pressed="true">Bold</button>
+            <button>Cancel</button>
+        </div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let checked = div.get_all(&HasRole::new("checkbox").checked(true));
+    let checked = checked
+        .iter()
+        .map(|e| e.text_content().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(checked, ["", "Plums"]);
+    assert!(div
+        .get(&HasRole("checkbox").checked(true).named("Pears"))
+        .is_some());
+    assert!(div
+        .get(&HasRole("checkbox").named("Apples").checked(false))
+        .is_some());
+    // The native state is read, not the attribute
+    let apples = div.get(&HasRole("checkbox").named("Apples")).unwrap();
+    apples.click();
+    assert_eq!(div.get_all(&HasRole("checkbox").checked(true)).len(), 3);
+    assert!(div.get(&HasRole("checkbox").checked(false)).is_none());
+
+    let expanded = div.get(&HasRole("button").expanded(true)).unwrap();
+    assert_eq!(expanded.inner_text(), "Shipping");
+    assert_eq!(
+        div.get(&HasRole("button").expanded(false))
+            .unwrap()
+            .inner_text(),
+        "Details"
+    );
+    assert_eq!(
+        div.get(&HasRole("button").pressed(true))
+            .unwrap()
+            .inner_text(),
+        "Bold"
+    );
+    // Buttons without the state are never matched
+    assert!(div.get(&HasRole("button").pressed(false)).is_none());
+    assert_eq!(
+        HasRole("button")
+            .named("Details")
+            .expanded(false)
+            .describe(),
+        "HasRole(\"button\").named(\"Details\").expanded(false)"
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn has_role_with_selects_by_level() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<div>
+            <h1>Shop</h1>
+            <h2>Fruits</h2>
+            <div role="heading" aria-level="2">Vegetables</div>
+            <h2 aria-level="3">Roots</h2>
+            <div role="heading">Other</div>
+        </div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let second = div.get_all(&HasRole("heading").level(2));
+    let second = second.iter().map(|e| e.inner_text()).collect::<Vec<_>>();
+    assert_eq!(second, ["Fruits", "Vegetables"]);
+    // aria-level takes precedence over the tag
+    assert!(div
+        .get(&HasRole("heading").named("Roots").level(3))
+        .is_some());
+    assert_eq!(
+        div.get(&HasRole("heading").level(1)).unwrap().inner_text(),
+        "Shop"
+    );
+    assert!(div
+        .get(&HasRole("heading").named("Other").level(2))
+        .is_none());
+    assert_eq!(
+        HasRole("heading").level(2).named("Fruits").describe(),
+        "HasRole(\"heading\").named(\"Fruits\").level(2)"
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Matches components that have given label.
+///
+/// This is also a great method for interacting with DOM in the way as a user would.
+/// Labels are not only visually connected with elements but also programatically.
+/// Screen readers will read out the labels when given component is selected as well as
+/// clicking on a label results in selecting labeled component.
+///
+/// [`Labeling'] is supported for input elements (except type="hidden"), button, meter,
+/// output, progress, select and text area.
+///
+/// # Example:
+///
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<div>
+///         <!-- for id attributes -->
+///         <label for="best-language">Type rust</label>
+///         <input id="best-language" />
+///
+///         <!-- implicit labels -->
+///         <label>Type rust <meter /></label>
+///
+///         <!-- wrapped implicit labels -->
+///         <label>
+///           <span>Type rust</span>
+///           <button />
+///         </label>
+///
+///         <!-- aria-labelledby attributes -->
+///         <label id="best-language">Type rust</label>
+///         <input aria-labelledby="best-language" />
+///
+///         <!-- aria-label attributes are not supported as they are not visible to user -->
+///         <input aria-label="Type rust" />
+///     </div>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// assert_eq!(div.get_all(&HasLabel("Type rust")).len(), 4);
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+/// [`Labeling`]: https://developer.mozilla.org/en-US/docs/Web/HTML/Element/label
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct HasLabel<S = &'static str>(pub S);
+
+impl<S: AsRef<str>> Matcher for HasLabel<S> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        let labels = match labels_of(elem) {
+            Some(labels) => labels,
+            None => return false,
+        };
+        // Check if element is labeled by requested label
+        if (0..labels.length())
+            .filter_map(|idx| labels.get(idx))
+            .any(|label| label.text_content().as_deref() == Some(self.0.as_ref()))
+        {
+            return true;
+        }
+        // Check if element is implicitly wrapped with label
+        if let Some(parent) = elem.parent_element() {
+            if parent.local_name() == "label" {
+                let child_nodes = parent.child_nodes();
+                if (0..child_nodes.length())
+                    .filter_map(|idx| child_nodes.get(idx))
+                    .filter(|child| !child.is_same_node(Some(elem)))
+                    .any(|child| {
+                        child.text_content().as_deref().map(str::trim) == Some(self.0.as_ref())
+                    })
+                {
+                    return true;
+                }
+            }
+        }
+        // Check if element is aria-labelledby a label
+        if let Some(label) = elem.get_attribute("aria-labelledby") {
+            if owner_document(elem).get_element_by_id(&label).is_some() {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn describe(&self) -> String {
+        format!("HasLabel({:?})", self.0.as_ref())
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_has_label() {
+    use crate::query::{HasLabel, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<div>
+            <!-- for id attributes -->
+            <label for="best-language">Type rust</label>
+            <input id="best-language" />
+
+            <!-- implicit labels -->
+            <label>Type rust <meter /></label>
+
+            <!-- wrapped implicit labels -->
+            <label>
+              <span>Type rust</span>
+              <button />
+            </label>
+
+            <!-- aria-labelledby attributes -->
+            <label id="best-language">Type rust</label>
+            <input aria-labelledby="best-language" />
+
+            <!-- aria-label attributes are not supported as they are not visible to user -->
+            <input aria-label="Type rust" />
+        </div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    assert_eq!(div.get_all(&HasLabel("Type rust")).len(), 4);
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Returns the first `<label>` associated with a [`labelable`] element.
+///
+/// The label can be associated explicitly, with its `for` attribute set to the `id` of the element,
+/// or implicitly, by wrapping the element. Labels referenced with `aria-labelledby` are not returned.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+/// use frontest::query::label_for;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<label for="name">Name</label>
+///     <input id="name" type="text" />"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// let input = div.get(&HasRole("textbox")).unwrap();
+/// assert_eq!(label_for(&input).unwrap().inner_text(), "Name");
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+///
+/// [`labelable`]: https://developer.mozilla.org/en-US/docs/Web/HTML/Element/label
+pub fn label_for(elem: &HtmlElement) -> Option<HtmlElement> {
+    labels_of(elem)?.get(0)?.dyn_into().ok()
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_label_for() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<label for="name">Name</label>
+        <input id="name" type="text" />"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let input = div.get(&HasRole("textbox")).unwrap();
+    assert_eq!(label_for(&input).unwrap().inner_text(), "Name");
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Returns the element labeled by a `<label>`, the inverse of [`label_for`].
+///
+/// That is the element referenced by the `for` attribute, or if there is none,
+/// the first [`labelable`] element wrapped by the label.
+///
+/// [`labelable`]: https://developer.mozilla.org/en-US/docs/Web/HTML/Element/label
+pub fn control_for(label: &HtmlLabelElement) -> Option<HtmlElement> {
+    label.control()
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn label_for_and_control_for_resolve_both_associations() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<label for="email">Email</label>
+        <input id="email" type="email" />
+        <label>Subscribe <input type="checkbox" /></label>
+        <label id="notes">Notes</label>
+        <textarea aria-labelledby="notes"></textarea>
+        <label>Unrelated</label>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let labels = div.get_all(&MatchesSelector("label"));
+    // Email inputs have no implicit role, and the textarea is a textbox too
+    let email = div.get(&MatchesSelector("#email")).unwrap();
+    let checkbox = div.get(&HasRole("checkbox")).unwrap();
+    let notes = div.get(&MatchesSelector("textarea")).unwrap();
+
+    // explicit, for/id
+    assert_eq!(label_for(&email).as_ref(), Some(&labels[0]));
+    assert_eq!(
+        control_for(labels[0].unchecked_ref()).as_ref(),
+        Some(&email)
+    );
+    // implicit, wrapped
+    assert_eq!(label_for(&checkbox).as_ref(), Some(&labels[1]));
+    assert_eq!(
+        control_for(labels[1].unchecked_ref()).as_ref(),
+        Some(&checkbox)
+    );
+    // aria-labelledby is not a label association
+    assert!(label_for(&notes).is_none());
+    assert!(control_for(labels[2].unchecked_ref()).is_none());
+    assert!(control_for(labels[3].unchecked_ref()).is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Matches components that have given placeholder text.
+///
+/// Placeholders are not a substitute for labels. If placeholder is the only identifier
+/// for an input, any assistive technology will not be able to identify them.
+/// It is still a better fallback than just using [`HasText`] for accessible elements.
+///
+/// # Example:
+///
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<div>
+///         <button>tests rocks</button>
+///         <input placeholder="tests rocks" />
+///     </div>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// assert_eq!(
+///     div.get(&HasPlaceholder("tests")).unwrap().tag_name(),
+///     "INPUT"
+/// );
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct HasPlaceholder<S = &'static str>(pub S);
+
+impl<S: AsRef<str>> Matcher for HasPlaceholder<S> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        let placeholder = if let Some(elem) = html_cast::<HtmlInputElement>(elem, "input") {
+            elem.placeholder()
+        } else if let Some(elem) = html_cast::<HtmlTextAreaElement>(elem, "textarea") {
+            elem.placeholder()
+        } else {
+            return false;
+        };
+        placeholder.contains(self.0.as_ref())
+    }
+
+    fn describe(&self) -> String {
+        format!("HasPlaceholder({:?})", self.0.as_ref())
+    }
+
+    fn selector_hint(&self) -> Option<String> {
+        Some(String::from("input, textarea"))
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_has_placeholder() {
+    use crate::query::{HasPlaceholder, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<div>
+            <button>tests rocks</button>
+            <input placeholder="tests rocks" />
+        </div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    assert_eq!(
+        div.get(&HasPlaceholder("tests")).unwrap().tag_name(),
+        "INPUT"
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Matches `<fieldset>` elements whose legend has given text.
+///
+/// The legend of a fieldset is its first `<legend>` child. Its text is compared exactly,
+/// after normalizing whitespace. Legends group related form controls the way labels name
+/// single ones, so it allows scoping queries to a section of a form with [`within`].
+///
+/// # Example:
+///
+/// ```no_run
+/// use frontest::prelude::*;
+///
+/// let mount = mount_html(
+///     r#"<fieldset>
+///         <legend>Billing</legend>
+///         <label>City <input type="text" /></label>
+///     </fieldset>
+///     <fieldset>
+///         <legend>Shipping</legend>
+///         <label>City <input type="text" /></label>
+///     </fieldset>"#,
+/// );
+///
+/// let shipping = mount.get(&HasLegend("Shipping")).unwrap();
+/// assert!(within(&shipping).get(&HasLabel("City")).is_some());
+/// ```
+///
+/// [`within`]: crate::within
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct HasLegend<S = &'static str>(pub S);
+
+impl<S: AsRef<str>> Matcher for HasLegend<S> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        if elem.local_name() != "fieldset" {
+            return false;
+        }
+        let children = elem.children();
+        let legend = (0..children.length())
+            .filter_map(|idx| children.item(idx))
+            .find(|child| child.local_name() == "legend");
+        legend.is_some_and(|legend| {
+            normalize_whitespace(&legend.text_content().unwrap_or_default())
+                == normalize_whitespace(self.0.as_ref())
+        })
+    }
+
+    fn describe(&self) -> String {
+        format!("HasLegend({:?})", self.0.as_ref())
+    }
+
+    fn selector_hint(&self) -> Option<String> {
+        Some(String::from("fieldset"))
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_has_legend() {
+    use crate::query::{HasLabel, HasLegend, Query};
+    use crate::{cleanup, mount_html, within};
+
+    let mount = mount_html(
+        r#"<fieldset>
+            <legend>Billing</legend>
+            <label>City <input type="text" /></label>
+        </fieldset>
+        <fieldset>
+            <legend>Shipping</legend>
+            <label>City <input type="text" /></label>
+        </fieldset>"#,
+    );
+
+    let shipping = mount.get(&HasLegend("Shipping")).unwrap();
+    assert!(within(&shipping).get(&HasLabel("City")).is_some());
+
+    cleanup();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn has_legend_reads_the_first_legend_child() {
+    use crate::query::{HasLabel, HasLegend, Query};
+    use crate::{cleanup, mount_html, within};
+
+    let mount = mount_html(
+        r#"<fieldset>
+            <legend>
+                Contact
+                details
+            </legend>
+            <label>Email <input type="email" value="me@example.com" /></label>
+            <fieldset>
+                <legend>Address</legend>
+                <label>Street <input type="text" value="Main" /></label>
+            </fieldset>
+        </fieldset>
+        <fieldset>
+            <div><legend>Address</legend></div>
+            <legend>Payment</legend>
+            <legend>Address</legend>
+            <label>Street <input type="text" value="Bank" /></label>
+        </fieldset>
+        <section><legend>Address</legend></section>"#,
+    );
+
+    let contact = mount.get(&HasLegend("Contact details")).unwrap();
+    assert!(within(&contact).get(&HasLabel("Email")).is_some());
+    // Only the nested fieldset, not the one with a later or deeper legend, nor the section
+    let address = mount.get(&HasLegend("Address")).unwrap();
+    let street = within(&address).get_input(&HasLabel("Street")).unwrap();
+    assert_eq!(street.value(), "Main");
+    assert!(mount.get(&HasLegend("Payment")).is_some());
+    assert!(mount.get(&HasLegend("Contact")).is_none());
+    assert_eq!(
+        HasLegend(String::from("Payment")).describe(),
+        r#"HasLegend("Payment")"#
+    );
+
+    cleanup();
+}
+
+/// Matches `<select>` elements with a selected option of given text.
+///
+/// The text of an option is compared exactly, after normalizing whitespace. All selected options
+/// of a `<select multiple>` are checked. Options inside `<optgroup>`s are matched too and
+/// [`HasSelectedOption::in_group`] additionally requires the `label` of the group.
+///
+/// # Example:
+///
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<select>
+///         <optgroup label="Fruits">
+///             <option>Apple</option>
+///             <option selected>Orange</option>
+///         </optgroup>
+///         <optgroup label="Colors">
+///             <option>Orange</option>
+///         </optgroup>
+///     </select>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// assert!(div.get(&HasSelectedOption::new("Orange")).is_some());
+/// assert!(div.get(&HasSelectedOption::in_group("Fruits", "Orange")).is_some());
+/// assert!(div.get(&HasSelectedOption::in_group("Colors", "Orange")).is_none());
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct HasSelectedOption<S = &'static str> {
+    group: Option<S>,
+    option: S,
+}
+
+impl<S: AsRef<str>> HasSelectedOption<S> {
+    /// Matches selects with a selected option of given text, in any group.
+    pub fn new(option: S) -> Self {
+        Self {
+            group: None,
+            option,
+        }
+    }
+
+    /// Matches selects with a selected option of given text, inside an `<optgroup>` with given label.
+    pub fn in_group(group: S, option: S) -> Self {
+        Self {
+            group: Some(group),
+            option,
+        }
+    }
+}
+
+impl<S: AsRef<str>> Matcher for HasSelectedOption<S> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        let Some(select) = html_cast::<HtmlSelectElement>(elem, "select") else {
+            return false;
+        };
+        let selected = select.selected_options();
+        (0..selected.length())
+            .filter_map(|idx| selected.item(idx))
+            .any(|option| {
+                let text = normalize_whitespace(&option.text_content().unwrap_or_default());
+                text == normalize_whitespace(self.option.as_ref())
+                    && self.group.as_ref().is_none_or(|group| {
+                        let label = option
+                            .parent_element()
+                            .filter(|parent| parent.local_name() == "optgroup")
+                            .and_then(|group| group.get_attribute("label"));
+                        label.is_some_and(|label| label == group.as_ref())
+                    })
+            })
+    }
+
+    fn describe(&self) -> String {
+        match &self.group {
+            Some(group) => format!(
+                "HasSelectedOption::in_group({:?}, {:?})",
+                group.as_ref(),
+                self.option.as_ref()
+            ),
+            None => format!("HasSelectedOption::new({:?})", self.option.as_ref()),
+        }
+    }
+
+    fn selector_hint(&self) -> Option<String> {
+        Some(String::from("select"))
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_has_selected_option() {
+    use crate::query::{HasSelectedOption, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<select>
+            <optgroup label="Fruits">
+                <option>Apple</option>
+                <option selected>Orange</option>
+            </optgroup>
+            <optgroup label="Colors">
+                <option>Orange</option>
+            </optgroup>
+        </select>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    assert!(div.get(&HasSelectedOption::new("Orange")).is_some());
+    assert!(div
+        .get(&HasSelectedOption::in_group("Fruits", "Orange"))
+        .is_some());
+    assert!(div
+        .get(&HasSelectedOption::in_group("Colors", "Orange"))
+        .is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn has_selected_option_follows_selection_changes() {
+    use crate::query::{HasLabel, HasSelectedOption, Joinable, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<label for="fruit">Fruit</label>
+        <select id="fruit">
+            <option>Plum</option>
+            <optgroup label="Citrus">
+                <option>  Lemon </option>
+                <option>Lime</option>
+            </optgroup>
+        </select>
+        <label for="toppings">Toppings</label>
+        <select id="toppings" multiple>
+            <option selected>Nuts</option>
+            <option selected>Honey</option>
+        </select>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    // The first option is selected by default
+    let fruit = HasLabel("Fruit");
+    assert!(div
+        .get(&fruit.and(HasSelectedOption::new("Plum")))
+        .is_some());
+    assert!(div
+        .get(&HasSelectedOption::in_group("Citrus", "Plum"))
+        .is_none());
+
+    let select = div.get(&fruit).unwrap();
+    select
+        .unchecked_ref::<HtmlSelectElement>()
+        .set_value("Lemon");
+    assert!(div
+        .get(&fruit.and(HasSelectedOption::in_group("Citrus", "Lemon")))
+        .is_some());
+    assert!(div.get(&HasSelectedOption::new("Plum")).is_none());
+
+    assert_eq!(div.get_all(&HasSelectedOption::new("Honey")).len(), 1);
+    assert_eq!(
+        HasSelectedOption::in_group("Citrus", "Lime").describe(),
+        r#"HasSelectedOption::in_group("Citrus", "Lime")"#
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Matches components that have a `data-*` attribute with a given name prefix.
+///
+/// Design systems often encode state in multiple `data-*` attributes. This matcher allows
+/// finding elements carrying any of them, regardless of their values.
+///
+/// # Example:
+///
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<div>
+///         <button data-state-open="true">Menu</button>
+///         <button data-variant="primary">Save</button>
+///     </div>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// let menu = div.get(&HasDataAttribute::any_with_prefix("data-state")).unwrap();
+/// assert_eq!(menu.inner_text(), "Menu");
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct HasDataAttribute<S = &'static str> {
+    prefix: S,
+}
+
+impl<S: AsRef<str>> HasDataAttribute<S> {
+    /// Matches elements having at least one attribute which name starts with `prefix`.
+    pub fn any_with_prefix(prefix: S) -> Self {
+        Self { prefix }
+    }
+}
+
+impl<S: AsRef<str>> Matcher for HasDataAttribute<S> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        let attributes = elem.attributes();
+        (0..attributes.length())
+            .filter_map(|idx| attributes.item(idx))
+            .any(|attr| attr.name().starts_with(self.prefix.as_ref()))
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "HasDataAttribute::any_with_prefix({:?})",
+            self.prefix.as_ref()
+        )
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_has_data_attribute() {
+    use crate::query::{HasDataAttribute, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<div>
+            <button data-state-open="true">Menu</button>
+            <button data-variant="primary">Save</button>
+        </div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let menu = div
+        .get(&HasDataAttribute::any_with_prefix("data-state"))
+        .unwrap();
+    assert_eq!(menu.inner_text(), "Menu");
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Matches components with given `data-testid` attribute.
+///
+/// Test ids aren't visible to the user in any way, so this matcher should only be used
+/// when there is no other way to find an element, eg. for a chart drawn on a canvas.
+///
+/// # Example:
+///
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<canvas data-testid="sales-chart"></canvas>"#);
+/// body().append_child(&div).unwrap();
+///
+/// assert!(div.get(&HasTestId("sales-chart")).is_some());
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct HasTestId<S = &'static str>(pub S);
+
+impl<S: AsRef<str>> Matcher for HasTestId<S> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        elem.get_attribute("data-testid").as_deref() == Some(self.0.as_ref())
+    }
+
+    fn describe(&self) -> String {
+        format!("HasTestId({:?})", self.0.as_ref())
+    }
+
+    fn selector_hint(&self) -> Option<String> {
+        Some(String::from("[data-testid]"))
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_has_test_id() {
+    use crate::query::{HasTestId, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<canvas data-testid="sales-chart"></canvas>"#);
+    body().append_child(&div).unwrap();
+
+    assert!(div.get(&HasTestId("sales-chart")).is_some());
+    assert!(div.get(&HasTestId("sales")).is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Matches components satisfying a given css selector.
+///
+/// Delegates to [`Element::matches`], so the full selector engine of the browser can be used,
+/// including pseudo-classes describing states computed by the browser, like `:checked`,
+/// `:disabled`, `:valid` or `:focus-visible`. It is a powerful escape hatch, however
+/// accessible matchers like [`HasRole`] or [`HasLabel`] should be preferred where possible.
+///
+/// # Panics:
+/// If the selector is invalid.
+///
+/// # Example:
+///
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<div>
+///         <input type="checkbox" checked />
+///         <input type="checkbox" />
+///         <button disabled>Save</button>
+///     </div>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// assert!(div.get(&MatchesSelector(":checked")).is_some());
+/// assert_eq!(div.get_all(&MatchesSelector("input:not(:checked)")).len(), 1);
+/// assert!(div.get(&MatchesSelector(":disabled").and(HasText("Save"))).is_some());
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct MatchesSelector<S = &'static str>(pub S);
+
+impl<S: AsRef<str>> Matcher for MatchesSelector<S> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        elem.matches(self.0.as_ref())
+            .unwrap_or_else(|_| panic!("Invalid selector: {:?}", self.0.as_ref()))
+    }
+
+    fn describe(&self) -> String {
+        format!("MatchesSelector({:?})", self.0.as_ref())
+    }
+
+    fn selector_hint(&self) -> Option<String> {
+        Some(String::from(self.0.as_ref()))
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_matches_selector() {
+    use crate::query::{HasText, Joinable, MatchesSelector, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<div>
+            <input type="checkbox" checked />
+            <input type="checkbox" />
+            <button disabled>Save</button>
+        </div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    assert!(div.get(&MatchesSelector(":checked")).is_some());
+    assert_eq!(
+        div.get_all(&MatchesSelector("input:not(:checked)")).len(),
+        1
+    );
+    assert!(div
+        .get(&MatchesSelector(":disabled").and(HasText("Save")))
+        .is_some());
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Matches components selected by a given XPath expression.
+///
+/// It is meant for users coming from Selenium and for structural queries which can't be expressed
+/// with css, like selecting by text of a sibling. Accessible matchers like [`HasRole`] or [`HasLabel`]
+/// should be preferred where possible, as XPaths are tied to the markup rather than to what the user sees.
+///
+/// Matchers check elements one by one, so the expression is evaluated from the root node of each
+/// element, ie. the document, a shadow root or the top of a detached subtree. It is evaluated
+/// for every checked element, which makes it slow for large subtrees.
+///
+/// # Panics:
+/// If the expression is invalid.
+///
+/// # Example:
+///
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<dl>
+///         <dt>Name</dt><dd>Alice</dd>
+///         <dt>Role</dt><dd>Admin</dd>
+///     </dl>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// let role = div.get(&HasXPath("//dt[text()='Role']/following-sibling::dd[1]"));
+/// assert_eq!(role.unwrap().inner_text(), "Admin");
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct HasXPath<S = &'static str>(pub S);
+
+impl<S: AsRef<str>> Matcher for HasXPath<S> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        let result = owner_document(elem)
+            .evaluate(self.0.as_ref(), &elem.get_root_node())
+            .unwrap_or_else(|_| panic!("Invalid XPath: {:?}", self.0.as_ref()));
+        std::iter::from_fn(|| result.iterate_next().ok().flatten())
+            .any(|node| node.is_same_node(Some(elem)))
+    }
+
+    fn describe(&self) -> String {
+        format!("HasXPath({:?})", self.0.as_ref())
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_has_xpath() {
+    use crate::query::{HasXPath, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<dl>
+            <dt>Name</dt><dd>Alice</dd>
+            <dt>Role</dt><dd>Admin</dd>
+        </dl>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let role = div.get(&HasXPath("//dt[text()='Role']/following-sibling::dd[1]"));
+    assert_eq!(role.unwrap().inner_text(), "Admin");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn has_xpath_is_limited_to_the_query_root() {
+    use crate::query::{HasXPath, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<ul><li>First</li><li>Second</li></ul>
+        <ol><li>Third</li></ol>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    assert_eq!(div.get_all(&HasXPath("//li")).len(), 3);
+    assert_eq!(
+        div.get(&HasXPath("//ul/li[2]")).unwrap().inner_text(),
+        "Second"
+    );
+    // Elements selected by the expression outside of the root aren't returned
+    let ol = div.get(&MatchesSelector("ol")).unwrap();
+    assert_eq!(ol.get_all(&HasXPath("//li")).len(), 1);
+    assert!(ol.get(&HasXPath("//ul")).is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+#[should_panic(expected = "Invalid XPath: \"//li[\"")]
+fn has_xpath_panics_on_invalid_expression() {
+    use crate::query::{HasXPath, Query};
+    use gloo::utils::document;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html("<li>First</li>");
+    div.get(&HasXPath("//li["));
+}
+
+/// Roles of regions whose changes are announced by assistive technologies.
+const LIVE_REGION_ROLES: &[&str] = &["alert", "log", "marquee", "status", "timer"];
+
+/// Returns whether an element satisfies its constraints, or [`None`] if it has no validity state.
+///
+/// An `aria-invalid` attribute set to anything but `false` makes any element invalid.
+/// Otherwise the [`ValidityState`] of form controls is used, without firing `invalid` events
+/// like `checkValidity()` does, and custom widgets with `aria-invalid="false"` are valid.
+/// Controls barred from constraint validation, those with [`willValidate`] being `false`,
+/// are treated like custom widgets.
+///
+/// [`ValidityState`]: https://developer.mozilla.org/en-US/docs/Web/API/ValidityState
+/// [`willValidate`]: https://developer.mozilla.org/en-US/docs/Web/API/HTMLInputElement/willValidate
+fn validity(elem: &HtmlElement) -> Option<bool> {
+    let aria_invalid = elem.get_attribute("aria-invalid");
+    if aria_invalid
+        .as_deref()
+        .is_some_and(|invalid| !invalid.is_empty() && invalid != "false")
+    {
+        return Some(false);
+    }
+    let validity = if let Some(elem) = html_cast::<HtmlInputElement>(elem, "input") {
+        elem.will_validate().then(|| elem.validity())
+    } else if let Some(elem) = html_cast::<HtmlSelectElement>(elem, "select") {
+        elem.will_validate().then(|| elem.validity())
+    } else if let Some(elem) = html_cast::<HtmlTextAreaElement>(elem, "textarea") {
+        elem.will_validate().then(|| elem.validity())
+    } else {
+        None
+    };
+    match validity {
+        Some(validity) => Some(validity.valid()),
+        None => aria_invalid.is_some().then_some(true),
+    }
+}
+
+/// Matches form controls which satisfy their constraints, like `required` or `pattern`.
+///
+/// `<input>`, `<select>` and `<textarea>` are checked with their [`validity`], while other elements
+/// are only valid if they have `aria-invalid="false"`. An `aria-invalid` attribute with other value
+/// makes any element invalid. Elements without any validity state, including controls which
+/// aren't validated, like `<input type="button">` or `<input type="hidden">`, match neither
+/// [`IsValid`] nor [`IsInvalid`].
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<label>Name <input type="text" required /></label>
+///     <label>Email <input type="email" value="me@example.com" required /></label>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// assert!(div.get(&HasLabel("Name").and(IsInvalid)).is_some());
+/// assert!(div.get(&HasLabel("Email").and(IsValid)).is_some());
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+///
+/// [`validity`]: https://developer.mozilla.org/en-US/docs/Web/API/ValidityState
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct IsValid;
+
+impl Matcher for IsValid {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        validity(elem) == Some(true)
+    }
+
+    fn describe(&self) -> String {
+        String::from("IsValid")
+    }
+}
+
+/// Matches form controls which don't satisfy their constraints, the opposite of [`IsValid`].
+///
+/// See [`IsValid`] for details and an example.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct IsInvalid;
+
+impl Matcher for IsInvalid {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        validity(elem) == Some(false)
+    }
+
+    fn describe(&self) -> String {
+        String::from("IsInvalid")
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_is_valid() {
+    use crate::query::{HasLabel, Joinable, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<label>Name <input type="text" required /></label>
+        <label>Email <input type="email" value="me@example.com" required /></label>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    assert!(div.get(&HasLabel("Name").and(IsInvalid)).is_some());
+    assert!(div.get(&HasLabel("Email").and(IsValid)).is_some());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn validity_follows_input_and_aria_invalid() {
+    use crate::query::{HasLabel, HasRole, Joinable, MatchesSelector, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<label>Name <input type="text" required /></label>
+        <label>Bio <textarea maxlength="10"></textarea></label>
+        <label>Color <select required><option value="">Pick</option><option>Red</option></select></label>
+        <div role="combobox" aria-label="City" aria-invalid="true"></div>
+        <div role="combobox" aria-label="Country" aria-invalid="false"></div>
+        <input type="button" value="Go" />
+        <input type="hidden" name="token" />
+        <p>No validity</p>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let name = div
+        .get_as::<HtmlInputElement, _>(&HasLabel("Name"))
+        .unwrap();
+    assert!(div.get(&HasLabel("Name").and(IsInvalid)).is_some());
+    name.set_value("Alice");
+    assert!(div.get(&HasLabel("Name").and(IsValid)).is_some());
+    name.set_attribute("aria-invalid", "true").unwrap();
+    assert!(div.get(&HasLabel("Name").and(IsInvalid)).is_some());
+
+    assert!(div.get(&HasLabel("Bio").and(IsValid)).is_some());
+    assert!(div.get(&HasLabel("Color").and(IsInvalid)).is_some());
+    let combobox = |name| HasRole("combobox").named(name);
+    assert!(div.get(&combobox("City").and(IsInvalid)).is_some());
+    assert!(div.get(&combobox("Country").and(IsValid)).is_some());
+
+    let with_validity = div.get_all(&IsValid.or(IsInvalid));
+    assert_eq!(with_validity.len(), 5);
+    assert!(div
+        .get(&MatchesSelector("p").and(IsValid.or(IsInvalid)))
+        .is_none());
+    // Buttons and hidden inputs aren't validated
+    assert!(div
+        .get(&MatchesSelector("input[type=button], input[type=hidden]").and(IsValid.or(IsInvalid)))
+        .is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Matches range widgets, like sliders, spinbuttons or progress bars, with given current value.
+///
+/// The value is read from the `aria-valuenow` attribute and compared numerically, so `"50"`
+/// and `"50.0"` are the same value. Native `<input type="range">`, `<meter>` and `<progress>`
+/// elements without the attribute are matched by their own value.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<div role="slider" aria-label="Volume" aria-valuenow="50"></div>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// assert!(div.get(&HasRole("slider").and(HasAriaValueNow(50.0))).is_some());
+/// assert!(div.get(&HasAriaValueNow(20.0)).is_none());
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HasAriaValueNow(pub f64);
+
+impl Matcher for HasAriaValueNow {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        value_now(elem) == Some(self.0)
+    }
+
+    fn describe(&self) -> String {
+        format!("HasAriaValueNow({})", self.0)
+    }
+}
+
+/// Returns the current value of a range widget, from `aria-valuenow` or the native element.
+fn value_now(elem: &HtmlElement) -> Option<f64> {
+    if let Some(value) = elem.get_attribute("aria-valuenow") {
+        return value
+            .trim()
+            .parse()
+            .ok()
+            .filter(|value: &f64| value.is_finite());
+    }
+    if let Some(input) = html_cast::<HtmlInputElement>(elem, "input") {
+        (input.type_() == "range").then(|| input.value_as_number())
+    } else if let Some(meter) = html_cast::<HtmlMeterElement>(elem, "meter") {
+        Some(meter.value())
+    } else if let Some(progress) = html_cast::<HtmlProgressElement>(elem, "progress") {
+        // progress without a value is indeterminate
+        progress.has_attribute("value").then(|| progress.value())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_has_aria_value_now() {
+    use crate::query::{HasRole, Joinable, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<div role="slider" aria-label="Volume" aria-valuenow="50"></div>"#);
+    body().append_child(&div).unwrap();
+
+    assert!(div
+        .get(&HasRole("slider").and(HasAriaValueNow(50.0)))
+        .is_some());
+    assert!(div.get(&HasAriaValueNow(20.0)).is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn has_aria_value_now_compares_numerically() {
+    use crate::query::{HasRole, Joinable, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<div role="slider" aria-label="Volume" aria-valuenow="50.0"></div>
+        <div role="spinbutton" aria-label="Amount" aria-valuenow="-2.5"></div>
+        <div role="slider" aria-label="Broken" aria-valuenow="loud"></div>
+        <input type="range" aria-label="Zoom" min="0" max="10" value="3" />
+        <progress aria-label="Loading" max="100" value="30"></progress>
+        <progress aria-label="Waiting"></progress>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let slider = |name| HasRole("slider").named(name);
+    assert!(div
+        .get(&slider("Volume").and(HasAriaValueNow(50.0)))
+        .is_some());
+    assert!(div
+        .get(&HasRole("spinbutton").and(HasAriaValueNow(-2.5)))
+        .is_some());
+    // Native range inputs have no implicit role, but their value is read
+    let zoom = div.get(&MatchesSelector("input[type=range]")).unwrap();
+    assert_eq!(div.get(&HasAriaValueNow(3.0)), Some(zoom));
+    let volume = div.get(&slider("Volume")).unwrap();
+    volume.set_attribute("aria-valuenow", "75").unwrap();
+    assert!(div.get(&HasAriaValueNow(50.0)).is_none());
+    assert!(div
+        .get(&slider("Volume").and(HasAriaValueNow(75.0)))
+        .is_some());
+    assert_eq!(
+        div.get(&HasAriaValueNow(30.0)).unwrap().local_name(),
+        "progress"
+    );
+    // Neither unparsable values nor indeterminate progress bars have a value
+    assert_eq!(div.get_all(&HasAriaValueNow(0.0)).len(), 0);
+    assert_eq!(HasAriaValueNow(0.5).describe(), "HasAriaValueNow(0.5)");
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Matches [`live regions`], elements whose content changes are announced by screen readers.
+///
+/// Those are elements with one of the live region roles (`alert`, `log`, `marquee`, `status`
+/// and `timer`), including implicit ones like `status` of `<output>`, and elements with
+/// `aria-live` set to `polite` or `assertive`. Use [`live_region_text`] to read what is announced.
+///
+/// # Example:
+///
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+/// use frontest::query::live_region_text;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<form>
+///         <input type="email" />
+///         <div role="alert">  Invalid   email  </div>
+///     </form>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// let alert = div.get(&IsLiveRegion.and(HasRole("alert"))).unwrap();
+/// assert_eq!(live_region_text(&alert), "Invalid email");
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+/// [`live regions`]: https://developer.mozilla.org/en-US/docs/Web/Accessibility/ARIA/ARIA_Live_Regions
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct IsLiveRegion;
+
+impl Matcher for IsLiveRegion {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        match elem.get_attribute("aria-live").as_deref() {
+            Some("polite") | Some("assertive") => true,
+            Some("off") => false,
+            _ => LIVE_REGION_ROLES
+                .iter()
+                .any(|role| HasRole(role).matches(elem)),
+        }
+    }
+
+    fn describe(&self) -> String {
+        String::from("IsLiveRegion")
+    }
+
+    fn selector_hint(&self) -> Option<String> {
+        let roles = LIVE_REGION_ROLES
+            .iter()
+            .filter_map(|role| HasRole(role).selector_hint())
+            .collect::<Vec<_>>();
+        Some(format!("[aria-live], {}", roles.join(", ")))
+    }
+}
+
+/// Returns the text of a live region, as it would be announced by a screen reader.
+///
+/// Unlike [`inner_text`] it includes content hidden only visually, as it is commonly done
+/// for messages meant just for assistive technologies. Whitespace is collapsed.
+///
+/// [`inner_text`]: web_sys::HtmlElement::inner_text
+pub fn live_region_text(elem: &HtmlElement) -> String {
+    normalize_whitespace(&elem.text_content().unwrap_or_default())
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_is_live_region() {
+    use crate::query::{HasRole, IsLiveRegion, Joinable, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<form>
+            <input type="email" />
+            <div role="alert">  Invalid   email  </div>
+        </form>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let alert = div.get(&IsLiveRegion.and(HasRole("alert"))).unwrap();
+    assert_eq!(live_region_text(&alert), "Invalid email");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn is_live_region_respects_aria_live() {
+    use crate::query::{HasRole, IsLiveRegion, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<output>42</output>
+        <p aria-live="polite">Saved <span class="sr-only" style="display: none;">draft</span></p>
+        <div role="status" aria-live="off">Muted</div>
+        <div aria-live="off">Quiet</div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let regions = div.get_all(&IsLiveRegion);
+    assert_eq!(regions.len(), 2);
+    assert_eq!(live_region_text(&regions[0]), "42");
+    assert_eq!(live_region_text(&regions[1]), "Saved draft");
+    assert_eq!(div.get_all(&HasRole("status")).len(), 2);
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Matches elements removed from the accessibility tree with `role="none"` or `role="presentation"`.
+///
+/// Their semantics are hidden from assistive technologies, so eg. a presentational `<ul>` is not
+/// announced as a list. It allows asserting that an element is intentionally presentational.
+/// Only the first role of a fallback list like `role="none list"` is taken into account,
+/// as the others are used only by older browsers.
+///
+/// # Example:
+///
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<ul role="presentation">
+///         <li>Home</li>
+///     </ul>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// let layout = div.get(&IsPresentational).unwrap();
+/// assert_eq!(layout.local_name(), "ul");
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct IsPresentational;
+
+impl Matcher for IsPresentational {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        let role = elem.get_attribute("role").unwrap_or_default();
+        matches!(
+            role.split_whitespace().next(),
+            Some("none" | "presentation")
+        )
+    }
+
+    fn describe(&self) -> String {
+        String::from("IsPresentational")
+    }
+
+    fn selector_hint(&self) -> Option<String> {
+        Some(String::from("[role~=none], [role~=presentation]"))
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_is_presentational() {
+    use crate::query::{IsPresentational, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<ul role="presentation">
+            <li>Home</li>
+        </ul>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let layout = div.get(&IsPresentational).unwrap();
+    assert_eq!(layout.local_name(), "ul");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn is_presentational_checks_the_first_role() {
+    use crate::query::{IsPresentational, Joinable, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<table role="none"><tr><td>Layout</td></tr></table>
+        <img src="spacer.png" alt="" role="presentation none" />
+        <div role="list none"><div role="listitem">Item</div></div>
+        <ul><li>Menu</li></ul>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let presentational = div.get_all(&IsPresentational);
+    assert_eq!(presentational.len(), 2);
+    assert_eq!(presentational[0].local_name(), "table");
+    assert_eq!(presentational[1].local_name(), "img");
+    assert!(div
+        .get(&MatchesSelector("div").and(IsPresentational))
+        .is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Matches elements which are being updated, marked with `aria-busy="true"`.
+///
+/// Loading widgets set it while their content isn't ready yet. Only the element itself is checked,
+/// not its ancestors. Combined with [`Not`] and [`find`] it allows waiting until eg. a region
+/// finishes loading.
+///
+/// # Example:
+///
+/// ```no_run
+/// use frontest::prelude::*;
+///
+/// # async fn wait_for_orders() {
+/// let mount = mount_html(r#"<section role="region" aria-label="Orders" aria-busy="true"></section>"#);
+/// assert!(mount.get(&HasRole("region").and(IsBusy)).is_some());
+///
+/// // Resolves once the region is no longer busy
+/// let orders = find(&mount, &HasRole("region").and(Not(IsBusy))).await;
+/// # }
+/// ```
+///
+/// [`find`]: crate::wait::find
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct IsBusy;
+
+impl Matcher for IsBusy {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        elem.get_attribute("aria-busy").as_deref() == Some("true")
+    }
+
+    fn describe(&self) -> String {
+        String::from("IsBusy")
+    }
+
+    fn selector_hint(&self) -> Option<String> {
+        Some(String::from("[aria-busy=true]"))
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_is_busy() {
+    use crate::query::{HasRole, Joinable, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<section role="region" aria-label="Orders" aria-busy="true"></section>"#);
+    body().append_child(&div).unwrap();
+
+    assert!(div.get(&HasRole("region").and(IsBusy)).is_some());
+    assert!(div.get(&HasRole("region").and(Not(IsBusy))).is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn is_busy_waits_out_loading_region() {
+    use crate::query::{HasRole, HasText, Joinable, Query};
+    use crate::wait::find;
+    use crate::{cleanup, mount_html};
+    use gloo::timers::callback::Timeout;
+
+    let mount = mount_html(
+        r#"<section role="region" aria-label="Orders" aria-busy="true">
+            <p>Loading...</p>
+        </section>"#,
+    );
+    let region = mount.get(&HasRole("region")).unwrap();
+    Timeout::new(20, {
+        let region = region.clone();
+        move || {
+            region.set_inner_html("<p>Order #1</p>");
+            region.set_attribute("aria-busy", "false").unwrap();
+        }
+    })
+    .forget();
+
+    let loaded = find(&mount, &HasRole("region").and(Not(IsBusy)))
+        .await
+        .unwrap();
+    assert_eq!(loaded, region);
+    assert!(loaded.get(&HasText("Order #1")).is_some());
+    assert_eq!(IsBusy.describe(), "IsBusy");
+    // Only "true" makes the element busy
+    region.set_attribute("aria-busy", "TRUE").unwrap();
+    assert!(mount.get(&IsBusy).is_none());
+
+    cleanup();
+}
+
+/// Returns whether the element has `hidden="until-found"`, compared case-insensitively like browsers do.
+pub(crate) fn is_until_found(elem: &Element) -> bool {
+    elem.get_attribute("hidden")
+        .is_some_and(|hidden| hidden.eq_ignore_ascii_case("until-found"))
+}
+
+/// Returns whether the element or one of its ancestors has `hidden="until-found"`.
+fn hidden_until_found(elem: &Element) -> bool {
+    std::iter::successors(Some(elem.clone()), |elem| elem.parent_element())
+        .any(|elem| is_until_found(&elem))
+}
+
+/// Matches elements with `hidden="until-found"`, whose content isn't rendered until it is found.
+///
+/// Unlike with a plain `hidden`, their content can be found by the in-page search of the browser,
+/// which removes the attribute and reveals it, like in collapsible FAQ sections. Until then it is
+/// treated as hidden, so eg. it isn't matched by [`HasText`]. This allows asserting that the content
+/// is present in the page and only collapsed.
+///
+/// # Example:
+///
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<h3>Can I return it?</h3>
+///     <div hidden="until-found">Yes, within 30 days.</div>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// assert!(div.get(&HasText("within 30 days")).is_none());
+/// let answer = div.get(&IsHiddenUntilFound).unwrap();
+/// assert_eq!(answer.text_content().unwrap(), "Yes, within 30 days.");
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct IsHiddenUntilFound;
+
+impl Matcher for IsHiddenUntilFound {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        is_until_found(elem)
+    }
+
+    fn describe(&self) -> String {
+        String::from("IsHiddenUntilFound")
+    }
+
+    fn selector_hint(&self) -> Option<String> {
+        Some(String::from("[hidden]"))
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_is_hidden_until_found() {
+    use crate::query::{HasText, IsHiddenUntilFound, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<h3>Can I return it?</h3>
+        <div hidden="until-found">Yes, within 30 days.</div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    assert!(div.get(&HasText("within 30 days")).is_none());
+    let answer = div.get(&IsHiddenUntilFound).unwrap();
+    assert_eq!(answer.text_content().unwrap(), "Yes, within 30 days.");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn hidden_until_found_differs_from_hidden() {
+    use crate::debug::{explain_no_match, hidden_reason};
+    use crate::query::{HasText, IsHiddenUntilFound, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<section hidden="UNTIL-FOUND"><p>Shipping is free.</p></section>
+        <section hidden><p>Internal notes</p></section>
+        <section hidden=""><p>Drafts</p></section>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    // Both are hidden from text queries
+    assert!(div.get(&HasText("Shipping")).is_none());
+    assert!(div.get(&HasText("Internal")).is_none());
+    assert_eq!(
+        explain_no_match(&div, &HasText("Shipping")).unwrap(),
+        r#"found 1 element matching HasText("Shipping") but it is hidden via hidden="until-found" on <section>"#
+    );
+    let notes = div.get_all(&MatchesSelector("p"))[1].clone();
+    assert_eq!(
+        hidden_reason(&notes).unwrap(),
+        "hidden attribute on <section>"
+    );
+
+    // But only one is findable
+    let found = div.get(&IsHiddenUntilFound).unwrap();
+    assert_eq!(found.text_content().unwrap(), "Shipping is free.");
+
+    // Revealed like by the in-page search
+    found.remove_attribute("hidden").unwrap();
+    assert!(div.get(&HasText("Shipping")).is_some());
+    assert!(div.get(&IsHiddenUntilFound).is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Matches elements controlled by the element with given id, as set with its [`aria-controls`].
+///
+/// To go the other way and find elements controlled by an already selected one,
+/// use [`controlled_by`].
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<button id="toggle" aria-controls="details" aria-expanded="true">Details</button>
+///     <div id="details">Made of wood</div>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// let details = div.get(&Controls("toggle")).unwrap();
+/// assert_eq!(details.inner_text(), "Made of wood");
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+///
+/// [`aria-controls`]: https://developer.mozilla.org/en-US/docs/Web/Accessibility/ARIA/Attributes/aria-controls
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Controls<S = &'static str>(pub S);
+
+impl<S: AsRef<str>> Matcher for Controls<S> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        owner_document(elem)
+            .get_element_by_id(self.0.as_ref())
+            .is_some_and(|control| id_references(&control, "aria-controls").contains(elem))
+    }
+
+    fn describe(&self) -> String {
+        format!("Controls({:?})", self.0.as_ref())
+    }
+}
+
+/// Returns elements referenced by the [`aria-controls`] attribute of a control, in the given order.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+/// use frontest::query::controlled_by;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<button aria-controls="details" aria-expanded="false">Details</button>
+///     <div id="details" hidden>Made of wood</div>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// let toggle = div.get(&HasRole("button")).unwrap();
+/// assert!(controlled_by(&toggle)[0].hidden());
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+///
+/// [`aria-controls`]: https://developer.mozilla.org/en-US/docs/Web/Accessibility/ARIA/Attributes/aria-controls
+pub fn controlled_by(control: &HtmlElement) -> Vec<HtmlElement> {
+    id_references(control, "aria-controls")
+        .into_iter()
+        .filter_map(as_html_element)
+        .collect()
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_controls() {
+    use crate::query::{Controls, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<button id="toggle" aria-controls="details" aria-expanded="true">Details</button>
+        <div id="details">Made of wood</div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let details = div.get(&Controls("toggle")).unwrap();
+    assert_eq!(details.inner_text(), "Made of wood");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn controlled_by_resolves_disclosure_panel() {
+    use crate::query::{Controls, HasRole, Query};
+    use gloo::events::EventListener;
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<button id="toggle" aria-controls="missing details" aria-expanded="false">Details</button>
+        <div id="details" hidden>Made of wood</div>
+        <div id="other">Made of stone</div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let toggle = div.get(&HasRole("button")).unwrap();
+    let _listener = EventListener::new(&toggle, "click", {
+        let toggle = toggle.clone();
+        move |_| {
+            toggle.set_attribute("aria-expanded", "true").unwrap();
+            for panel in controlled_by(&toggle) {
+                panel.set_hidden(false);
+            }
+        }
+    });
+
+    let panels = controlled_by(&toggle);
+    assert_eq!(panels.len(), 1);
+    assert_eq!(panels[0].id(), "details");
+    assert!(panels[0].hidden());
+    assert_eq!(div.get_all(&Controls("toggle")), panels);
+    assert!(div.get(&Controls("details")).is_none());
+
+    toggle.click();
+    assert_eq!(toggle.get_attribute("aria-expanded").unwrap(), "true");
+    assert!(!panels[0].hidden());
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Matches elements matched by the `inner` matcher, which have an ancestor with given role.
+///
+/// This allows to express relationships like "a textbox inside a search landmark" as a single matcher,
+/// without scoping queries with [`within`]. Only ancestors are checked, not the element itself.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<form role="search"><input type="text" /></form>
+///     <form><input type="text" /></form>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// let query = WithinRole {
+///     ancestor_role: "search",
+///     inner: HasRole("textbox"),
+/// };
+/// assert!(div.get(&query).is_some());
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+///
+/// [`within`]: crate::within
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct WithinRole<M, S = &'static str> {
+    /// Role of one of the ancestors.
+    pub ancestor_role: S,
+    /// Matcher for the element itself.
+    pub inner: M,
+}
+
+impl<M: Matcher, S: AsRef<str>> WithinRole<M, S> {
+    fn has_ancestor(&self, elem: &HtmlElement) -> bool {
+        let role = HasRole(self.ancestor_role.as_ref());
+        std::iter::successors(elem.parent_element(), |elem| elem.parent_element())
+            .filter_map(as_html_element)
+            .any(|ancestor| role.matches(&ancestor))
+    }
+}
+
+impl<M: Matcher, S: AsRef<str>> Matcher for WithinRole<M, S> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        self.inner.matches(elem) && self.has_ancestor(elem)
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "WithinRole({:?}, {})",
+            self.ancestor_role.as_ref(),
+            self.inner.describe()
+        )
+    }
+
+    fn matches_ignoring_visibility(&self, elem: &HtmlElement) -> bool {
+        self.inner.matches_ignoring_visibility(elem) && self.has_ancestor(elem)
+    }
+
+    fn selector_hint(&self) -> Option<String> {
+        self.inner.selector_hint()
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_within_role() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<form role="search"><input type="text" /></form>
+        <form><input type="text" /></form>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let query = WithinRole {
+        ancestor_role: "search",
+        inner: HasRole("textbox"),
+    };
+    assert!(div.get(&query).is_some());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn within_role_checks_only_ancestors() {
+    use crate::query::{HasLabel, HasRole, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<div role="search">
+            <div><label>Query <input type="text" /></label></div>
+        </div>
+        <label>Name <input type="text" /></label>
+        <div role="search" aria-label="Empty"></div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    fn search<M: Matcher>(inner: M) -> WithinRole<M, String> {
+        WithinRole {
+            ancestor_role: String::from("search"),
+            inner,
+        }
+    }
+    let input = div.get(&search(HasRole("textbox"))).unwrap();
+    assert_eq!(div.get(&HasLabel("Query")).unwrap(), input);
+    assert!(div.get(&search(HasLabel("Name"))).is_none());
+    // The element itself isn't its own ancestor
+    assert!(div.get(&search(HasRole("search"))).is_none());
+    assert_eq!(
+        search(HasRole("textbox")).describe(),
+        r#"WithinRole("search", HasRole("textbox"))"#
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+/// A trait for joining multiple matchers.
+///
+/// It is automatically implemented for all matchers.
+/// It allows for joining matchers using `or` and `and` methods that consume both matchers
+/// and returns a joined matcher. It can be chained with multiple calls.
+/// Matchers can also be negated in place with `not`, `and_not` and `or_not`.
+///
+/// # Example:
+/// ```no_run
+/// use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<div>
+///         <button>I eat cookies</button>
+///     </div>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// assert!(div
+///     .get(
+///         &HasRole("button")
+///             .and(HasText("bananas").or(HasText("apples")))
+///             .or(HasText("cookies"))
+///     )
+///     .is_some());
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+pub trait Joinable {
+    /// Join two matchers by applying logical `and` operation.
+    fn and<'a, 'b, M>(self, other: M) -> And<'b>
+    where
+        'a: 'b,
+        Self: Sized + Matcher + 'a,
+        M: Matcher + 'a,
+    {
+        And {
+            filters: [Rc::new(self), Rc::new(other)],
+        }
+    }
+
+    /// Join two matchers by applying logical `or` operation.
+    fn or<'a, 'b, M>(self, other: M) -> Or<'b>
+    where
+        'a: 'b,
+        Self: Sized + Matcher + 'a,
+        M: Matcher + 'a,
+    {
+        Or {
+            filters: [Rc::new(self), Rc::new(other)],
+        }
+    }
+
+    /// Negate the matcher, the same as wrapping it with [`Not`].
+    ///
+    /// # Example:
+    /// ```no_run
+    /// use gloo::utils::{body, document};
+    /// use frontest::prelude::*;
+    ///
+    /// let div = document().create_element("div").unwrap();
+    /// div.set_inner_html(
+    ///     r#"<div>
+    ///         <p>what</p>
+    ///         <a href="/foo">is</a>
+    ///         <button>this</button>
+    ///     </div>"#,
+    /// );
+    /// body().append_child(&div).unwrap();
+    ///
+    /// let link = div.get(&HasText("is").and(HasRole("button").not())).unwrap();
+    /// assert_eq!(&link.get_attribute("href").unwrap(), "/foo");
+    ///
+    /// body().remove_child(&div).unwrap();
+    /// ```
+    fn not(self) -> Not<Self>
+    where
+        Self: Sized + Matcher,
+    {
+        Not(self)
+    }
+
+    /// Join two matchers, requiring the first one to match and the second one not to.
+    ///
+    /// It is a shorter way to write `self.and(Not(other))`.
+    fn and_not<'a, 'b, M>(self, other: M) -> And<'b>
+    where
+        'a: 'b,
+        Self: Sized + Matcher + 'a,
+        M: Matcher + 'a,
+    {
+        And {
+            filters: [Rc::new(self), Rc::new(Not(other))],
+        }
+    }
+
+    /// Join two matchers, requiring the first one to match or the second one not to.
+    ///
+    /// It is a shorter way to write `self.or(Not(other))`.
+    fn or_not<'a, 'b, M>(self, other: M) -> Or<'b>
+    where
+        'a: 'b,
+        Self: Sized + Matcher + 'a,
+        M: Matcher + 'a,
+    {
+        Or {
+            filters: [Rc::new(self), Rc::new(Not(other))],
+        }
+    }
+}
+
+impl<M> Joinable for M where M: Matcher {}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_joinable_not() {
+    use crate::query::{HasRole, HasText, Joinable, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<div>
+            <p>what</p>
+            <a href="/foo">is</a>
+            <button>this</button>
+        </div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let link = div
+        .get(&HasText("is").and(HasRole("button").not()))
+        .unwrap();
+    assert_eq!(&link.get_attribute("href").unwrap(), "/foo");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn negating_methods_match_not_wrapper() {
+    use crate::query::{HasRole, HasText, Joinable, Not, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<button>Save</button>
+        <button>Cancel</button>
+        <a href="/help">Save</a>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let pairs: [(Box<dyn Matcher>, Box<dyn Matcher>); 3] = [
+        (
+            Box::new(HasRole("button").not()),
+            Box::new(Not(HasRole("button"))),
+        ),
+        (
+            Box::new(HasRole("button").and_not(HasText("Save"))),
+            Box::new(HasRole("button").and(Not(HasText("Save")))),
+        ),
+        (
+            Box::new(HasRole("link").or_not(HasText("Save"))),
+            Box::new(HasRole("link").or(Not(HasText("Save")))),
+        ),
+    ];
+    for (method, wrapper) in pairs {
+        assert_eq!(method.describe(), wrapper.describe());
+        assert_eq!(div.get_all(&method), div.get_all(&wrapper));
+    }
+    let cancel = div.get(&HasRole("button").and_not(HasText("Save")));
+    assert_eq!(cancel.unwrap().inner_text(), "Cancel");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_joinable() {
+    use crate::query::{HasRole, HasText, Joinable, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<div>
+            <button>I eat cookies</button>
+        </div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    assert!(div
+        .get(
+            &HasRole("button")
+                .and(HasText("bananas").or(HasText("apples")))
+                .or(HasText("cookies"))
+        )
+        .is_some());
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Result of joining two [`Matcher`]s by applyng a logical [`and`] operation on them.
+///
+/// [`and`]: Joinable::and
+#[derive(Clone)]
+pub struct And<'a> {
+    filters: [Rc<dyn Matcher + 'a>; 2],
+}
+
+impl<'a> Matcher for And<'a> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        self.filters.iter().all(|f| f.matches(elem))
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{}.and({})",
+            self.filters[0].describe(),
+            self.filters[1].describe()
+        )
+    }
+
+    fn matches_ignoring_visibility(&self, elem: &HtmlElement) -> bool {
+        self.filters
+            .iter()
+            .all(|f| f.matches_ignoring_visibility(elem))
+    }
+
+    fn selector_hint(&self) -> Option<String> {
+        match (
+            self.filters[0].selector_hint(),
+            self.filters[1].selector_hint(),
+        ) {
+            (Some(first), Some(second)) => Some(format!(":is({}):is({})", first, second)),
+            (first, second) => first.or(second),
+        }
+    }
 }
 
 /// Result of combining two [`Matcher`]s by applyng a logical [`or`] operation on them.
 ///
-/// [`or`]: Joinable::or
-pub struct Or<'a> {
-    filters: [Box<dyn Matcher + 'a>; 2],
+/// [`or`]: Joinable::or
+#[derive(Clone)]
+pub struct Or<'a> {
+    filters: [Rc<dyn Matcher + 'a>; 2],
+}
+
+impl<'a> Matcher for Or<'a> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        self.filters.iter().any(|f| f.matches(elem))
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{}.or({})",
+            self.filters[0].describe(),
+            self.filters[1].describe()
+        )
+    }
+
+    fn matches_ignoring_visibility(&self, elem: &HtmlElement) -> bool {
+        self.filters
+            .iter()
+            .any(|f| f.matches_ignoring_visibility(elem))
+    }
+}
+
+impl<'a> And<'a> {
+    /// Joins another matcher, flattening the result into a single [`All`].
+    pub fn and<M: Matcher + 'a>(self, other: M) -> All<'a> {
+        All(Vec::from(self.filters)).and(other)
+    }
+}
+
+impl<'a> Or<'a> {
+    /// Joins another matcher, flattening the result into a single [`Any`].
+    pub fn or<M: Matcher + 'a>(self, other: M) -> Any<'a> {
+        Any(Vec::from(self.filters)).or(other)
+    }
+}
+
+/// Formats a type-erased matcher with its [`Matcher::describe`] for [`Debug`] output.
+struct Described<'m>(&'m dyn Matcher);
+
+impl std::fmt::Debug for Described<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0.describe())
+    }
+}
+
+/// Formats a combinator of type-erased matchers as a tuple struct with given name.
+fn debug_combinator(
+    f: &mut std::fmt::Formatter<'_>,
+    name: &str,
+    filters: &[Rc<dyn Matcher + '_>],
+) -> std::fmt::Result {
+    let mut tuple = f.debug_tuple(name);
+    for filter in filters {
+        tuple.field(&Described(&**filter));
+    }
+    tuple.finish()
+}
+
+impl std::fmt::Debug for And<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        debug_combinator(f, "And", &self.filters)
+    }
+}
+
+impl std::fmt::Debug for Or<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        debug_combinator(f, "Or", &self.filters)
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn combinators_can_be_cloned_and_debugged() {
+    use crate::query::{HasRole, HasText, Joinable, Not, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<button>Save</button>
+        <button>Save draft</button>
+        <a href="/save">Save</a>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let save = HasRole("button").and(Not(HasText("draft")));
+    let copy = save.clone();
+    assert_eq!(div.get(&save), div.get(&copy));
+    assert_eq!(div.get(&copy).unwrap().inner_text(), "Save");
+    let link = copy.or(HasRole("link"));
+    assert_eq!(div.get_all(&link).len(), 2);
+
+    assert_eq!(
+        format!("{:?}", save),
+        r#"And(HasRole("button"), Not(HasText("draft")))"#
+    );
+    assert_eq!(
+        format!("{:?}", link),
+        r#"Or(HasRole("button").and(Not(HasText("draft"))), HasRole("link"))"#
+    );
+    assert_eq!(
+        format!("{:?}", Not(HasText("draft"))),
+        r#"Not(HasText("draft"))"#
+    );
+    let all = crate::all![HasRole("button"), HasText("Save")];
+    assert_eq!(
+        format!("{:?}", all.clone()),
+        r#"All(HasRole("button"), HasText("Save"))"#
+    );
+    assert_eq!(
+        format!("{:?}", crate::any![HasRole("link")]),
+        r#"Any(HasRole("link"))"#
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Matches elements matched by all of the given [`Matcher`]s.
+///
+/// Matchers are checked in the given order, stopping at the first one that doesn't match.
+/// An empty [`All`] matches every element. It is most conveniently created with the [`all!`] macro,
+/// and chaining [`and`] on [`All`] or [`And`] adds to the same list instead of nesting matchers.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<button disabled>Save</button>
+///     <button>Save</button>
+///     <a href="/save">Save</a>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// let save = all![
+///     HasRole("button"),
+///     HasText("Save"),
+///     Not(MatchesSelector(":disabled")),
+/// ];
+/// assert!(div.get(&save).is_some());
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+///
+/// [`and`]: All::and
+/// [`all!`]: crate::all
+#[derive(Clone, Default)]
+pub struct All<'a>(pub Vec<Rc<dyn Matcher + 'a>>);
+
+impl<'a> All<'a> {
+    /// Adds another matcher to the list.
+    pub fn and<M: Matcher + 'a>(mut self, other: M) -> Self {
+        self.0.push(Rc::new(other));
+        self
+    }
+}
+
+impl std::fmt::Debug for All<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        debug_combinator(f, "All", &self.0)
+    }
+}
+
+impl<'a> Matcher for All<'a> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        self.0.iter().all(|f| f.matches(elem))
+    }
+
+    fn describe(&self) -> String {
+        let filters = self.0.iter().map(|f| f.describe()).collect::<Vec<_>>();
+        format!("all of ({})", filters.join(", "))
+    }
+
+    fn matches_ignoring_visibility(&self, elem: &HtmlElement) -> bool {
+        self.0.iter().all(|f| f.matches_ignoring_visibility(elem))
+    }
+
+    fn selector_hint(&self) -> Option<String> {
+        let hints = self
+            .0
+            .iter()
+            .filter_map(|f| f.selector_hint())
+            .map(|hint| format!(":is({})", hint))
+            .collect::<String>();
+        (!hints.is_empty()).then_some(hints)
+    }
+}
+
+/// Matches elements matched by any of the given [`Matcher`]s.
+///
+/// Matchers are checked in the given order, stopping at the first one that matches.
+/// An empty [`Any`] doesn't match anything. It is most conveniently created with the [`any!`] macro,
+/// and chaining [`or`] on [`Any`] or [`Or`] adds to the same list instead of nesting matchers.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<button>Ok</button><a href="/cancel">Cancel</a>"#);
+/// body().append_child(&div).unwrap();
+///
+/// let actions = any![HasRole("button"), HasRole("link")];
+/// assert_eq!(div.get_all(&actions).len(), 2);
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+///
+/// [`or`]: Any::or
+/// [`any!`]: crate::any
+#[derive(Clone, Default)]
+pub struct Any<'a>(pub Vec<Rc<dyn Matcher + 'a>>);
+
+impl<'a> Any<'a> {
+    /// Adds another matcher to the list.
+    pub fn or<M: Matcher + 'a>(mut self, other: M) -> Self {
+        self.0.push(Rc::new(other));
+        self
+    }
+}
+
+impl std::fmt::Debug for Any<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        debug_combinator(f, "Any", &self.0)
+    }
+}
+
+impl<'a> Matcher for Any<'a> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        self.0.iter().any(|f| f.matches(elem))
+    }
+
+    fn describe(&self) -> String {
+        let filters = self.0.iter().map(|f| f.describe()).collect::<Vec<_>>();
+        format!("any of ({})", filters.join(", "))
+    }
+
+    fn matches_ignoring_visibility(&self, elem: &HtmlElement) -> bool {
+        self.0.iter().any(|f| f.matches_ignoring_visibility(elem))
+    }
+
+    fn selector_hint(&self) -> Option<String> {
+        // Every alternative has to be narrowed down, otherwise matches would be skipped
+        let hints = self
+            .0
+            .iter()
+            .map(|f| f.selector_hint())
+            .collect::<Option<Vec<_>>>()?;
+        (!hints.is_empty()).then(|| hints.join(", "))
+    }
+}
+
+/// Creates an [`All`] matcher from a list of matchers.
+///
+/// `all![a, b, c]` is a shorter way to write `All::default().and(a).and(b).and(c)`.
+/// See [`All`] for an example.
+#[macro_export]
+macro_rules! all {
+    ($($matcher:expr),* $(,)?) => {
+        $crate::query::All::default()$(.and($matcher))*
+    };
+}
+
+/// Creates an [`Any`] matcher from a list of matchers.
+///
+/// `any![a, b, c]` is a shorter way to write `Any::default().or(a).or(b).or(c)`.
+/// See [`Any`] for an example.
+#[macro_export]
+macro_rules! any {
+    ($($matcher:expr),* $(,)?) => {
+        $crate::query::Any::default()$(.or($matcher))*
+    };
+}
+
+/// Creates the matcher for a single `key = value` pair of [`get_by!`] and similar macros.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __matcher_by {
+    (role = $value:expr) => {
+        $crate::query::HasRole($value)
+    };
+    (text = $value:expr) => {
+        $crate::query::HasText($value)
+    };
+    (label = $value:expr) => {
+        $crate::query::HasLabel($value)
+    };
+    (placeholder = $value:expr) => {
+        $crate::query::HasPlaceholder($value)
+    };
+    (testid = $value:expr) => {
+        $crate::query::HasTestId($value)
+    };
+    ($key:ident = $value:expr) => {
+        compile_error!(concat!(
+            "unknown key `",
+            stringify!($key),
+            "`, expected one of: role, text, label, placeholder, testid"
+        ))
+    };
+}
+
+/// Creates the matcher for all `key = value` pairs of [`get_by!`] and similar macros.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __matchers_by {
+    ($key:ident = $value:expr) => {
+        $crate::__matcher_by!($key = $value)
+    };
+    ($($key:ident = $value:expr),+) => {
+        ($($crate::__matcher_by!($key = $value),)+)
+    };
+}
+
+/// Shorthand for [`Query::get`] with matchers given as `key = value` pairs, all of which must match.
+///
+/// `get_by!(mount, role = "button", text = "Save")` is the same as
+/// `mount.get(&(HasRole("button"), HasText("Save")))`. Supported keys are `role` ([`HasRole`]),
+/// `text` ([`HasText`]), `label` ([`HasLabel`]), `placeholder` ([`HasPlaceholder`])
+/// and `testid` ([`HasTestId`]). Values can be any expressions accepted by those matchers.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<label>Email <input placeholder="you@example.com" /></label>
+///     <button data-testid="save">Save</button>
+///     <button>Cancel</button>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// let action = String::from("Save");
+/// assert!(get_by!(div, role = "button", text = action.as_str()).is_some());
+/// assert!(get_by!(div, label = "Email", placeholder = "you@").is_some());
+/// assert!(get_by!(screen(), testid = "save").is_some());
+/// assert_eq!(get_all_by!(div, role = "button").len(), 2);
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+///
+/// Unknown keys are reported at compile time:
+/// ```compile_fail
+/// # use frontest::prelude::*;
+/// get_by!(screen(), colour = "red");
+/// ```
+#[macro_export]
+macro_rules! get_by {
+    ($query:expr, $($key:ident = $value:expr),+ $(,)?) => {{
+        use $crate::query::Query as _;
+        ($query).get(&$crate::__matchers_by!($($key = $value),+))
+    }};
+}
+
+/// Shorthand for [`Query::get_all`] with matchers given as `key = value` pairs.
+///
+/// See [`get_by!`] for supported keys and an example.
+#[macro_export]
+macro_rules! get_all_by {
+    ($query:expr, $($key:ident = $value:expr),+ $(,)?) => {{
+        use $crate::query::Query as _;
+        ($query).get_all(&$crate::__matchers_by!($($key = $value),+))
+    }};
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_get_by() {
+    use crate::screen;
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<label>Email <input placeholder="you@example.com" /></label>
+        <button data-testid="save">Save</button>
+        <button>Cancel</button>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let action = String::from("Save");
+    assert!(crate::get_by!(div, role = "button", text = action.as_str()).is_some());
+    assert!(crate::get_by!(div, label = "Email", placeholder = "you@").is_some());
+    assert!(crate::get_by!(screen(), testid = "save").is_some());
+    assert_eq!(crate::get_all_by!(div, role = "button").len(), 2);
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn get_by_expands_to_matchers() {
+    use gloo::utils::{body, document};
+
+    assert_eq!(
+        crate::__matchers_by!(role = "button").describe(),
+        r#"HasRole("button")"#
+    );
+    let name = String::from("Save");
+    assert_eq!(
+        crate::__matchers_by!(role = "button", text = name.clone(), testid = "save").describe(),
+        r#"all of (HasRole("button"), HasText("Save"), HasTestId("save"))"#
+    );
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<button data-testid="save">Save</button>
+        <button data-testid="save-as">Save as</button>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    // References to queries work too
+    let query = &div;
+    assert_eq!(crate::get_all_by!(query, text = name).len(), 2);
+    assert_eq!(
+        crate::get_by!(query, role = "button", testid = "save-as",)
+            .unwrap()
+            .inner_text(),
+        "Save as"
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_all() {
+    use crate::query::{HasRole, HasText, MatchesSelector, Not, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<button disabled>Save</button>
+        <button>Save</button>
+        <a href="/save">Save</a>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let save = crate::all![
+        HasRole("button"),
+        HasText("Save"),
+        Not(MatchesSelector(":disabled")),
+    ];
+    assert!(div.get(&save).is_some());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_any() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button>Ok</button><a href="/cancel">Cancel</a>"#);
+    body().append_child(&div).unwrap();
+
+    let actions = crate::any![HasRole("button"), HasRole("link")];
+    assert_eq!(div.get_all(&actions).len(), 2);
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn all_and_any_stay_flat_and_short_circuit() {
+    use crate::query::fixtures::Logged;
+    use crate::query::{HasRole, HasText, Joinable, Query};
+    use gloo::utils::document;
+
+    let chained = HasRole("button")
+        .and(HasText("Save"))
+        .and(HasText("Now"))
+        .or(HasRole("link"))
+        .or(HasRole("menuitem"))
+        .or(HasRole("tab"));
+    assert_eq!(
+        chained.describe(),
+        r#"any of (all of (HasRole("button"), HasText("Save"), HasText("Now")), HasRole("link"), HasRole("menuitem"), HasRole("tab"))"#
+    );
+    assert_eq!(crate::all![].describe(), "all of ()");
+
+    let div = document()
+        .create_element("div")
+        .unwrap()
+        .unchecked_into::<HtmlElement>();
+    let log = RefCell::new(vec![]);
+    let all = crate::all![
+        Logged("first", true, &log),
+        Logged("second", false, &log),
+        Logged("third", true, &log),
+    ];
+    assert!(!all.matches(&div));
+    assert_eq!(*log.borrow(), ["first", "second"]);
+
+    log.borrow_mut().clear();
+    let any = crate::any![Logged("first", false, &log)]
+        .or(Logged("second", true, &log))
+        .or(Logged("third", true, &log));
+    assert!(any.matches(&div));
+    assert_eq!(*log.borrow(), ["first", "second"]);
+
+    assert!(crate::all![].matches(&div));
+    assert!(!crate::any![].matches(&div));
+    assert!(div.get(&crate::any![]).is_none());
+}
+
+/// Matches elements matched by exactly one of two [`Matcher`]s, a logical `xor` of them.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<div role="progressbar" aria-label="Loading"></div>
+///     <ul role="list" aria-busy="true"></ul>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// let state = Xor(HasRole("progressbar"), HasRole("list"));
+/// assert_eq!(div.get_all(&state).len(), 2);
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct Xor<A, B>(pub A, pub B);
+
+impl<A: Matcher, B: Matcher> Matcher for Xor<A, B> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        self.0.matches(elem) != self.1.matches(elem)
+    }
+
+    fn describe(&self) -> String {
+        format!("Xor({}, {})", self.0.describe(), self.1.describe())
+    }
+
+    fn matches_ignoring_visibility(&self, elem: &HtmlElement) -> bool {
+        // Hidden elements may match both matchers when ignoring visibility, but never lose a match
+        self.matches(elem)
+            || self.0.matches_ignoring_visibility(elem) != self.1.matches_ignoring_visibility(elem)
+    }
+}
+
+/// Matches elements matched by exactly one of the given [`Matcher`]s.
+///
+/// Unlike [`Any`], all matchers are always checked. An empty [`ExactlyOne`] doesn't match anything.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+/// use std::rc::Rc;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<button>Save</button><button>Save all</button>"#);
+/// body().append_child(&div).unwrap();
+///
+/// let save = ExactlyOne(vec![Rc::new(HasText("Save")), Rc::new(HasText("all"))]);
+/// assert_eq!(div.get(&save).unwrap().inner_text(), "Save");
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+#[derive(Clone, Default)]
+pub struct ExactlyOne<'a>(pub Vec<Rc<dyn Matcher + 'a>>);
+
+impl std::fmt::Debug for ExactlyOne<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        debug_combinator(f, "ExactlyOne", &self.0)
+    }
+}
+
+impl<'a> Matcher for ExactlyOne<'a> {
+    fn matches(&self, elem: &HtmlElement) -> bool {
+        self.0.iter().filter(|f| f.matches(elem)).count() == 1
+    }
+
+    fn describe(&self) -> String {
+        let filters = self.0.iter().map(|f| f.describe()).collect::<Vec<_>>();
+        format!("exactly one of ({})", filters.join(", "))
+    }
+
+    fn matches_ignoring_visibility(&self, elem: &HtmlElement) -> bool {
+        // Same as for `Xor`, relaxed matchers must not reject what is matched
+        self.matches(elem)
+            || self
+                .0
+                .iter()
+                .filter(|f| f.matches_ignoring_visibility(elem))
+                .count()
+                == 1
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_xor() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<div role="progressbar" aria-label="Loading"></div>
+        <ul role="list" aria-busy="true"></ul>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let state = Xor(HasRole("progressbar"), HasRole("list"));
+    assert_eq!(div.get_all(&state).len(), 2);
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_exactly_one() {
+    use crate::query::{HasText, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button>Save</button><button>Save all</button>"#);
+    body().append_child(&div).unwrap();
+
+    let save = ExactlyOne(vec![Rc::new(HasText("Save")), Rc::new(HasText("all"))]);
+    assert_eq!(div.get(&save).unwrap().inner_text(), "Save");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn xor_and_exactly_one_reject_both_and_neither() {
+    use crate::query::{HasRole, HasText, MatchesSelector, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<button class="busy">Both</button>
+        <button>Role</button>
+        <span class="busy">Class</span>
+        <span>Neither</span>
+        <p style="visibility: hidden;">Secret</p>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let texts = |matched: Vec<HtmlElement>| {
+        matched
+            .iter()
+            .map(|elem| elem.inner_text())
+            .collect::<Vec<_>>()
+    };
+    let xor = Xor(HasRole("button"), MatchesSelector(".busy"));
+    assert_eq!(texts(div.get_all(&xor)), ["Role", "Class"]);
+    assert_eq!(
+        xor.describe(),
+        r#"Xor(HasRole("button"), MatchesSelector(".busy"))"#
+    );
+
+    let one = ExactlyOne(vec![
+        Rc::new(HasRole("button")),
+        Rc::new(MatchesSelector(".busy")),
+        Rc::new(MatchesSelector("span")),
+    ]);
+    // "Class" is both a span and busy, "Neither" only a span
+    assert_eq!(texts(div.get_all(&one)), ["Role", "Neither"]);
+    assert_eq!(
+        one.describe(),
+        r#"exactly one of (HasRole("button"), MatchesSelector(".busy"), MatchesSelector("span"))"#
+    );
+    assert!(div.get(&ExactlyOne::default()).is_none());
+
+    // The hidden text matches only when ignoring visibility, which mustn't drop the match
+    let secret = div.get(&MatchesSelector("p")).unwrap();
+    let xor = Xor(HasText("Secret"), MatchesSelector("p"));
+    assert!(xor.matches(&secret));
+    assert!(xor.matches_ignoring_visibility(&secret));
+    let one = ExactlyOne(vec![
+        Rc::new(HasText("Secret")),
+        Rc::new(MatchesSelector("p")),
+    ]);
+    assert!(one.matches(&secret));
+    assert!(one.matches_ignoring_visibility(&secret));
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Options changing how [`Query`] traverses the DOM.
+///
+/// By default only the light DOM in the subtree of the queried root is searched.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+/// use web_sys::{ShadowRootInit, ShadowRootMode};
+///
+/// let host = document().create_element("div").unwrap();
+/// let shadow = host
+///     .attach_shadow(&ShadowRootInit::new(ShadowRootMode::Open))
+///     .unwrap();
+/// shadow.set_inner_html("<button>Inside shadow</button>");
+/// body().append_child(&host).unwrap();
+///
+/// assert!(screen().get(&HasRole("button")).is_none());
+/// let options = QueryOptions {
+///     pierce_shadow: true,
+///     ..Default::default()
+/// };
+/// assert!(screen().get_with(&HasRole("button"), options).is_some());
+///
+/// body().remove_child(&host).unwrap();
+/// ```
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct QueryOptions {
+    /// Descend into open shadow roots of web components.
+    ///
+    /// Content of a shadow root is visited right after its host and before the host's light DOM children,
+    /// the same way as it is rendered. Closed shadow roots are not reachable from javascript,
+    /// so their content is never matched.
+    pub pierce_shadow: bool,
+    /// Descend into documents of same-origin `<iframe>`s.
+    ///
+    /// Content of a frame's `<body>` is visited right after the `<iframe>` element.
+    /// Because of the same-origin policy, [`HtmlIFrameElement::content_document`] is only available
+    /// for frames loaded from the same origin as the tested page (or from `srcdoc`).
+    /// Cross-origin frames are silently skipped.
+    pub pierce_iframes: bool,
+    /// Descend into the content of `<template>` elements.
+    ///
+    /// Content of a template lives in a separate [`DocumentFragment`], which is visited
+    /// right after the `<template>` element. As it is never rendered, see the notes
+    /// on querying [`DocumentFragment`]s.
+    ///
+    /// Template content is inert until it is stamped, ie. cloned into the document: its event
+    /// handlers don't run and components inside aren't upgraded. Elements found this way
+    /// are the template itself, so changing them changes every future copy.
+    pub pierce_templates: bool,
+    /// Treat elements referenced with [`aria-owns`] as children of the owning element.
+    ///
+    /// Owned elements, with their subtrees, are visited right after the children of their owner
+    /// and in the order they are listed in the attribute, the same way assistive technologies see them.
+    /// This makes eg. a popup rendered at the end of `<body>` a part of the combobox owning it.
+    /// An element is visited only once, even if it is owned multiple times, and ownership
+    /// by element's own descendants is ignored.
+    ///
+    /// [`aria-owns`]: https://developer.mozilla.org/en-US/docs/Web/Accessibility/ARIA/Attributes/aria-owns
+    pub follow_aria_owns: bool,
+}
+
+/// Configuration of how [`Query::get`] reports failures.
+///
+/// By default [`get`] returns [`None`] when nothing is matched and panics when the match is ambiguous.
+/// The configuration can be changed for all subsequent queries with [`configure`], or just for
+/// a single [`Query`] with [`ConfiguredQuery`].
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<button>Ok</button><button>Cancel</button>"#);
+/// body().append_child(&div).unwrap();
+///
+/// let _config = configure(|config| config.panic_on_ambiguous(false));
+/// assert!(screen().get(&HasRole("button")).is_none());
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+///
+/// [`get`]: Query::get
+/// [`configure`]: crate::configure
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct QueryConfig {
+    panic_on_not_found: bool,
+    panic_on_ambiguous: bool,
+    suggest_queries: bool,
+    pub(crate) wait_options: WaitOptions,
+}
+
+impl Default for QueryConfig {
+    fn default() -> Self {
+        Self {
+            panic_on_not_found: false,
+            panic_on_ambiguous: true,
+            suggest_queries: false,
+            wait_options: WaitOptions::default(),
+        }
+    }
+}
+
+thread_local! {
+    static CONFIG: Cell<QueryConfig> = Cell::new(QueryConfig::default());
+}
+
+impl QueryConfig {
+    /// Returns the configuration currently set with [`configure`].
+    ///
+    /// [`configure`]: crate::configure
+    pub fn current() -> Self {
+        CONFIG.with(Cell::get)
+    }
+
+    /// Sets whether [`get`] should panic if no element is matched, instead of returning [`None`].
+    ///
+    /// [`get`]: Query::get
+    pub fn panic_on_not_found(mut self, panic: bool) -> Self {
+        self.panic_on_not_found = panic;
+        self
+    }
+
+    /// Sets whether [`get`] should panic if more than one element is matched, instead of returning [`None`].
+    ///
+    /// [`get`]: Query::get
+    pub fn panic_on_ambiguous(mut self, panic: bool) -> Self {
+        self.panic_on_ambiguous = panic;
+        self
+    }
+
+    /// Sets whether [`get`] should log a warning to the console when the found element could be
+    /// found with a matcher closer to how users find elements, see [`suggest_query`].
+    ///
+    /// The warning is logged only when the suggested matcher has a higher priority than the used one,
+    /// so eg. `HasRole("button")` isn't reported although `HasRole("button").named("Save")` is suggested.
+    ///
+    /// [`get`]: Query::get
+    /// [`suggest_query`]: crate::debug::suggest_query
+    pub fn suggest_queries(mut self, suggest: bool) -> Self {
+        self.suggest_queries = suggest;
+        self
+    }
+
+    /// Sets the [`WaitOptions`] used by async helpers like [`find`] called without explicit ones.
+    ///
+    /// [`find`]: crate::wait::find
+    pub fn wait_options(mut self, options: WaitOptions) -> Self {
+        self.wait_options = options;
+        self
+    }
+}
+
+/// Restores the previous [`QueryConfig`] when dropped.
+///
+/// Returned by [`configure`].
+///
+/// [`configure`]: crate::configure
+#[must_use = "the configuration is restored as soon as the guard is dropped"]
+#[derive(Debug)]
+pub struct ConfigGuard {
+    previous: QueryConfig,
+}
+
+impl ConfigGuard {
+    pub(crate) fn set(config: QueryConfig) -> Self {
+        Self {
+            previous: CONFIG.with(|current| current.replace(config)),
+        }
+    }
+}
+
+impl Drop for ConfigGuard {
+    fn drop(&mut self) {
+        CONFIG.with(|current| current.set(self.previous));
+    }
+}
+
+/// Returned by fallible queries when the DOM doesn't match the expectations.
+///
+/// See [`Query::try_get`] and [`Query::try_get_one`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum QueryError {
+    /// No element was matched where one was required.
+    NoMatch {
+        /// Description of the matcher, see [`Matcher::describe`].
+        matcher: String,
+        /// Pretty-printed HTML of everything that was searched, see [`Query::root_html`].
+        root_html: String,
+    },
+    /// More than one element was matched where a single one was expected.
+    TooManyMatches {
+        /// Description of the matcher, see [`Matcher::describe`].
+        matcher: String,
+        /// All matched elements, in document order.
+        matched: Vec<HtmlElement>,
+    },
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryError::NoMatch { matcher, root_html } if root_html.is_empty() => {
+                write!(f, "Found no element matching {}.", matcher)
+            }
+            QueryError::NoMatch { matcher, root_html } => {
+                write!(
+                    f,
+                    "Found no element matching {} in:\n{}",
+                    matcher, root_html
+                )
+            }
+            QueryError::TooManyMatches { matcher, matched } => write!(
+                f,
+                "Found {} elements matching {}:{}",
+                matched.len(),
+                matcher,
+                list_matches(matched)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// Returned when a matched element can't be cast into the requested type.
+///
+/// See [`Query::try_get_as`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CastError {
+    expected: &'static str,
+    element: HtmlElement,
+}
+
+impl CastError {
+    fn new<T>(element: HtmlElement) -> Self {
+        let expected = std::any::type_name::<T>();
+        Self {
+            // Strip the module path, eg. `web_sys::features::gen_HtmlInputElement::`
+            expected: expected.rsplit("::").next().unwrap_or(expected),
+            element,
+        }
+    }
+
+    /// Returns the name of the type the element was supposed to be cast into.
+    pub fn expected(&self) -> &str {
+        self.expected
+    }
+
+    /// Returns the element that couldn't be cast.
+    pub fn element(&self) -> &HtmlElement {
+        &self.element
+    }
+}
+
+impl std::fmt::Display for CastError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "expected the matched element to be {}, but it is {}",
+            self.expected,
+            opening_tag(&self.element)
+        )
+    }
+}
+
+impl std::error::Error for CastError {}
+
+/// Returns the html of the element's opening tag, with all its attributes.
+fn opening_tag(elem: &Element) -> String {
+    // Serializing a shallow clone avoids looking for the end of the tag in attribute values
+    let clone = elem
+        .clone_node()
+        .map(|node| node.unchecked_into::<Element>().outer_html())
+        .unwrap_or_else(|_| format!("<{}>", elem.local_name()));
+    let closing = format!("</{}>", elem.local_name());
+    clone.strip_suffix(&closing).unwrap_or(&clone).to_string()
+}
+
+/// Allows selecting [`HtmlElement`]s using [`Matcher`]s.
+///
+/// By default implemented for [`Element`] where it selects the element itself and it's children
+/// matching provided pattern,
+/// for [`Document`] where it selects elements from the whole document, for [`DocumentFragment`], for [`Screen`]
+/// and for collections of [`HtmlElement`]s where it selects from subtrees of all of them.
+pub trait Query {
+    /// Tries to get a unique component. Returns [`None`] on failure and [`HtmlElement`] on success.
+    ///
+    /// # Panics:
+    /// If more than one element is found. This, as well as panicking when no element is found,
+    /// can be changed with [`QueryConfig`].
+    /// The message starts with the location of the call, eg. `tests/form.rs:12:5`.
+    #[track_caller]
+    fn get<M: Matcher + ?Sized>(&self, rules: &M) -> Option<HtmlElement> {
+        self.get_with(rules, QueryOptions::default())
+    }
+
+    /// Fallible version of [`get`], which never panics.
+    ///
+    /// Returns `Ok(None)` if no element is found and [`QueryError::TooManyMatches`]
+    /// if more than one is, regardless of the [`QueryConfig`].
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use gloo::utils::{body, document};
+    /// use frontest::prelude::*;
+    /// use frontest::query::QueryError;
+    ///
+    /// let div = document().create_element("div").unwrap();
+    /// div.set_inner_html(r#"<button>Ok</button><button>Cancel</button>"#);
+    /// body().append_child(&div).unwrap();
+    ///
+    /// assert!(div.try_get(&HasRole("link")).unwrap().is_none());
+    /// assert!(div.try_get(&HasText("Ok")).unwrap().is_some());
+    /// assert!(matches!(
+    ///     div.try_get(&HasRole("button")),
+    ///     Err(QueryError::TooManyMatches { .. })
+    /// ));
+    ///
+    /// body().remove_child(&div).unwrap();
+    /// ```
+    ///
+    /// [`get`]: Query::get
+    fn try_get<M: Matcher + ?Sized>(&self, rules: &M) -> Result<Option<HtmlElement>, QueryError> {
+        let mut matched = self.get_n(rules, 2);
+        if matched.len() > 1 {
+            return Err(QueryError::TooManyMatches {
+                matcher: rules.describe(),
+                matched: self.get_all(rules),
+            });
+        }
+        Ok(matched.pop())
+    }
+
+    /// Fallible version of [`get`] which requires exactly one match and never panics.
+    ///
+    /// Unlike [`try_get`], not finding any element is an error too, [`QueryError::NoMatch`].
+    /// Both errors describe the matcher and either the searched HTML or all the matched elements,
+    /// so they are suitable for reporting failures by custom test harnesses.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use gloo::utils::{body, document};
+    /// use frontest::prelude::*;
+    /// use frontest::query::QueryError;
+    ///
+    /// let div = document().create_element("div").unwrap();
+    /// div.set_inner_html(r#"<button>Ok</button>"#);
+    /// body().append_child(&div).unwrap();
+    ///
+    /// assert!(div.try_get_one(&HasRole("button")).is_ok());
+    /// match div.try_get_one(&HasRole("link")) {
+    ///     Err(QueryError::NoMatch { matcher, .. }) => assert_eq!(matcher, r#"HasRole("link")"#),
+    ///     _ => unreachable!(),
+    /// }
+    ///
+    /// body().remove_child(&div).unwrap();
+    /// ```
+    ///
+    /// [`get`]: Query::get
+    /// [`try_get`]: Query::try_get
+    fn try_get_one<M: Matcher + ?Sized>(&self, rules: &M) -> Result<HtmlElement, QueryError> {
+        self.try_get(rules)?.ok_or_else(|| QueryError::NoMatch {
+            matcher: rules.describe(),
+            root_html: self.root_html(),
+        })
+    }
+
+    /// Returns a [`Vec`] of all components matched by a [`Matcher`].
+    ///
+    /// Elements are guaranteed to be returned in document order, that is the order
+    /// of depth-first pre-order traversal. Parents always come before their children
+    /// and children before following siblings of their parents. The queried root,
+    /// if it is matched itself, always comes first.
+    fn get_all<M: Matcher + ?Sized>(&self, rules: &M) -> Vec<HtmlElement> {
+        self.get_all_with(rules, QueryOptions::default())
+    }
+
+    /// Same as [`get`], but traverses the DOM according to given [`QueryOptions`].
+    ///
+    /// # Panics:
+    /// If more than one element is found, unless configured otherwise with [`QueryConfig`].
+    ///
+    /// [`get`]: Query::get
+    #[track_caller]
+    fn get_with<M: Matcher + ?Sized>(
+        &self,
+        rules: &M,
+        options: QueryOptions,
+    ) -> Option<HtmlElement> {
+        unique(
+            self.get_all_with(rules, options),
+            rules,
+            QueryConfig::current(),
+        )
+    }
+
+    /// Same as [`get_all`], but traverses the DOM according to given [`QueryOptions`].
+    ///
+    /// [`get_all`]: Query::get_all
+    fn get_all_with<M: Matcher + ?Sized>(
+        &self,
+        rules: &M,
+        options: QueryOptions,
+    ) -> Vec<HtmlElement>;
+
+    /// Returns the `n`-th (counting from zero) component matched by a [`Matcher`] in document order.
+    ///
+    /// Returns [`None`] if less than `n + 1` elements were matched.
+    fn get_nth<M: Matcher + ?Sized>(&self, rules: &M, n: usize) -> Option<HtmlElement> {
+        self.get_all(rules).into_iter().nth(n)
+    }
+
+    /// Returns the first component matched by a [`Matcher`] in document order.
+    ///
+    /// Unlike [`get`] it never panics when more than one element is matched,
+    /// regardless of the [`QueryConfig`]. Returns [`None`] if no element was matched.
+    /// The traversal stops at the first match where possible.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use gloo::utils::{body, document};
+    /// use frontest::prelude::*;
+    ///
+    /// let div = document().create_element("div").unwrap();
+    /// div.set_inner_html(r#"<button>Previous</button><button>Next</button>"#);
+    /// body().append_child(&div).unwrap();
+    ///
+    /// assert_eq!(div.get_first(&HasRole("button")).unwrap().inner_text(), "Previous");
+    /// assert_eq!(div.get_last(&HasRole("button")).unwrap().inner_text(), "Next");
+    ///
+    /// body().remove_child(&div).unwrap();
+    /// ```
+    ///
+    /// [`get`]: Query::get
+    fn get_first<M: Matcher + ?Sized>(&self, rules: &M) -> Option<HtmlElement> {
+        self.get_nth(rules, 0)
+    }
+
+    /// Returns the last component matched by a [`Matcher`] in document order.
+    ///
+    /// Unlike [`get`] it never panics when more than one element is matched,
+    /// regardless of the [`QueryConfig`]. Returns [`None`] if no element was matched.
+    ///
+    /// [`get`]: Query::get
+    fn get_last<M: Matcher + ?Sized>(&self, rules: &M) -> Option<HtmlElement> {
+        self.get_all(rules).pop()
+    }
+
+    /// Same as [`get`], but casts the matched element into a specific element type.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use gloo::utils::{body, document};
+    /// use frontest::prelude::*;
+    /// use web_sys::HtmlInputElement;
+    ///
+    /// let div = document().create_element("div").unwrap();
+    /// div.set_inner_html(r#"<label>Name <input type="text" value="Alice" /></label>"#);
+    /// body().append_child(&div).unwrap();
+    ///
+    /// let name = div.get_as::<HtmlInputElement, _>(&HasLabel("Name")).unwrap();
+    /// assert_eq!(name.value(), "Alice");
+    ///
+    /// body().remove_child(&div).unwrap();
+    /// ```
+    ///
+    /// # Panics:
+    /// If the matched element is not a `T`, with a message showing what it is instead,
+    /// and in all the cases in which [`get`] panics.
+    ///
+    /// [`get`]: Query::get
+    #[track_caller]
+    fn get_as<T: JsCast, M: Matcher + ?Sized>(&self, rules: &M) -> Option<T> {
+        match self.try_get_as(rules) {
+            Ok(elem) => elem,
+            Err(err) => panic!("{}: {}", Location::caller(), err),
+        }
+    }
+
+    /// Same as [`get_as`], but returns a [`CastError`] if the matched element is not a `T`.
+    ///
+    /// # Panics:
+    /// In all the cases in which [`get`] panics.
+    ///
+    /// [`get`]: Query::get
+    /// [`get_as`]: Query::get_as
+    #[track_caller]
+    fn try_get_as<T: JsCast, M: Matcher + ?Sized>(
+        &self,
+        rules: &M,
+    ) -> Result<Option<T>, CastError> {
+        self.get(rules)
+            .map(|elem| elem.dyn_into::<T>().map_err(CastError::new::<T>))
+            .transpose()
+    }
+
+    /// Same as [`get`], but matches only `<input>` elements and returns them already cast.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use gloo::utils::{body, document};
+    /// use frontest::prelude::*;
+    ///
+    /// let div = document().create_element("div").unwrap();
+    /// div.set_inner_html(
+    ///     r#"<label>Name <input type="text" value="Alice" /></label>
+    ///     <button>Name</button>"#,
+    /// );
+    /// body().append_child(&div).unwrap();
+    ///
+    /// let name = div.get_input(&HasLabel("Name")).unwrap();
+    /// assert_eq!(name.value(), "Alice");
+    ///
+    /// body().remove_child(&div).unwrap();
+    /// ```
+    ///
+    /// # Panics:
+    /// In all the cases in which [`get`] panics.
+    ///
+    /// [`get`]: Query::get
+    #[track_caller]
+    fn get_input<M: Matcher + ?Sized>(&self, rules: &M) -> Option<HtmlInputElement> {
+        self.get(&MatchesSelector("input").and(rules))
+            .map(JsCast::unchecked_into)
+    }
+
+    /// Same as [`get`], but matches only `<button>` elements and returns them already cast.
+    ///
+    /// See [`get_input`] for an example.
+    ///
+    /// # Panics:
+    /// In all the cases in which [`get`] panics.
+    ///
+    /// [`get`]: Query::get
+    /// [`get_input`]: Query::get_input
+    #[track_caller]
+    fn get_button<M: Matcher + ?Sized>(&self, rules: &M) -> Option<HtmlButtonElement> {
+        self.get(&MatchesSelector("button").and(rules))
+            .map(JsCast::unchecked_into)
+    }
+
+    /// Same as [`get`], but matches only `<select>` elements and returns them already cast.
+    ///
+    /// See [`get_input`] for an example.
+    ///
+    /// # Panics:
+    /// In all the cases in which [`get`] panics.
+    ///
+    /// [`get`]: Query::get
+    /// [`get_input`]: Query::get_input
+    #[track_caller]
+    fn get_select<M: Matcher + ?Sized>(&self, rules: &M) -> Option<HtmlSelectElement> {
+        self.get(&MatchesSelector("select").and(rules))
+            .map(JsCast::unchecked_into)
+    }
+
+    /// Same as [`get`], but matches only `<textarea>` elements and returns them already cast.
+    ///
+    /// See [`get_input`] for an example.
+    ///
+    /// # Panics:
+    /// In all the cases in which [`get`] panics.
+    ///
+    /// [`get`]: Query::get
+    /// [`get_input`]: Query::get_input
+    #[track_caller]
+    fn get_textarea<M: Matcher + ?Sized>(&self, rules: &M) -> Option<HtmlTextAreaElement> {
+        self.get(&MatchesSelector("textarea").and(rules))
+            .map(JsCast::unchecked_into)
+    }
+
+    /// Returns at most `n` first components matched by a [`Matcher`] in document order.
+    ///
+    /// Where possible, the traversal stops as soon as `n` elements were matched,
+    /// which makes it much cheaper than [`get_all`] on large documents.
+    ///
+    /// [`get_all`]: Query::get_all
+    fn get_n<M: Matcher + ?Sized>(&self, rules: &M, n: usize) -> Vec<HtmlElement> {
+        let mut matched = self.get_all(rules);
+        matched.truncate(n);
+        matched
+    }
+
+    /// Returns an iterator over components matched by a [`Matcher`] in document order.
+    ///
+    /// Where possible, elements are matched lazily while iterating, so taking only a few of them
+    /// or stopping at the first one satisfying some condition doesn't search the whole tree.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use gloo::utils::{body, document};
+    /// use frontest::prelude::*;
+    ///
+    /// let div = document().create_element("div").unwrap();
+    /// div.set_inner_html(r#"<ul><li>Apple</li><li>Banana</li><li>Cherry</li></ul>"#);
+    /// body().append_child(&div).unwrap();
+    ///
+    /// let banana = div
+    ///     .iter_matching(&HasRole("listitem"))
+    ///     .find(|item| item.inner_text().starts_with('B'));
+    /// assert_eq!(banana.unwrap().inner_text(), "Banana");
+    ///
+    /// body().remove_child(&div).unwrap();
+    /// ```
+    fn iter_matching<'a, M: Matcher + ?Sized>(
+        &'a self,
+        rules: &'a M,
+    ) -> impl Iterator<Item = HtmlElement> + 'a {
+        self.get_all(rules).into_iter()
+    }
+
+    /// Returns the position of an element among all components matched by a [`Matcher`],
+    /// or [`None`] if it isn't matched.
+    ///
+    /// The position is counted in document order, the same as of [`get_all`], so it allows
+    /// asserting the order of elements, eg. after reordering a list.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use gloo::utils::{body, document};
+    /// use frontest::prelude::*;
+    ///
+    /// let div = document().create_element("div").unwrap();
+    /// div.set_inner_html(r#"<ul><li>Apple</li><li>Banana</li><li>Cherry</li></ul>"#);
+    /// body().append_child(&div).unwrap();
+    ///
+    /// let cherry = div.get(&HasText("Cherry")).unwrap();
+    /// assert_eq!(div.match_index(&cherry, &HasRole("listitem")), Some(2));
+    /// assert_eq!(div.match_index(&cherry, &HasRole("button")), None);
+    ///
+    /// body().remove_child(&div).unwrap();
+    /// ```
+    ///
+    /// [`get_all`]: Query::get_all
+    fn match_index<M: Matcher + ?Sized>(&self, elem: &HtmlElement, rules: &M) -> Option<usize> {
+        self.iter_matching(rules)
+            .position(|matched| matched == *elem)
+    }
+
+    /// Returns all components matched by a [`Matcher`], asserting there are exactly `n` of them.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use gloo::utils::{body, document};
+    /// use frontest::prelude::*;
+    ///
+    /// let div = document().create_element("div").unwrap();
+    /// div.set_inner_html(r#"<button>Delete</button><button>Delete</button>"#);
+    /// body().append_child(&div).unwrap();
+    ///
+    /// let delete = div.expect_count(&HasText("Delete"), 2);
+    /// assert_eq!(delete[0].inner_text(), "Delete");
+    ///
+    /// body().remove_child(&div).unwrap();
+    /// ```
+    ///
+    /// # Panics:
+    /// If the number of matched elements is different than `n`. The message contains
+    /// the HTML of all matched elements, or the [`root_html`] if none was matched.
+    ///
+    /// [`root_html`]: Query::root_html
+    #[track_caller]
+    fn expect_count<M: Matcher + ?Sized>(&self, rules: &M, n: usize) -> Vec<HtmlElement> {
+        let matched = self.get_all(rules);
+        if matched.len() == n {
+            return matched;
+        }
+        let expected = match n {
+            1 => String::from("1 element"),
+            n => format!("{} elements", n),
+        };
+        if matched.is_empty() {
+            panic!(
+                "{}: expected {} matching {}, found 0 in:\n{}",
+                Location::caller(),
+                expected,
+                rules.describe(),
+                self.root_html()
+            );
+        }
+        panic!(
+            "{}: expected {} matching {}, found {}:\n{}",
+            Location::caller(),
+            expected,
+            rules.describe(),
+            matched.len(),
+            matched
+                .iter()
+                .map(|elem| snapshot_html(elem))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+
+    /// Asserts that exactly `expected` components are matched by a [`Matcher`].
+    ///
+    /// It is the same as [`expect_count`], for when the matched elements aren't needed.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use gloo::utils::{body, document};
+    /// use frontest::prelude::*;
+    ///
+    /// let div = document().create_element("div").unwrap();
+    /// div.set_inner_html(r#"<ul><li>First</li><li>Second</li><li>Third</li></ul>"#);
+    /// body().append_child(&div).unwrap();
+    ///
+    /// div.assert_count(&HasRole("listitem"), 3);
+    ///
+    /// body().remove_child(&div).unwrap();
+    /// ```
+    ///
+    /// # Panics:
+    /// If the number of matched elements is different than `expected`, with the same message as [`expect_count`].
+    ///
+    /// [`expect_count`]: Query::expect_count
+    #[track_caller]
+    fn assert_count<M: Matcher + ?Sized>(&self, rules: &M, expected: usize) {
+        self.expect_count(rules, expected);
+    }
+
+    /// Returns the pretty-printed HTML of everything searched by the query, used in failure messages.
+    ///
+    /// Implemented by all queries provided by this crate. For other implementations it is empty by default.
+    fn root_html(&self) -> String {
+        String::new()
+    }
+}
+
+/// Returns the only element of `matched`, or [`None`] if it is empty or ambiguous.
+///
+/// # Panics:
+/// If `matched` is empty or has more than one element and the [`QueryConfig`] says so.
+/// The message starts with the location of the caller.
+#[track_caller]
+fn unique<M: Matcher + ?Sized>(
+    mut matched: Vec<HtmlElement>,
+    matcher: &M,
+    config: QueryConfig,
+) -> Option<HtmlElement> {
+    match matched.len() {
+        0 if config.panic_on_not_found => {
+            panic!(
+                "{}: Found no element matching {}.",
+                Location::caller(),
+                matcher.describe()
+            )
+        }
+        0 => None,
+        1 => {
+            let elem = matched.pop().unwrap();
+            if config.suggest_queries {
+                warn_about_better_query(&elem, matcher);
+            }
+            Some(elem)
+        }
+        _ if config.panic_on_ambiguous => panic!(
+            "{}: {}",
+            Location::caller(),
+            QueryError::TooManyMatches {
+                matcher: matcher.describe(),
+                matched,
+            }
+        ),
+        _ => None,
+    }
+}
+
+/// Logs a warning if the element could be found with a matcher of a higher priority, see [`QueryConfig::suggest_queries`].
+#[track_caller]
+fn warn_about_better_query<M: Matcher + ?Sized>(elem: &HtmlElement, matcher: &M) {
+    if let Some(suggestion) = better_query(elem, matcher) {
+        gloo::console::warn!(format!(
+            "{}: Found <{}> with {}, consider using {} instead.",
+            Location::caller(),
+            elem.local_name(),
+            matcher.describe(),
+            suggestion
+        ));
+    }
+}
+
+/// How many matched elements are listed in failure messages.
+const LISTED_MATCHES: usize = 5;
+
+/// Longest text of an element shown in failure messages.
+const LISTED_TEXT_LEN: usize = 60;
+
+/// Returns a single-line summary of a matched element, eg. `<button id=save role=button> "Save"`.
+fn summary(elem: &HtmlElement) -> String {
+    let role = elem.get_attribute("role").or_else(|| {
+        element_to_aria_roles(elem)
+            .first()
+            .map(|role| role.to_string())
+    });
+    let attributes = [
+        ("id", elem.get_attribute("id")),
+        ("role", role),
+        ("data-testid", elem.get_attribute("data-testid")),
+    ]
+    .into_iter()
+    .filter_map(|(attr, value)| value.map(|value| format!(" {}={}", attr, value)))
+    .collect::<String>();
+    let mut text = normalize_whitespace(&elem.inner_text());
+    if let Some((idx, _)) = text.char_indices().nth(LISTED_TEXT_LEN) {
+        text.truncate(idx);
+        text.push_str("...");
+    }
+    format!("<{}{}> {:?}", elem.local_name(), attributes, text)
+}
+
+/// Lists summaries of the first few matched elements, each in a separate indented line.
+pub(crate) fn list_matches(matched: &[HtmlElement]) -> String {
+    let mut listed = matched
+        .iter()
+        .take(LISTED_MATCHES)
+        .map(|elem| format!("\n  {}", summary(elem)))
+        .collect::<String>();
+    if matched.len() > LISTED_MATCHES {
+        listed.push_str(&format!(
+            "\n  ...and {} more",
+            matched.len() - LISTED_MATCHES
+        ));
+    }
+    listed
+}
+
+/// Value of [`NodeFilter.SHOW_ELEMENT`], which isn't exposed by `web_sys`.
+///
+/// [`NodeFilter.SHOW_ELEMENT`]: https://developer.mozilla.org/en-US/docs/Web/API/Document/createTreeWalker#whattoshow
+const SHOW_ELEMENT: u32 = 0x1;
+
+/// Iterates over `root` and all its descendants in document order, according to given [`QueryOptions`].
+///
+/// Elements of the light DOM are visited lazily, so consumers can stop early without
+/// walking the whole subtree.
+pub(crate) fn candidates(
+    root: &Element,
+    options: QueryOptions,
+) -> Box<dyn Iterator<Item = HtmlElement>> {
+    let this = as_html_element(root.clone());
+    if options == QueryOptions::default() {
+        let walker = owner_document(root)
+            .create_tree_walker_with_what_to_show(root, SHOW_ELEMENT)
+            .unwrap();
+        // Walker was created with `SHOW_ELEMENT` so it returns only elements
+        let descendants = std::iter::from_fn(move || walker.next_node().unwrap())
+            .map(|node| node.unchecked_into::<Element>())
+            .filter_map(as_html_element);
+        return Box::new(this.into_iter().chain(descendants));
+    }
+    let mut collected = this.into_iter().collect::<Vec<_>>();
+    collect_descendants(root, options, &mut collected, &mut vec![root.clone()]);
+    // Elements reached through different paths, eg. owned elements through their place
+    // in the DOM, must be counted once
+    Box::new(dedup(collected).into_iter())
+}
+
+/// Removes repeated elements, compared by their identity, keeping the first occurrence.
+fn dedup(elems: impl IntoIterator<Item = HtmlElement>) -> Vec<HtmlElement> {
+    // Javascript sets compare objects by identity
+    let seen = js_sys::Set::new(&JsValue::UNDEFINED);
+    elems
+        .into_iter()
+        .filter(|elem| {
+            let first = !seen.has(elem);
+            seen.add(elem);
+            first
+        })
+        .collect()
+}
+
+/// Same as [`candidates`], but narrowed down with [`Matcher::selector_hint`] where possible.
+fn candidates_for<M: Matcher + ?Sized>(
+    root: &Element,
+    matcher: &M,
+    options: QueryOptions,
+) -> Box<dyn Iterator<Item = HtmlElement>> {
+    let hint = match matcher.selector_hint() {
+        Some(hint) if options == QueryOptions::default() => hint,
+        _ => return candidates(root, options),
+    };
+    // Let the matcher itself report invalid selectors
+    let (this, selected) = match (root.matches(&hint), root.query_selector_all(&hint)) {
+        (Ok(this), Ok(selected)) => (this, selected),
+        _ => return candidates(root, options),
+    };
+    let this = as_html_element(root.clone()).filter(|_| this);
+    let descendants = (0..selected.length())
+        .filter_map(move |idx| selected.get(idx))
+        // `querySelectorAll` returns only elements
+        .map(|node| node.unchecked_into::<Element>())
+        .filter_map(as_html_element);
+    Box::new(this.into_iter().chain(descendants))
+}
+
+fn collect_descendants(
+    root: &Element,
+    options: QueryOptions,
+    collected: &mut Vec<HtmlElement>,
+    owned: &mut Vec<Element>,
+) {
+    let children =
+        |children: HtmlCollection| (0..children.length()).filter_map(move |idx| children.item(idx));
+    let mut visited = vec![];
+    if options.pierce_shadow {
+        if let Some(shadow) = root.shadow_root() {
+            visited.extend(children(shadow.children()));
+        }
+    }
+    if options.pierce_iframes && root.local_name() == "iframe" {
+        // Cross-origin frames don't expose their document
+        let frame = root.unchecked_ref::<HtmlIFrameElement>();
+        if let Some(body) = frame.content_document().and_then(|doc| doc.body()) {
+            visited.extend(children(body.children()));
+        }
+    }
+    if options.pierce_templates && root.local_name() == "template" {
+        let template = root.unchecked_ref::<HtmlTemplateElement>();
+        visited.extend(children(template.content().children()));
+    }
+    visited.extend(children(root.children()));
+    if options.follow_aria_owns {
+        for child in id_references(root, "aria-owns") {
+            // Each element is visited once and can't be owned by its own descendant
+            let seen = owned.contains(&child) || collected.iter().any(|elem| **elem == child);
+            if !seen && !child.contains(Some(root)) {
+                owned.push(child.clone());
+                visited.push(child);
+            }
+        }
+    }
+    for child in visited {
+        if let Some(child) = as_html_element(child.clone()) {
+            collected.push(child);
+        }
+        collect_descendants(&child, options, collected, owned);
+    }
+}
+
+/// Returns elements referenced by an attribute with a space-separated list of ids, in the given order.
+///
+/// Ids which don't belong to any element are skipped.
+fn id_references(elem: &Element, attribute: &str) -> Vec<Element> {
+    let document = owner_document(elem);
+    elem.get_attribute(attribute)
+        .unwrap_or_default()
+        .split_whitespace()
+        .filter_map(|id| document.get_element_by_id(id))
+        .collect()
+}
+
+impl Query for Element {
+    fn root_html(&self) -> String {
+        snapshot_html(self)
+    }
+
+    fn get_with<M: Matcher + ?Sized>(
+        &self,
+        matcher: &M,
+        options: QueryOptions,
+    ) -> Option<HtmlElement> {
+        let config = QueryConfig::current();
+        let piercing = PiercingShadow::set(options);
+        // Two matches are enough to know the query is ambiguous
+        let matched = candidates_for(self, matcher, options)
+            .filter(|elem| matcher.matches(elem))
+            .take(2)
+            .collect::<Vec<_>>();
+        drop(piercing);
+        if matched.len() > 1 && config.panic_on_ambiguous {
+            // Collect all the matches to report them
+            return unique(self.get_all_with(matcher, options), matcher, config);
+        }
+        unique(matched, matcher, config)
+    }
+
+    fn get_nth<M: Matcher + ?Sized>(&self, matcher: &M, n: usize) -> Option<HtmlElement> {
+        candidates_for(self, matcher, QueryOptions::default())
+            .filter(|elem| matcher.matches(elem))
+            .nth(n)
+    }
+
+    fn get_n<M: Matcher + ?Sized>(&self, matcher: &M, n: usize) -> Vec<HtmlElement> {
+        candidates_for(self, matcher, QueryOptions::default())
+            .filter(|elem| matcher.matches(elem))
+            .take(n)
+            .collect()
+    }
+
+    fn iter_matching<'a, M: Matcher + ?Sized>(
+        &'a self,
+        matcher: &'a M,
+    ) -> impl Iterator<Item = HtmlElement> + 'a {
+        candidates_for(self, matcher, QueryOptions::default())
+            .filter(move |elem| matcher.matches(elem))
+    }
+
+    fn get_all_with<M: Matcher + ?Sized>(
+        &self,
+        matcher: &M,
+        options: QueryOptions,
+    ) -> Vec<HtmlElement> {
+        let _piercing = PiercingShadow::set(options);
+        // Get all nodes matching given text
+        candidates_for(self, matcher, options)
+            .filter(|e| matcher.matches(e))
+            .collect::<Vec<_>>()
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn query_selector_hints_keep_results() {
+    use crate::query::{
+        HasDataAttribute, HasLabel, HasPlaceholder, HasRole, HasText, Joinable, Matcher,
+        MatchesSelector, Not, Query,
+    };
+    use gloo::utils::{body, document};
+
+    // Optionally hides the hint of the wrapped matcher, so all elements are checked.
+    struct Hinted<'a>(&'a dyn Matcher, bool);
+    impl Matcher for Hinted<'_> {
+        fn matches(&self, elem: &HtmlElement) -> bool {
+            self.0.matches(elem)
+        }
+
+        fn selector_hint(&self) -> Option<String> {
+            self.0.selector_hint().filter(|_| self.1)
+        }
+    }
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<nav>
+            <ul>
+                <li><a href="/">Home</a></li>
+                <li><span role="link">Fake link</span></li>
+            </ul>
+        </nav>
+        <form>
+            <h2>Sign "in"</h2>
+            <label>User <input type="text" placeholder="user" /></label>
+            <textarea placeholder="about user"></textarea>
+            <input type="button" value="Cancel" />
+            <div role="button" data-state-open="true">Help</div>
+            <button>Submit</button>
+        </form>
+        <table role="grid"><tr><td>Cell</td></tr></table>
+        <div role='say "hi"'>Odd role</div>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let matchers: Vec<Box<dyn Matcher>> = vec![
+        Box::new(HasRole("button")),
+        Box::new(HasRole("link")),
+        Box::new(HasRole("gridcell")),
+        Box::new(HasRole("textbox")),
+        Box::new(HasRole("heading").named("Sign \"in\"")),
+        Box::new(HasRole("say \"hi\"")),
+        Box::new(HasRole("generic")),
+        Box::new(HasPlaceholder("user")),
+        Box::new(HasLabel("User ")),
+        Box::new(MatchesSelector("li > *")),
+        Box::new(HasRole("button").and(HasText("Help"))),
+        Box::new(HasRole("link").and(MatchesSelector("[href]"))),
+        Box::new(HasRole("button").or(HasPlaceholder("user"))),
+        Box::new(Not(HasRole("listitem")).and(HasDataAttribute::any_with_prefix("data-"))),
+    ];
+    for matcher in &matchers {
+        assert_eq!(
+            div.get_all(&Hinted(matcher.as_ref(), true)),
+            div.get_all(&Hinted(matcher.as_ref(), false)),
+            "{}",
+            matcher.describe()
+        );
+    }
+    let form = div.get(&HasRole("form")).unwrap();
+    assert_eq!(form.get_all(&MatchesSelector("form, input")).len(), 3);
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn query_stops_traversal_early() {
+    use crate::query::fixtures::Counting;
+    use crate::query::{MatchesSelector, Query};
+    use gloo::utils::document;
+    use std::cell::Cell;
+
+    let buttons = MatchesSelector("button");
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(&format!(
+        "<button>First</button><button>Second</button>{}",
+        "<p><span></span></p>".repeat(5000)
+    ));
+
+    let touched = Cell::new(0);
+    let first = div.get_first(&Counting(&touched, &buttons)).unwrap();
+    assert_eq!(first.inner_text(), "First");
+    assert!(touched.get() < 10, "touched {} nodes", touched.get());
+
+    touched.set(0);
+    assert_eq!(div.get_n(&Counting(&touched, &buttons), 2).len(), 2);
+    assert!(touched.get() < 10, "touched {} nodes", touched.get());
+
+    touched.set(0);
+    assert_eq!(div.get_all(&Counting(&touched, &buttons)).len(), 2);
+    assert_eq!(touched.get(), 10_003);
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn query_get_stops_after_second_match() {
+    use crate::configure;
+    use crate::query::fixtures::Counting;
+    use crate::query::{MatchesSelector, Query};
+    use gloo::utils::document;
+    use std::cell::Cell;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(&format!(
+        "<button>First</button><button>Second</button>{}",
+        "<p><span></span></p>".repeat(5000)
+    ));
+    let _config = configure(|config| config.panic_on_ambiguous(false));
+    let touched = Cell::new(0);
+    assert!(div
+        .get(&Counting(&touched, MatchesSelector("button")))
+        .is_none());
+    // Traversal stops right after the ambiguous matches
+    assert!(touched.get() < 10, "touched {} nodes", touched.get());
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+#[should_panic(
+    expected = "Found 7 elements matching HasRole(\"button\"):\n  <button role=button> \"Ok\"\n  <div id=cancel role=button data-testid=cancel> \"Cancel\"\n  <button role=button> \"Retry\"\n  <button role=button> \"012345678901234567890123456789012345678901234567890123456789...\"\n  <button role=button> \"\"\n  ...and 2 more"
+)]
+fn query_get_lists_ambiguous_matches() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::document;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(&format!(
+        r#"<button>Ok</button>
+        <div id="cancel" role="button" data-testid="cancel">  Cancel  </div>
+        <button>Retry</button>
+        <button>{}</button>
+        <button></button>
+        <button>Sixth</button>
+        <button>Seventh</button>"#,
+        "0123456789".repeat(7)
+    ));
+    div.get(&HasRole("button"));
+}
+
+/// Selects elements from the whole document.
+///
+/// Content of the `<head>` element, like `<title>` or `<style>`, is never matched
+/// as it isn't presented to the user.
+impl Query for Document {
+    fn root_html(&self) -> String {
+        self.body()
+            .map(|body| snapshot_html(&body))
+            .unwrap_or_default()
+    }
+
+    fn get_all_with<M: Matcher + ?Sized>(
+        &self,
+        matcher: &M,
+        options: QueryOptions,
+    ) -> Vec<HtmlElement> {
+        let root = match self.document_element() {
+            Some(root) => root,
+            None => return vec![],
+        };
+        let head = self.head();
+        root.get_all_with(matcher, options)
+            .into_iter()
+            .filter(|e| match &head {
+                Some(head) => !head.contains(Some(e)),
+                None => true,
+            })
+            .collect()
+    }
+}
+
+/// Selects elements from the subtrees of all children of a [`DocumentFragment`].
+///
+/// Elements of a fragment aren't rendered, so no css rules apply to them. For such elements
+/// browsers return the same value from [`inner_text`] as from [`text_content`], so [`HasText`]
+/// matches them by their raw text and doesn't respect visibility.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::document;
+/// use frontest::prelude::*;
+/// use wasm_bindgen::JsCast;
+///
+/// let template = document().create_element("template").unwrap();
+/// template.set_inner_html(r#"<li><button>Remove</button></li>"#);
+/// let fragment = template.unchecked_into::<web_sys::HtmlTemplateElement>().content();
+///
+/// assert!(fragment.get(&HasRole("listitem")).is_some());
+/// assert!(fragment.get(&HasText("Remove")).is_some());
+/// ```
+///
+/// [`inner_text`]: web_sys::HtmlElement::inner_text
+/// [`text_content`]: web_sys::Node::text_content
+impl Query for DocumentFragment {
+    fn root_html(&self) -> String {
+        let children = self.children();
+        (0..children.length())
+            .filter_map(|idx| children.item(idx))
+            .map(|child| snapshot_html(&child))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn get_all_with<M: Matcher + ?Sized>(
+        &self,
+        matcher: &M,
+        options: QueryOptions,
+    ) -> Vec<HtmlElement> {
+        let children = self.children();
+        (0..children.length())
+            .filter_map(|idx| children.item(idx))
+            .flat_map(|child| child.get_all_with(matcher, options))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_query_document_fragment() {
+    use crate::query::{HasRole, HasText, Query};
+    use gloo::utils::document;
+
+    let template = document().create_element("template").unwrap();
+    template.set_inner_html(r#"<li><button>Remove</button></li>"#);
+    let fragment = template.unchecked_into::<HtmlTemplateElement>().content();
+
+    assert!(fragment.get(&HasRole("listitem")).is_some());
+    assert!(fragment.get(&HasText("Remove")).is_some());
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn query_document_fragment_and_template_content() {
+    use crate::query::{HasRole, HasText, Query, QueryOptions};
+    use gloo::utils::{body, document};
+
+    let fragment = document().create_document_fragment();
+    let first = document().create_element("p").unwrap();
+    first.set_inner_html("<span>First</span>");
+    let second = document().create_element("button").unwrap();
+    second.set_text_content(Some("Second"));
+    fragment.append_child(&first).unwrap();
+    fragment.append_child(&second).unwrap();
+    assert_eq!(fragment.get(&HasText("First")).unwrap().tag_name(), "SPAN");
+    assert_eq!(fragment.get_all(&HasRole("button")).len(), 1);
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<template><button>In template</button></template>
+        <button>Outside</button>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    assert_eq!(div.get_all(&HasRole("button")).len(), 1);
+    let options = QueryOptions {
+        pierce_templates: true,
+        ..Default::default()
+    };
+    let buttons = div.get_all_with(&HasRole("button"), options);
+    assert_eq!(buttons.len(), 2);
+    assert_eq!(buttons[0].inner_text(), "In template");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn stamped_template_content_is_found_without_piercing() {
+    use crate::query::{HasRole, HasText, Joinable, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<template><li><button>Remove</button></li></template>
+        <ul></ul>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let options = QueryOptions {
+        pierce_templates: true,
+        ..Default::default()
+    };
+    let remove = HasRole("button").and(HasText("Remove"));
+    assert!(div.get(&remove).is_none());
+    let in_template = div.get_with(&remove, options).unwrap();
+    assert!(!in_template.is_connected());
+
+    let template = div.first_element_child().unwrap();
+    let content = template.unchecked_into::<HtmlTemplateElement>().content();
+    let stamped = document().import_node_with_deep(&content, true).unwrap();
+    div.last_element_child()
+        .unwrap()
+        .append_child(&stamped)
+        .unwrap();
+
+    let stamped = div.get(&remove).unwrap();
+    assert!(stamped.is_connected());
+    assert_eq!(div.get_all_with(&remove, options), [in_template, stamped]);
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Compares two nodes by their position in the document.
+fn document_order(a: &HtmlElement, b: &HtmlElement) -> Ordering {
+    if a == b {
+        Ordering::Equal
+    } else if a.compare_document_position(b) & Node::DOCUMENT_POSITION_FOLLOWING != 0 {
+        Ordering::Less
+    } else {
+        Ordering::Greater
+    }
+}
+
+/// Selects elements from subtrees of all elements in the collection.
+///
+/// Results are flattened and returned in document order. Elements reachable from more than
+/// one root, eg. when the collection contains both a parent and its child, are returned only once.
+///
+/// This allows chaining queries, for example to select the row containing a given text:
+///
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let table = document().create_element("table").unwrap();
+/// table.set_inner_html(
+///     r#"<tr><td>Alice</td><td>Admin</td></tr>
+///     <tr><td>Bob</td><td>User</td></tr>"#,
+/// );
+/// body().append_child(&table).unwrap();
+///
+/// let rows = table.get_all(&HasRole("row"));
+/// let alice = rows.get(&HasText("Alice")).unwrap();
+/// assert_eq!(alice.inner_text(), "Alice");
+///
+/// body().remove_child(&table).unwrap();
+/// ```
+///
+/// Note that when called on a slice rather than a [`Vec`], method `get` resolves to [`slice::get`],
+/// so [`Query::get`] has to be called explicitly.
+impl Query for [HtmlElement] {
+    fn root_html(&self) -> String {
+        self.iter()
+            .map(|elem| snapshot_html(elem))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn get_all_with<M: Matcher + ?Sized>(
+        &self,
+        matcher: &M,
+        options: QueryOptions,
+    ) -> Vec<HtmlElement> {
+        let mut matched = dedup(
+            self.iter()
+                .flat_map(|root| root.get_all_with(matcher, options)),
+        );
+        matched.sort_by(document_order);
+        matched
+    }
+}
+
+/// Selects elements from subtrees of all elements in the collection.
+///
+/// See the implementation for [`slice`](#impl-Query-for-%5BHtmlElement%5D) for details.
+impl Query for Vec<HtmlElement> {
+    fn root_html(&self) -> String {
+        self.as_slice().root_html()
+    }
+
+    fn get_all_with<M: Matcher + ?Sized>(
+        &self,
+        matcher: &M,
+        options: QueryOptions,
+    ) -> Vec<HtmlElement> {
+        self.as_slice().get_all_with(matcher, options)
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn query_collection_deduplicates_overlapping_subtrees() {
+    use crate::query::{HasRole, HasText, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<section id="outer">
+            <button>Outer</button>
+            <section id="inner">
+                <button>Inner</button>
+            </section>
+        </section>
+        <table>
+            <tr><td>Alice</td></tr>
+            <tr><td>Bob</td></tr>
+        </table>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let outer = document().get_element_by_id("outer").unwrap();
+    let inner = document().get_element_by_id("inner").unwrap();
+    // Inner section comes first to make sure the results are still in document order.
+    let sections = vec![inner.unchecked_into(), outer.unchecked_into()];
+    let buttons = sections.get_all(&HasRole("button"));
+    assert_eq!(buttons.len(), 2);
+    assert_eq!(buttons[0].inner_text(), "Outer");
+    assert_eq!(buttons[1].inner_text(), "Inner");
+
+    let rows = div.get_all(&HasRole("row"));
+    let bob = rows.get(&HasText("Bob")).unwrap();
+    assert_eq!(bob.tag_name(), "TD");
+
+    body().remove_child(&div).unwrap();
+}
+
+/// Extends [`HtmlElement`] with queries going beyond its subtree.
+pub trait QueryExt {
+    /// Returns the closest element matched by a [`Matcher`], starting from the element itself
+    /// and walking up through its ancestors.
+    ///
+    /// It works like [`Element::closest`], but accepts any [`Matcher`] instead of a css selector.
+    /// The search stops at the `<body>` of the document.
+    ///
+    /// Note that with this trait in scope, [`Element::closest`] has to be called explicitly on [`HtmlElement`]s,
+    /// eg. `Element::closest(&elem, "table")`, or with [`MatchesSelector`] matcher.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use gloo::utils::{body, document};
+    /// use frontest::prelude::*;
+    ///
+    /// let div = document().create_element("div").unwrap();
+    /// div.set_inner_html(
+    ///     r#"<table>
+    ///         <tr id="alice"><td>Alice</td><td><button>Remove</button></td></tr>
+    ///         <tr id="bob"><td>Bob</td><td><button>Remove</button></td></tr>
+    ///     </table>"#,
+    /// );
+    /// body().append_child(&div).unwrap();
+    ///
+    /// let bob = div.get(&HasText("Bob")).unwrap();
+    /// let row = bob.closest(&HasRole("row")).unwrap();
+    /// assert_eq!(row.id(), "bob");
+    /// assert!(row.get(&HasRole("button")).is_some());
+    ///
+    /// body().remove_child(&div).unwrap();
+    /// ```
+    fn closest<M: Matcher + ?Sized>(&self, matcher: &M) -> Option<HtmlElement>;
+
+    /// Returns the first of following siblings of the element matched by a [`Matcher`].
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # use gloo::utils::{body, document};
+    /// use frontest::prelude::*;
+    ///
+    /// let div = document().create_element("div").unwrap();
+    /// div.set_inner_html(
+    ///     r#"<form>
+    ///         <label>Email <input type="text" /></label>
+    ///         <p role="alert">Invalid email</p>
+    ///     </form>"#,
+    /// );
+    /// body().append_child(&div).unwrap();
+    ///
+    /// let email = div.get(&HasLabel("Email")).unwrap();
+    /// let field = email.closest(&MatchesSelector("label")).unwrap();
+    /// assert!(field.next_matching(&HasRole("alert")).is_some());
+    ///
+    /// body().remove_child(&div).unwrap();
+    /// ```
+    fn next_matching<M: Matcher + ?Sized>(&self, matcher: &M) -> Option<HtmlElement>;
+
+    /// Returns the first of preceding siblings of the element matched by a [`Matcher`],
+    /// searching from the closest one.
+    fn prev_matching<M: Matcher + ?Sized>(&self, matcher: &M) -> Option<HtmlElement>;
+
+    /// Returns the first element matched by a [`Matcher`] that follows the element in document order,
+    /// searching the subtree of `root`.
+    ///
+    /// Like the `following` axis of XPath, it skips descendants of the element, but crosses
+    /// the boundaries of its parent.
+    fn following<M: Matcher + ?Sized>(&self, matcher: &M, root: &Element) -> Option<HtmlElement>;
+}
+
+impl QueryExt for HtmlElement {
+    fn closest<M: Matcher + ?Sized>(&self, matcher: &M) -> Option<HtmlElement> {
+        let mut current = Some(self.clone());
+        while let Some(elem) = current {
+            if matcher.matches(&elem) {
+                return Some(elem);
+            }
+            if elem.local_name() == "body" {
+                return None;
+            }
+            current = elem.parent_element().and_then(as_html_element);
+        }
+        None
+    }
+
+    fn next_matching<M: Matcher + ?Sized>(&self, matcher: &M) -> Option<HtmlElement> {
+        std::iter::successors(self.next_element_sibling(), Element::next_element_sibling)
+            .filter_map(as_html_element)
+            .find(|sibling| matcher.matches(sibling))
+    }
+
+    fn prev_matching<M: Matcher + ?Sized>(&self, matcher: &M) -> Option<HtmlElement> {
+        std::iter::successors(
+            self.previous_element_sibling(),
+            Element::previous_element_sibling,
+        )
+        .filter_map(as_html_element)
+        .find(|sibling| matcher.matches(sibling))
+    }
+
+    fn following<M: Matcher + ?Sized>(&self, matcher: &M, root: &Element) -> Option<HtmlElement> {
+        candidates(root, QueryOptions::default())
+            .skip_while(|elem| elem != self)
+            .skip(1)
+            .filter(|elem| !self.contains(Some(elem)))
+            .find(|elem| matcher.matches(elem))
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_query_ext_closest() {
+    use crate::query::{HasRole, HasText, Query, QueryExt};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<table>
+            <tr id="alice"><td>Alice</td><td><button>Remove</button></td></tr>
+            <tr id="bob"><td>Bob</td><td><button>Remove</button></td></tr>
+        </table>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let bob = div.get(&HasText("Bob")).unwrap();
+    let row = bob.closest(&HasRole("row")).unwrap();
+    assert_eq!(row.id(), "bob");
+    assert!(row.get(&HasRole("button")).is_some());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn query_ext_closest_is_inclusive_and_stops_at_body() {
+    use crate::query::{HasRole, MatchesSelector, Query, QueryExt};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<nav><button>Menu</button></nav>"#);
+    body().append_child(&div).unwrap();
+
+    let button = div.get(&HasRole("button")).unwrap();
+    assert_eq!(button.closest(&HasRole("button")).unwrap(), button);
+    assert!(button.closest(&HasRole("navigation")).is_some());
+    assert!(button.closest(&HasRole("row")).is_none());
+    assert!(button.closest(&MatchesSelector("html")).is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_query_ext_next_matching() {
+    use crate::query::{HasLabel, HasRole, MatchesSelector, Query, QueryExt};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<form>
+            <label>Email <input type="text" /></label>
+            <p role="alert">Invalid email</p>
+        </form>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let email = div.get(&HasLabel("Email")).unwrap();
+    let field = email.closest(&MatchesSelector("label")).unwrap();
+    assert!(field.next_matching(&HasRole("alert")).is_some());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn query_ext_sibling_navigation() {
+    use crate::query::{HasRole, HasText, Query, QueryExt};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<section>
+            <h2>First</h2>
+            <p>Intro</p>
+            <button>Middle</button>
+            <p>Details</p>
+            <h2>Second</h2>
+        </section>
+        <aside><button>Outside</button></aside>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let middle = div.get(&HasText("Middle")).unwrap();
+    let next = middle.next_matching(&HasRole("heading")).unwrap();
+    assert_eq!(next.inner_text(), "Second");
+    let prev = middle.prev_matching(&HasRole("heading")).unwrap();
+    assert_eq!(prev.inner_text(), "First");
+    assert!(middle.next_matching(&HasRole("button")).is_none());
+
+    let outside = middle.following(&HasRole("button"), &div).unwrap();
+    assert_eq!(outside.inner_text(), "Outside");
+    let section = div.get(&HasText("Details")).unwrap();
+    let section = section
+        .parent_element()
+        .unwrap()
+        .unchecked_into::<HtmlElement>();
+    // Descendants of the element itself are skipped
+    assert_eq!(
+        section.following(&HasRole("button"), &div).unwrap(),
+        outside
+    );
+    assert!(outside.following(&HasRole("button"), &div).is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+/// A [`Query`] over the whole [`body`] of the document.
+///
+/// Obtained with [`screen`]. It allows querying rendered elements without passing their
+/// mount-point around.
+///
+/// [`body`]: gloo::utils::body
+/// [`screen`]: crate::screen
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Screen;
+
+impl Query for Screen {
+    fn root_html(&self) -> String {
+        body().root_html()
+    }
+
+    fn get_with<M: Matcher + ?Sized>(
+        &self,
+        matcher: &M,
+        options: QueryOptions,
+    ) -> Option<HtmlElement> {
+        body().get_with(matcher, options)
+    }
+
+    fn get_nth<M: Matcher + ?Sized>(&self, matcher: &M, n: usize) -> Option<HtmlElement> {
+        body().get_nth(matcher, n)
+    }
+
+    fn get_n<M: Matcher + ?Sized>(&self, matcher: &M, n: usize) -> Vec<HtmlElement> {
+        body().get_n(matcher, n)
+    }
+
+    fn iter_matching<'a, M: Matcher + ?Sized>(
+        &'a self,
+        matcher: &'a M,
+    ) -> impl Iterator<Item = HtmlElement> + 'a {
+        candidates_for(&body(), matcher, QueryOptions::default())
+            .filter(move |elem| matcher.matches(elem))
+    }
+
+    fn get_all_with<M: Matcher + ?Sized>(
+        &self,
+        matcher: &M,
+        options: QueryOptions,
+    ) -> Vec<HtmlElement> {
+        body().get_all_with(matcher, options)
+    }
+}
+
+/// A [`Query`] with its own [`QueryConfig`], independent of the one set with [`configure`].
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+/// use frontest::query::{ConfiguredQuery, QueryConfig};
+///
+/// let div = document().create_element("div").unwrap();
+/// body().append_child(&div).unwrap();
+///
+/// let strict = ConfiguredQuery::new(&div, QueryConfig::default().panic_on_not_found(true));
+/// // Panics with: Found no element matching HasRole("button").
+/// strict.get(&HasRole("button"));
+/// ```
+///
+/// [`configure`]: crate::configure
+#[derive(Debug)]
+pub struct ConfiguredQuery<'a, Q: ?Sized> {
+    query: &'a Q,
+    config: QueryConfig,
+}
+
+impl<'a, Q: Query + ?Sized> ConfiguredQuery<'a, Q> {
+    /// Wraps a [`Query`] so it reports failures according to `config`.
+    pub fn new(query: &'a Q, config: QueryConfig) -> Self {
+        Self { query, config }
+    }
+}
+
+impl<Q: Query + ?Sized> Query for ConfiguredQuery<'_, Q> {
+    fn root_html(&self) -> String {
+        self.query.root_html()
+    }
+
+    fn get_with<M: Matcher + ?Sized>(
+        &self,
+        matcher: &M,
+        options: QueryOptions,
+    ) -> Option<HtmlElement> {
+        unique(
+            self.query.get_all_with(matcher, options),
+            matcher,
+            self.config,
+        )
+    }
+
+    fn get_all_with<M: Matcher + ?Sized>(
+        &self,
+        matcher: &M,
+        options: QueryOptions,
+    ) -> Vec<HtmlElement> {
+        self.query.get_all_with(matcher, options)
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn configured_query_never_panics() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button>Ok</button><button>Cancel</button>"#);
+    body().append_child(&div).unwrap();
+
+    let lenient = ConfiguredQuery::new(&div, QueryConfig::default().panic_on_ambiguous(false));
+    assert!(lenient.get(&HasRole("button")).is_none());
+    assert!(lenient.get(&HasRole("link")).is_none());
+    assert_eq!(lenient.get_all(&HasRole("button")).len(), 2);
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_try_get() {
+    use crate::query::{HasRole, HasText, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button>Ok</button><button>Cancel</button>"#);
+    body().append_child(&div).unwrap();
+
+    assert!(div.try_get(&HasRole("link")).unwrap().is_none());
+    assert!(div.try_get(&HasText("Ok")).unwrap().is_some());
+    assert!(matches!(
+        div.try_get(&HasRole("button")),
+        Err(QueryError::TooManyMatches { .. })
+    ));
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn try_get_ignores_query_config() {
+    use crate::query::{HasRole, HasText, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<button>Ok</button>
+        <button>Cancel</button>
+        <button>Retry</button>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let config = QueryConfig::default()
+        .panic_on_not_found(true)
+        .panic_on_ambiguous(true);
+    let strict = ConfiguredQuery::new(&div, config);
+    assert_eq!(strict.try_get(&HasRole("link")), Ok(None));
+    assert_eq!(
+        strict
+            .try_get(&HasText("Retry"))
+            .unwrap()
+            .unwrap()
+            .inner_text(),
+        "Retry"
+    );
+    let err = strict.try_get(&HasRole("button")).unwrap_err();
+    let QueryError::TooManyMatches { matcher, matched } = &err else {
+        unreachable!()
+    };
+    assert_eq!(matcher, r#"HasRole("button")"#);
+    // All matches are reported, not only the first two found
+    assert_eq!(matched.len(), 3);
+    assert!(err.to_string().starts_with(
+        "Found 3 elements matching HasRole(\"button\"):\n  <button role=button> \"Ok\""
+    ));
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_try_get_one() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button>Ok</button>"#);
+    body().append_child(&div).unwrap();
+
+    assert!(div.try_get_one(&HasRole("button")).is_ok());
+    match div.try_get_one(&HasRole("link")) {
+        Err(QueryError::NoMatch { matcher, .. }) => assert_eq!(matcher, r#"HasRole("link")"#),
+        _ => unreachable!(),
+    }
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn query_errors_carry_context() {
+    use crate::query::{HasRole, HasText, Joinable, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<button id="ok">Ok</button>
+        <a href="/help" role="button">Help</a>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let missing = HasRole("link").and(HasText("Help"));
+    let err = div.try_get_one(&missing).unwrap_err();
+    assert_eq!(
+        err,
+        QueryError::NoMatch {
+            matcher: String::from(r#"HasRole("link").and(HasText("Help"))"#),
+            root_html: div.root_html(),
+        }
+    );
+    assert!(err.to_string().starts_with(
+        "Found no element matching HasRole(\"link\").and(HasText(\"Help\")) in:\n<div>\n  <button id=\"ok\">"
+    ));
+    let err = QueryError::NoMatch {
+        matcher: String::from(r#"HasRole("link")"#),
+        root_html: String::new(),
+    };
+    assert_eq!(
+        err.to_string(),
+        r#"Found no element matching HasRole("link")."#
+    );
+
+    let err = div.try_get_one(&HasRole("button")).unwrap_err();
+    let QueryError::TooManyMatches { matcher, matched } = &err else {
+        unreachable!()
+    };
+    assert_eq!(matcher, r#"HasRole("button")"#);
+    assert_eq!(matched.len(), 2);
+    assert_eq!(matched[1].local_name(), "a");
+    assert_eq!(
+        err.to_string(),
+        "Found 2 elements matching HasRole(\"button\"):\n  <button id=ok role=button> \"Ok\"\n  <a role=button> \"Help\""
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_get_first() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button>Previous</button><button>Next</button>"#);
+    body().append_child(&div).unwrap();
+
+    assert_eq!(
+        div.get_first(&HasRole("button")).unwrap().inner_text(),
+        "Previous"
+    );
+    assert_eq!(
+        div.get_last(&HasRole("button")).unwrap().inner_text(),
+        "Next"
+    );
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_get_as() {
+    use crate::query::{HasLabel, Query};
+    use gloo::utils::{body, document};
+    use web_sys::HtmlInputElement;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<label>Name <input type="text" value="Alice" /></label>"#);
+    body().append_child(&div).unwrap();
+
+    let name = div
+        .get_as::<HtmlInputElement, _>(&HasLabel("Name"))
+        .unwrap();
+    assert_eq!(name.value(), "Alice");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_get_input() {
+    use crate::query::{HasLabel, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<label>Name <input type="text" value="Alice" /></label>
+        <button>Name</button>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let name = div.get_input(&HasLabel("Name")).unwrap();
+    assert_eq!(name.value(), "Alice");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn typed_getters_match_only_their_elements() {
+    use crate::query::{HasLabel, HasText, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<label>Color
+            <select><option>Red</option><option selected>Blue</option></select>
+        </label>
+        <label>Bio <textarea>Hello</textarea></label>
+        <button type="submit">Send</button>
+        <input type="submit" value="Send" />"#,
+    );
+    body().append_child(&div).unwrap();
+
+    assert_eq!(div.get_select(&HasLabel("Color")).unwrap().value(), "Blue");
+    assert_eq!(div.get_textarea(&HasLabel("Bio")).unwrap().value(), "Hello");
+    // Both buttons have the text, but only one of them is a `<button>`
+    assert_eq!(div.get_button(&HasText("Send")).unwrap().type_(), "submit");
+    assert_eq!(div.get_input(&HasText("Send")).unwrap().value(), "Send");
+    assert!(div.get_input(&HasLabel("Bio")).is_none());
+    assert!(div.get_textarea(&HasLabel("Color")).is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn try_get_as_names_the_actual_element() {
+    use crate::query::{HasText, Query};
+    use gloo::utils::{body, document};
+    use web_sys::HtmlInputElement;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<div id="name" class="field">Alice</div>"#);
+    body().append_child(&div).unwrap();
+
+    let err = div
+        .try_get_as::<HtmlInputElement, _>(&HasText("Alice"))
+        .unwrap_err();
+    assert_eq!(err.expected(), "HtmlInputElement");
+    assert_eq!(err.element().id(), "name");
+    assert_eq!(
+        err.to_string(),
+        r#"expected the matched element to be HtmlInputElement, but it is <div id="name" class="field">"#
+    );
+    assert!(div
+        .try_get_as::<HtmlInputElement, _>(&HasText("Bob"))
+        .unwrap()
+        .is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+#[should_panic(expected = "expected the matched element to be HtmlInputElement, but it is <div>")]
+fn get_as_panics_on_wrong_type() {
+    use crate::query::{HasText, Query};
+    use gloo::utils::document;
+    use web_sys::HtmlInputElement;
+
+    // Not attached, as the panic skips removing it and it would leak into other tests
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<div>Alice</div>"#);
+
+    div.get_as::<HtmlInputElement, _>(&HasText("Alice"));
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn get_first_and_last_ignore_query_config() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button>Previous</button><button>Next</button>"#);
+    body().append_child(&div).unwrap();
+
+    let config = QueryConfig::default()
+        .panic_on_not_found(true)
+        .panic_on_ambiguous(true);
+    let strict = ConfiguredQuery::new(&div, config);
+    assert_eq!(
+        strict.get_first(&HasRole("button")).unwrap().inner_text(),
+        "Previous"
+    );
+    assert_eq!(
+        strict.get_last(&HasRole("button")).unwrap().inner_text(),
+        "Next"
+    );
+    assert!(strict.get_first(&HasRole("link")).is_none());
+    assert!(strict.get_last(&HasRole("link")).is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+#[should_panic(expected = "Found no element matching HasRole(\"link\").")]
+fn configured_query_panics_on_not_found() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::document;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button>Ok</button>"#);
+
+    let strict = ConfiguredQuery::new(&div, QueryConfig::default().panic_on_not_found(true));
+    assert!(strict.get(&HasRole("button")).is_some());
+    strict.get(&HasRole("link"));
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+#[should_panic(expected = "Found 2 elements matching HasRole(\"button\"):")]
+fn configured_query_panics_on_ambiguous() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::document;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button>Ok</button><button>Cancel</button>"#);
+
+    // Explicitly enabled even though it is the default
+    let strict = ConfiguredQuery::new(&div, QueryConfig::default().panic_on_ambiguous(true));
+    strict.get(&HasRole("button"));
+}
+
+/// Returns a short, single-line summary of an element, eg. `<section aria-label=Billing>`.
+fn breadcrumb(elem: &Element) -> String {
+    let attributes = ["id", "role", "aria-label", "data-testid"]
+        .iter()
+        .filter_map(|attr| {
+            elem.get_attribute(attr)
+                .map(|value| format!(" {}={}", attr, value))
+        })
+        .collect::<String>();
+    format!("<{}{}>", elem.local_name(), attributes)
+}
+
+/// A [`Query`] scoped to a subtree of a given element.
+///
+/// Obtained with [`within`]. Selecting elements with it works the same way as selecting them
+/// directly from the scoping element, however [`Within`] remembers the chain of scopes, so
+/// failure messages can point out where the query was performed.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::{body, document};
+/// use frontest::prelude::*;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(
+///     r#"<section aria-label="Billing">
+///         <fieldset>
+///             <button>Save</button>
+///         </fieldset>
+///     </section>
+///     <section aria-label="Shipping">
+///         <button>Save</button>
+///     </section>"#,
+/// );
+/// body().append_child(&div).unwrap();
+///
+/// let billing = within(&div).within(&HasRole("group"));
+/// assert!(billing.get(&HasRole("button")).is_some());
+/// assert_eq!(within(&div).get_all(&HasRole("button")).len(), 2);
+///
+/// body().remove_child(&div).unwrap();
+/// ```
+///
+/// [`within`]: crate::within
+#[derive(Clone, Debug)]
+pub struct Within {
+    root: Element,
+    chain: Vec<String>,
 }
 
-impl<'a> Matcher for Or<'a> {
-    fn matches(&self, elem: &HtmlElement) -> bool {
-        self.filters.iter().any(|f| f.matches(elem))
+impl Within {
+    /// Creates a [`Query`] scoped to the given element.
+    pub fn new(root: impl AsRef<Element>) -> Self {
+        let root = root.as_ref().clone();
+        let chain = vec![breadcrumb(&root)];
+        Self { root, chain }
     }
-}
 
-/// Allows selecting [`HtmlElement`]s using [`Matcher`]s.
-///
-/// By default implemented for [`Element`] where it selects it's children matching provided pattern.
-pub trait Query {
-    /// Tries to get a unique component. Returns [`None`] on failure and [`HtmlElement`] on success.
+    /// Narrows the scope to the unique element matched by a [`Matcher`].
     ///
     /// # Panics:
-    /// If more than one element is found.
-    fn get<M: Matcher>(&self, rules: &M) -> Option<HtmlElement>;
+    /// If there isn't exactly one matching element. The message contains the whole chain of scopes.
+    #[track_caller]
+    pub fn within<M: Matcher + ?Sized>(&self, matcher: &M) -> Self {
+        let root = match self.root.get_all(matcher).as_slice() {
+            [elem] => Element::clone(elem),
+            [] => panic!(
+                "{}: {}: no element matching {}",
+                Location::caller(),
+                self.location(),
+                matcher.describe()
+            ),
+            matched => panic!(
+                "{}: {}: found {} elements matching {}:{}",
+                Location::caller(),
+                self.location(),
+                matched.len(),
+                matcher.describe(),
+                list_matches(matched)
+            ),
+        };
+        let mut chain = self.chain.clone();
+        chain.push(breadcrumb(&root));
+        Self { root, chain }
+    }
 
-    /// Returns a [`Vec`] of all components matched by a [`Matcher`].
-    fn get_all<M: Matcher>(&self, rules: &M) -> Vec<HtmlElement>;
+    /// Returns the element this query is scoped to.
+    pub fn root(&self) -> &Element {
+        &self.root
+    }
+
+    /// Describes the chain of scopes, eg. `within <section aria-label=Billing> > <fieldset>`.
+    pub fn location(&self) -> String {
+        format!("within {}", self.chain.join(" > "))
+    }
 }
 
-impl Query for Element {
-    fn get<M: Matcher>(&self, matcher: &M) -> Option<HtmlElement> {
-        let selected = self.query_selector_all("*").unwrap();
-        // Get all nodes matching given text
-        let mut preprocessed = (0..selected.length())
-            .filter_map(|idx| selected.get(idx))
-            .filter_map(|node| node.dyn_into::<HtmlElement>().ok())
-            .filter(|e| matcher.matches(e))
-            .collect::<Vec<_>>();
+impl AsRef<Element> for Within {
+    fn as_ref(&self) -> &Element {
+        &self.root
+    }
+}
+
+impl Query for Within {
+    fn root_html(&self) -> String {
+        self.root.root_html()
+    }
 
-        match preprocessed.len() {
-            0 => None,
-            1 => Some(preprocessed.pop().unwrap()),
-            _ => panic!("Found more than one element."),
+    fn get_with<M: Matcher + ?Sized>(
+        &self,
+        matcher: &M,
+        options: QueryOptions,
+    ) -> Option<HtmlElement> {
+        let config = QueryConfig::current();
+        let mut matched = self.root.get_all_with(matcher, options);
+        match matched.len() {
+            0 if config.panic_on_not_found => panic!(
+                "{}: {}: no element matching {}",
+                Location::caller(),
+                self.location(),
+                matcher.describe()
+            ),
+            1 => {
+                let elem = matched.pop().unwrap();
+                if config.suggest_queries {
+                    warn_about_better_query(&elem, matcher);
+                }
+                Some(elem)
+            }
+            n if n > 1 && config.panic_on_ambiguous => panic!(
+                "{}: {}: found {} elements matching {}:{}",
+                Location::caller(),
+                self.location(),
+                n,
+                matcher.describe(),
+                list_matches(&matched)
+            ),
+            _ => None,
         }
     }
 
-    fn get_all<M: Matcher>(&self, matcher: &M) -> Vec<HtmlElement> {
-        let selected = self.query_selector_all("*").unwrap();
-        // Get all nodes matching given text
-        (0..selected.length())
-            .filter_map(|idx| selected.get(idx))
-            .filter_map(|node| node.dyn_into::<HtmlElement>().ok())
-            .filter(|e| matcher.matches(e))
-            .collect::<Vec<_>>()
+    fn get_all_with<M: Matcher + ?Sized>(
+        &self,
+        matcher: &M,
+        options: QueryOptions,
+    ) -> Vec<HtmlElement> {
+        self.root.get_all_with(matcher, options)
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_within() {
+    use crate::query::{HasRole, Query};
+    use crate::within;
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<section aria-label="Billing">
+            <fieldset>
+                <button>Save</button>
+            </fieldset>
+        </section>
+        <section aria-label="Shipping">
+            <button>Save</button>
+        </section>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let billing = within(&div).within(&HasRole("group"));
+    assert!(billing.get(&HasRole("button")).is_some());
+    assert_eq!(within(&div).get_all(&HasRole("button")).len(), 2);
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+#[should_panic(
+    expected = "within <div> > <section aria-label=Billing>: no element matching HasRole(\"button\")"
+)]
+fn within_reports_scope_chain_on_failure() {
+    use crate::within;
+    use gloo::utils::document;
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<section aria-label="Billing">
+            <p>Nothing to click here</p>
+        </section>"#,
+    );
+
+    struct IsSection;
+
+    impl Matcher for IsSection {
+        fn matches(&self, elem: &HtmlElement) -> bool {
+            elem.tag_name() == "SECTION"
+        }
     }
+
+    let billing = within(&div).within(&IsSection);
+    billing.within(&HasRole("button"));
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn query_document_skips_head() {
+    use crate::query::{HasText, Query};
+    use gloo::utils::{body, document};
+    let title = document().create_element("title").unwrap();
+    title.set_text_content(Some("Only in the head"));
+    document().head().unwrap().append_child(&title).unwrap();
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<p>Only in the body</p>"#);
+    body().append_child(&div).unwrap();
+
+    assert!(document().get(&HasText("Only in the head")).is_none());
+    assert!(document().get(&HasText("Only in the body")).is_some());
+
+    body().remove_child(&div).unwrap();
+    title.remove();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn query_positional_accessors_follow_document_order() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<ul>
+            <li>first
+                <ul>
+                    <li>nested</li>
+                </ul>
+            </li>
+            <li>last</li>
+        </ul>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    // A breadth-first traversal would yield "last" before "nested".
+    let items = div
+        .get_all(&HasRole("listitem"))
+        .into_iter()
+        .map(|li| li.text_content().unwrap())
+        .collect::<Vec<_>>();
+    assert!(items[0].trim().starts_with("first"));
+    assert_eq!(items[1].trim(), "nested");
+    assert_eq!(items[2].trim(), "last");
+
+    let nested = div.get_nth(&HasRole("listitem"), 1).unwrap();
+    assert_eq!(nested.text_content().unwrap().trim(), "nested");
+    let first = div.get_first(&HasRole("listitem")).unwrap();
+    assert!(first.text_content().unwrap().trim().starts_with("first"));
+    let last = div.get_last(&HasRole("listitem")).unwrap();
+    assert_eq!(last.text_content().unwrap().trim(), "last");
+
+    let two = div.get_n(&HasRole("listitem"), 2);
+    assert_eq!(two.len(), 2);
+    assert_eq!(two[1], nested);
+    assert_eq!(div.get_n(&HasRole("listitem"), 5).len(), 3);
+    assert!(div.get_n(&HasRole("listitem"), 0).is_empty());
+
+    assert!(div.get_nth(&HasRole("listitem"), 3).is_none());
+    assert!(div.get_first(&HasRole("button")).is_none());
+    assert!(div.get_last(&HasRole("button")).is_none());
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_expect_count() {
+    use crate::query::{HasText, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<button>Delete</button><button>Delete</button>"#);
+    body().append_child(&div).unwrap();
+
+    let delete = div.expect_count(&HasText("Delete"), 2);
+    assert_eq!(delete[0].inner_text(), "Delete");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+#[should_panic(
+    expected = "expected 1 element matching HasRole(\"button\"), found 2:\n<button>\n  Ok\n</button>\n<button>\n  Cancel\n</button>"
+)]
+fn expect_count_lists_extra_matches() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::document;
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<p>Sure?</p><button>Ok</button><button>Cancel</button>"#);
+
+    div.expect_count(&HasRole("button"), 1);
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+#[should_panic(
+    expected = "expected 3 elements matching HasRole(\"link\"), found 0 in:\n<div>\n  <p>\n    Sure?\n  </p>\n</div>"
+)]
+fn expect_count_shows_root_without_matches() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::document;
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<p>Sure?</p>"#);
+
+    div.expect_count(&HasRole("link"), 3);
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_assert_count() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<ul><li>First</li><li>Second</li><li>Third</li></ul>"#);
+    body().append_child(&div).unwrap();
+
+    div.assert_count(&HasRole("listitem"), 3);
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+#[should_panic(
+    expected = "expected 3 elements matching HasRole(\"listitem\"), found 2:\n<li>\n  First\n</li>\n<li>\n  Second\n</li>"
+)]
+fn assert_count_reports_the_difference() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::document;
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<ul><li>First</li><li>Second</li></ul>"#);
+
+    div.assert_count(&HasRole("listitem"), 3);
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_iter_matching() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<ul><li>Apple</li><li>Banana</li><li>Cherry</li></ul>"#);
+    body().append_child(&div).unwrap();
+
+    let banana = div
+        .iter_matching(&HasRole("listitem"))
+        .find(|item| item.inner_text().starts_with('B'));
+    assert_eq!(banana.unwrap().inner_text(), "Banana");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn iter_matching_stops_after_taken_elements() {
+    use crate::query::fixtures::Counting;
+    use crate::query::{HasRole, Query};
+    use crate::screen;
+    use gloo::utils::{body, document};
+    use std::cell::Cell;
+
+    let div = document().create_element("div").unwrap();
+    let items = (0..1000)
+        .map(|i| format!("<li>Item {}</li>", i))
+        .collect::<String>();
+    div.set_inner_html(&format!("<ul>{}</ul>", items));
+    body().append_child(&div).unwrap();
+
+    let checked = Cell::new(0);
+    let first = div
+        .iter_matching(&Counting(&checked, HasRole("listitem")))
+        .take(2)
+        .map(|item| item.inner_text())
+        .collect::<Vec<_>>();
+    assert_eq!(first, ["Item 0", "Item 1"]);
+    // The `div`, the `ul` and the two items
+    assert_eq!(checked.get(), 4);
+
+    let last = screen().iter_matching(&HasRole("listitem")).last();
+    assert_eq!(last.unwrap().inner_text(), "Item 999");
+    // Same as `get_all` for other queries
+    let items = vec![div.clone().unchecked_into::<HtmlElement>()];
+    assert_eq!(items.iter_matching(&HasRole("list")).count(), 1);
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_match_index() {
+    use crate::query::{HasRole, HasText, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<ul><li>Apple</li><li>Banana</li><li>Cherry</li></ul>"#);
+    body().append_child(&div).unwrap();
+
+    let cherry = div.get(&HasText("Cherry")).unwrap();
+    assert_eq!(div.match_index(&cherry, &HasRole("listitem")), Some(2));
+    assert_eq!(div.match_index(&cherry, &HasRole("button")), None);
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn match_index_follows_reordering() {
+    use crate::query::{HasRole, HasText, Joinable, MatchesSelector, Query};
+    use gloo::utils::{body, document};
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<ol>
+            <li>Write tests</li>
+            <li class="done">Fix bug</li>
+            <li>Release</li>
+        </ol>
+        <p>Release notes</p>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let item = HasRole("listitem");
+    let release = div.get(&item.and(HasText("Release"))).unwrap();
+    assert_eq!(div.match_index(&release, &item), Some(2));
+    // Moving the item to the top, like dragging it would
+    let list = div.get(&HasRole("list")).unwrap();
+    list.insert_before(&release, list.first_child().as_ref())
+        .unwrap();
+    assert_eq!(div.match_index(&release, &item), Some(0));
+    let bug = div.get(&HasText("Fix bug")).unwrap();
+    assert_eq!(div.match_index(&bug, &item), Some(2));
+    assert_eq!(div.match_index(&bug, &MatchesSelector(".done")), Some(0));
+
+    let notes = div.get(&HasText("Release notes")).unwrap();
+    assert_eq!(div.match_index(&notes, &item), None);
+    // Elements outside of the root are never matched
+    assert_eq!(list.match_index(&notes, &HasText("Release notes")), None);
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_query_options() {
+    use crate::query::{HasRole, Query, QueryOptions};
+    use crate::screen;
+    use gloo::utils::{body, document};
+    use web_sys::{ShadowRootInit, ShadowRootMode};
+
+    let host = document().create_element("div").unwrap();
+    let shadow = host
+        .attach_shadow(&ShadowRootInit::new(ShadowRootMode::Open))
+        .unwrap();
+    shadow.set_inner_html("<button>Inside shadow</button>");
+    body().append_child(&host).unwrap();
+
+    assert!(screen().get(&HasRole("button")).is_none());
+    let options = QueryOptions {
+        pierce_shadow: true,
+        ..Default::default()
+    };
+    assert!(screen().get_with(&HasRole("button"), options).is_some());
+
+    body().remove_child(&host).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn query_pierce_shadow_keeps_document_order() {
+    use crate::query::{HasRole, Query, QueryOptions};
+    use gloo::utils::{body, document};
+    use web_sys::{ShadowRootInit, ShadowRootMode};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<button>Before</button>
+        <custom-host><button>Slotted</button></custom-host>
+        <button>After</button>"#,
+    );
+    let host = div.query_selector("custom-host").unwrap().unwrap();
+    let shadow = host
+        .attach_shadow(&ShadowRootInit::new(ShadowRootMode::Open))
+        .unwrap();
+    shadow.set_inner_html("<button>Shadow</button><slot></slot>");
+    body().append_child(&div).unwrap();
+
+    let options = QueryOptions {
+        pierce_shadow: true,
+        ..Default::default()
+    };
+    let buttons = div
+        .get_all_with(&HasRole("button"), options)
+        .into_iter()
+        .map(|button| button.inner_text())
+        .collect::<Vec<_>>();
+    assert_eq!(buttons, ["Before", "Shadow", "Slotted", "After"]);
+    assert_eq!(div.get_all(&HasRole("button")).len(), 3);
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn query_piercing_never_repeats_elements() {
+    use crate::query::{HasRole, Query, QueryOptions};
+    use futures::channel::oneshot;
+    use gloo::events::EventListener;
+    use gloo::utils::{body, document};
+    use web_sys::{ShadowRootInit, ShadowRootMode};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<custom-menu><li id="slotted" role="menuitem">Copy</li></custom-menu>
+        <iframe srcdoc="<li role='menuitem'>Paste</li>"></iframe>
+        <li id="later" role="menuitem">Cut</li>"#,
+    );
+    let host = div.query_selector("custom-menu").unwrap().unwrap();
+    let shadow = host
+        .attach_shadow(&ShadowRootInit::new(ShadowRootMode::Open))
+        .unwrap();
+    // The menu owns both the slotted item and the one after the host
+    shadow.set_inner_html(r#"<ul role="menu" aria-owns="slotted later"><slot></slot></ul>"#);
+    let iframe = div.query_selector("iframe").unwrap().unwrap();
+    let (loaded, on_load) = oneshot::channel();
+    let _listener = EventListener::once(&iframe, "load", move |_| {
+        loaded.send(()).unwrap();
+    });
+    body().append_child(&div).unwrap();
+    on_load.await.unwrap();
+
+    let assert_unique = |items: &[HtmlElement]| {
+        for (idx, item) in items.iter().enumerate() {
+            assert!(!items[idx + 1..].contains(item));
+        }
+    };
+    // The slotted item is reached through the host, not through the slot
+    let piercing = QueryOptions {
+        pierce_shadow: true,
+        pierce_iframes: true,
+        ..Default::default()
+    };
+    let items = div.get_all_with(&HasRole("menuitem"), piercing);
+    assert_eq!(items.len(), 3);
+    assert_unique(&items);
+    assert!(div.get_with(&HasText("Copy"), piercing).is_some());
+
+    let options = QueryOptions {
+        follow_aria_owns: true,
+        ..piercing
+    };
+    let items = div.get_all_with(&HasRole("menuitem"), options);
+    assert_eq!(items.len(), 3);
+    assert_unique(&items);
+    assert!(div.get_with(&HasText("Copy"), options).is_some());
+    div.expect_count(&HasRole("menuitem"), 2);
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn query_pierce_iframes_finds_frame_content() {
+    use crate::query::{HasLabel, HasRole, Query, QueryOptions};
+    use futures::channel::oneshot;
+    use gloo::events::EventListener;
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    let iframe = document()
+        .create_element("iframe")
+        .unwrap()
+        .unchecked_into::<HtmlIFrameElement>();
+    iframe.set_srcdoc(
+        r#"<label for="card">Card number</label>
+        <input id="card" />
+        <button>Pay</button>"#,
+    );
+    div.append_child(&iframe).unwrap();
+    let (loaded, on_load) = oneshot::channel();
+    let _listener = EventListener::once(&iframe, "load", move |_| {
+        loaded.send(()).unwrap();
+    });
+    body().append_child(&div).unwrap();
+    on_load.await.unwrap();
+
+    let options = QueryOptions {
+        pierce_iframes: true,
+        ..Default::default()
+    };
+    assert!(div.get(&HasLabel("Card number")).is_none());
+    let card = div.get_with(&HasLabel("Card number"), options).unwrap();
+    assert_eq!(card.id(), "card");
+
+    assert!(div.get_all(&HasRole("button")).is_empty());
+    let buttons = div.get_all_with(&HasRole("button"), options);
+    assert_eq!(buttons.len(), 1);
+    assert_eq!(buttons[0].text_content().unwrap(), "Pay");
+
+    body().remove_child(&div).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn query_follow_aria_owns_reaches_owned_popup() {
+    use crate::query::{HasRole, Query, QueryOptions};
+    use crate::within;
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<div id="combo" role="combobox" aria-owns="fruits missing fruits">
+            <input type="text" aria-controls="fruits" />
+        </div>"#,
+    );
+    body().append_child(&div).unwrap();
+    let popup = document().create_element("ul").unwrap();
+    popup.set_id("fruits");
+    popup.set_attribute("role", "listbox").unwrap();
+    // Ownership cycles are ignored
+    popup.set_attribute("aria-owns", "combo fruits").unwrap();
+    popup.set_inner_html(r#"<li role="option">Apple</li><li role="option">Pear</li>"#);
+    body().append_child(&popup).unwrap();
+
+    let options = QueryOptions {
+        follow_aria_owns: true,
+        ..Default::default()
+    };
+    let combo = within(&div).within(&HasRole("combobox"));
+    assert!(combo.get_all(&HasRole("option")).is_empty());
+    let fruits = combo.get_all_with(&HasRole("option"), options);
+    assert_eq!(fruits.len(), 2);
+    assert_eq!(fruits[0].inner_text(), "Apple");
+
+    // Elements owned from inside the scope are visited once, where they are owned
+    let all = div.get_all_with(&HasRole("listbox").or(HasRole("textbox")), options);
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[0].local_name(), "input");
+    assert_eq!(all[1].id(), "fruits");
+    assert_eq!(body().get_all_with(&HasRole("option"), options).len(), 2);
+
+    body().remove_child(&div).unwrap();
+    body().remove_child(&popup).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn query_includes_root_element() {
+    use crate::query::{HasRole, Query};
+    use gloo::utils::{body, document};
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(
+        r#"<button>Alone</button>
+        <ul id="outer">
+            <li><ul id="nested"><li>Nested</li></ul></li>
+        </ul>"#,
+    );
+    body().append_child(&div).unwrap();
+
+    let button = div.get(&HasRole("button")).unwrap();
+    assert_eq!(button.get(&HasRole("button")).unwrap(), button);
+
+    let outer = document().get_element_by_id("outer").unwrap();
+    let lists = outer.get_all(&HasRole("list"));
+    assert_eq!(lists.len(), 2);
+    assert_eq!(lists[0].id(), "outer");
+    assert_eq!(lists[1].id(), "nested");
+
+    body().remove_child(&div).unwrap();
 }
@@ -0,0 +1,145 @@
+use wasm_bindgen::JsCast;
+use web_sys::{Element, Node};
+
+/// Elements that can't have any content and are never closed.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Serializes an element's subtree into a normalized and indented HTML.
+///
+/// The output is deterministic, so it is suitable for comparing against a stored snapshot:
+/// - every element and text is placed on a separate line, indented by two spaces per level,
+/// - attributes are sorted by their names,
+/// - whitespace inside texts is collapsed and whitespace-only texts are skipped.
+///
+/// # Example:
+/// ```no_run
+/// # use gloo::utils::document;
+/// use frontest::snapshot::snapshot_html;
+///
+/// let div = document().create_element("div").unwrap();
+/// div.set_inner_html(r#"<p id="greeting" class="big">  Hello   world  </p>"#);
+///
+/// assert_eq!(
+///     snapshot_html(&div),
+///     "<div>\n  <p class=\"big\" id=\"greeting\">\n    Hello world\n  </p>\n</div>"
+/// );
+/// ```
+pub fn snapshot_html(elem: &Element) -> String {
+    let mut lines = vec![];
+    write_element(elem, 0, &mut lines);
+    lines.join("\n")
+}
+
+fn write_node(node: &Node, depth: usize, lines: &mut Vec<String>) {
+    let indent = "  ".repeat(depth);
+    match node.node_type() {
+        Node::ELEMENT_NODE => write_element(node.unchecked_ref(), depth, lines),
+        Node::TEXT_NODE => {
+            let text = node.text_content().unwrap_or_default();
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !text.is_empty() {
+                lines.push(format!("{}{}", indent, escape(&text, false)));
+            }
+        }
+        Node::COMMENT_NODE => {
+            let comment = node.text_content().unwrap_or_default();
+            lines.push(format!("{}<!--{}-->", indent, comment.trim()));
+        }
+        _ => {}
+    }
+}
+
+fn write_element(elem: &Element, depth: usize, lines: &mut Vec<String>) {
+    let indent = "  ".repeat(depth);
+    let tag = elem.local_name();
+
+    let attributes = elem.attributes();
+    let mut attributes = (0..attributes.length())
+        .filter_map(|idx| attributes.item(idx))
+        .map(|attr| (attr.name(), attr.value()))
+        .collect::<Vec<_>>();
+    attributes.sort();
+    let attributes = attributes
+        .iter()
+        .map(|(name, value)| format!(" {}=\"{}\"", name, escape(value, true)))
+        .collect::<String>();
+
+    if VOID_ELEMENTS.contains(&tag.as_str()) {
+        lines.push(format!("{}<{}{}>", indent, tag, attributes));
+        return;
+    }
+
+    let opening = lines.len();
+    lines.push(format!("{}<{}{}>", indent, tag, attributes));
+    let children = elem.child_nodes();
+    for child in (0..children.length()).filter_map(|idx| children.get(idx)) {
+        write_node(&child, depth + 1, lines);
+    }
+    if lines.len() == opening + 1 {
+        lines[opening].push_str(&format!("</{}>", tag));
+    } else {
+        lines.push(format!("{}</{}>", indent, tag));
+    }
+}
+
+fn escape(text: &str, attribute: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_snapshot_html() {
+    use gloo::utils::document;
+
+    let div = document().create_element("div").unwrap();
+    div.set_inner_html(r#"<p id="greeting" class="big">  Hello   world  </p>"#);
+
+    assert_eq!(
+        snapshot_html(&div),
+        "<div>\n  <p class=\"big\" id=\"greeting\">\n    Hello world\n  </p>\n</div>"
+    );
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn snapshot_html_ignores_attribute_order() {
+    use gloo::utils::document;
+
+    let first = document().create_element("div").unwrap();
+    first.set_inner_html(
+        r#"<form name="login" action="/login">
+            <input type="text" name="user" placeholder="User">
+            <button type="submit" class="primary">Log in</button>
+            <span></span>
+        </form>"#,
+    );
+    let second = document().create_element("div").unwrap();
+    second.set_inner_html(
+        r#"<form action="/login" name="login"><input placeholder="User" name="user" type="text"><button class="primary" type="submit">Log in</button><span></span></form>"#,
+    );
+
+    let expected = r#"<div>
+  <form action="/login" name="login">
+    <input name="user" placeholder="User" type="text">
+    <button class="primary" type="submit">
+      Log in
+    </button>
+    <span></span>
+  </form>
+</div>"#;
+    assert_eq!(snapshot_html(&first), expected);
+    assert_eq!(snapshot_html(&second), expected);
+}
@@ -0,0 +1,492 @@
+use gloo::utils::window;
+use js_sys::{Function, Promise, Reflect};
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::future::{poll_fn, Future};
+use std::time::Duration;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+/// Names of the functions of `window` replaced by [`pause`].
+const PATCHED: [&str; 4] = ["setTimeout", "clearTimeout", "setInterval", "clearInterval"];
+
+/// A callback scheduled while the time is paused.
+struct Timer {
+    at: Duration,
+    callback: Function,
+    interval: Option<Duration>,
+}
+
+/// State of the paused time, present between [`pause`] and [`resume`].
+struct FakeClock {
+    now: Duration,
+    next_id: i32,
+    timers: BTreeMap<i32, Timer>,
+    originals: Vec<JsValue>,
+}
+
+type Patch = Closure<dyn FnMut(JsValue, JsValue) -> JsValue>;
+
+thread_local! {
+    static CLOCK: RefCell<Option<FakeClock>> = const { RefCell::new(None) };
+    // Never dropped, as the page may keep references to them after the time is resumed
+    static PATCHES: [Patch; 4] = [
+        Closure::new(|callback, delay| schedule(callback, delay, false)),
+        Closure::new(|id, _| cancel(id)),
+        Closure::new(|callback, delay| schedule(callback, delay, true)),
+        Closure::new(|id, _| cancel(id)),
+    ];
+}
+
+/// Stops timers of the page, so they only fire when the time is moved with [`advance`].
+///
+/// Replaces `setTimeout`, `setInterval` and their `clear*` counterparts of the `window`, so it affects
+/// everything scheduled with them, including [`gloo::timers`] and [`yew::platform::time`].
+/// Components debouncing input or hiding toasts after a few seconds can be tested without
+/// actually waiting. Helpers of this crate, like [`tick`] or [`wait_for`], keep using the real timers.
+///
+/// Only timers are paused, `Date.now()`, `performance.now()` and animation frames are not affected.
+///
+/// The real timers are restored when the returned guard is dropped, or earlier with [`resume`],
+/// so keeping the guard alive for the duration of a test scopes the paused time to that test.
+/// Like with [`configure`], a test panicking in the browser doesn't run destructors unless it is
+/// built with unwinding. Pausing again while already paused does nothing, and neither does dropping
+/// the guard returned then.
+///
+/// # Example:
+/// ```no_run
+/// use frontest::prelude::*;
+/// use frontest::time;
+/// use gloo::timers::callback::Timeout;
+/// use std::time::Duration;
+///
+/// # async fn hide_toast() {
+/// let paused = time::pause();
+/// let mount = mount_html(r#"<p role="status">Saved</p>"#);
+/// let toast = mount.get(&HasRole("status")).unwrap();
+/// Timeout::new(5_000, move || toast.remove()).forget();
+///
+/// time::advance(Duration::from_secs(5));
+/// assert!(mount.get(&HasRole("status")).is_none());
+///
+/// drop(paused);
+/// cleanup();
+/// # }
+/// ```
+///
+/// [`gloo::timers`]: gloo::timers
+/// [`yew::platform::time`]: https://docs.rs/yew/latest/yew/platform/time/index.html
+/// [`tick`]: crate::tick
+/// [`wait_for`]: crate::wait::wait_for
+/// [`configure`]: crate::configure
+pub fn pause() -> PauseGuard {
+    if is_paused() {
+        return PauseGuard { paused: false };
+    }
+    let window = window();
+    let originals = PATCHED
+        .iter()
+        .map(|name| Reflect::get(&window, &JsValue::from_str(name)).unwrap())
+        .collect();
+    PATCHES.with(|patches| {
+        for (name, patch) in PATCHED.iter().zip(patches) {
+            Reflect::set(&window, &JsValue::from_str(name), patch.as_ref()).unwrap();
+        }
+    });
+    CLOCK.with(|clock| {
+        *clock.borrow_mut() = Some(FakeClock {
+            now: Duration::ZERO,
+            next_id: 1,
+            timers: BTreeMap::new(),
+            originals,
+        })
+    });
+    PauseGuard { paused: true }
+}
+
+/// Calls [`resume`] when dropped, unless the time was already paused when it was created.
+///
+/// Returned by [`pause`].
+#[must_use = "the time is resumed as soon as the guard is dropped"]
+#[derive(Debug)]
+pub struct PauseGuard {
+    paused: bool,
+}
+
+impl Drop for PauseGuard {
+    fn drop(&mut self) {
+        if self.paused {
+            resume();
+        }
+    }
+}
+
+/// Restores the real timers replaced by [`pause`].
+///
+/// Timers scheduled while the time was paused and not fired yet are dropped.
+/// Resuming when the time isn't paused does nothing.
+pub fn resume() {
+    let Some(clock) = CLOCK.with(|clock| clock.borrow_mut().take()) else {
+        return;
+    };
+    let window = window();
+    for (name, original) in PATCHED.iter().zip(&clock.originals) {
+        Reflect::set(&window, &JsValue::from_str(name), original).unwrap();
+    }
+}
+
+/// Returns whether the timers are paused with [`pause`].
+pub fn is_paused() -> bool {
+    CLOCK.with(|clock| clock.borrow().is_some())
+}
+
+/// Moves the paused time forward, firing all timers which are due, in order.
+///
+/// Timers scheduled by the fired callbacks are fired too, if they are due before the end of `duration`,
+/// and intervals fire as many times as they would in that time.
+///
+/// # Panics:
+/// If the time isn't paused with [`pause`].
+#[track_caller]
+pub fn advance(duration: Duration) {
+    let end = CLOCK.with(|clock| {
+        let mut clock = clock.borrow_mut();
+        let Some(clock) = clock.as_mut() else {
+            panic!(
+                "{}: the time has to be paused to advance it",
+                std::panic::Location::caller()
+            );
+        };
+        clock.now + duration
+    });
+    while let Some(callback) = next_due(end) {
+        // Errors thrown by callbacks would be uncaught exceptions with real timers too
+        let _ = callback.call0(&JsValue::UNDEFINED);
+    }
+    CLOCK.with(|clock| {
+        if let Some(clock) = clock.borrow_mut().as_mut() {
+            clock.now = end;
+        }
+    });
+}
+
+/// Removes the earliest timer due until `end` and moves the time to it, rescheduling intervals.
+fn next_due(end: Duration) -> Option<Function> {
+    CLOCK.with(|clock| {
+        let mut clock = clock.borrow_mut();
+        let clock = clock.as_mut()?;
+        let (&id, _) = clock
+            .timers
+            .iter()
+            .filter(|(_, timer)| timer.at <= end)
+            .min_by_key(|(&id, timer)| (timer.at, id))?;
+        let mut timer = clock.timers.remove(&id).unwrap();
+        clock.now = timer.at;
+        let callback = timer.callback.clone();
+        if let Some(interval) = timer.interval {
+            timer.at += interval;
+            clock.timers.insert(id, timer);
+        }
+        Some(callback)
+    })
+}
+
+/// Replacement of `setTimeout` and `setInterval`.
+///
+/// Schedules a real timer when the time isn't paused, as the page may have kept a reference
+/// to the replacement from before [`resume`].
+fn schedule(callback: JsValue, delay: JsValue, repeat: bool) -> JsValue {
+    let scheduled = CLOCK.with(|clock| {
+        let mut clock = clock.borrow_mut();
+        let clock = clock.as_mut()?;
+        // Like browsers, intervals are at least 1ms so they can't block advancing the time
+        let millis = delay
+            .as_f64()
+            .unwrap_or(0.0)
+            .max(if repeat { 1.0 } else { 0.0 });
+        let delay = Duration::from_secs_f64(millis / 1000.0);
+        let id = clock.next_id;
+        clock.next_id += 1;
+        clock.timers.insert(
+            id,
+            Timer {
+                at: clock.now + delay,
+                callback: callback.clone().unchecked_into(),
+                interval: repeat.then_some(delay),
+            },
+        );
+        Some(JsValue::from(id))
+    });
+    scheduled.unwrap_or_else(|| real_timer(if repeat { 2 } else { 0 }, &[&callback, &delay]))
+}
+
+/// Replacement of `clearTimeout` and `clearInterval`.
+///
+/// Like [`schedule`], cancels a real timer when the time isn't paused.
+fn cancel(id: JsValue) -> JsValue {
+    let paused = CLOCK.with(|clock| {
+        let mut clock = clock.borrow_mut();
+        let clock = clock.as_mut()?;
+        if let Some(id) = id.as_f64() {
+            clock.timers.remove(&(id as i32));
+        }
+        Some(())
+    });
+    if paused.is_none() {
+        real_timer(1, &[&id]);
+    }
+    JsValue::UNDEFINED
+}
+
+/// A timer of the browser, working even when the time is paused. Cancelled when dropped.
+pub(crate) struct RealTimeout {
+    id: JsValue,
+    _callback: Closure<dyn FnMut()>,
+}
+
+impl RealTimeout {
+    pub(crate) fn new(millis: u32, callback: impl FnOnce() + 'static) -> Self {
+        let callback = Closure::once(callback);
+        let id = real_timer(0, &[callback.as_ref(), &JsValue::from(millis)]);
+        Self {
+            id,
+            _callback: callback,
+        }
+    }
+}
+
+impl Drop for RealTimeout {
+    fn drop(&mut self) {
+        real_timer(1, &[&self.id]);
+    }
+}
+
+/// Waits using the real timers of the browser, even when the time is paused.
+pub(crate) async fn real_sleep(duration: Duration) {
+    let millis = u32::try_from(duration.as_millis()).unwrap_or(u32::MAX);
+    let promise = Promise::new(&mut |resolve, _| {
+        real_timer(0, &[&resolve, &JsValue::from(millis)]);
+    });
+    // Resolved by the timer, so it never fails
+    let _ = JsFuture::from(promise).await;
+}
+
+/// State of a [`TimeoutCounter`].
+struct Counted {
+    previous: JsValue,
+    count: usize,
+}
+
+thread_local! {
+    static COUNTED: RefCell<Option<Counted>> = const { RefCell::new(None) };
+    static UNCOUNTED: Cell<bool> = const { Cell::new(false) };
+    // Never dropped, like the `PATCHES`
+    static COUNTING_PATCH: Patch = Closure::new(count_timeout);
+}
+
+/// Counts timeouts without a delay scheduled by the page, which run as soon as the event loop gets to them.
+///
+/// Replaces `setTimeout` of the `window` until dropped. Timeouts scheduled while the time is paused
+/// don't run until it is advanced, so they aren't counted.
+pub(crate) struct TimeoutCounter {
+    installed: bool,
+}
+
+impl TimeoutCounter {
+    pub(crate) fn new() -> Self {
+        if COUNTED.with(|counted| counted.borrow().is_some()) {
+            return Self { installed: false };
+        }
+        let window = window();
+        let name = JsValue::from_str("setTimeout");
+        let previous = Reflect::get(&window, &name).unwrap();
+        COUNTING_PATCH.with(|patch| Reflect::set(&window, &name, patch.as_ref()).unwrap());
+        COUNTED.with(|counted| *counted.borrow_mut() = Some(Counted { previous, count: 0 }));
+        Self { installed: true }
+    }
+
+    /// Returns the number of timeouts scheduled since the last call.
+    pub(crate) fn take(&self) -> usize {
+        COUNTED.with(|counted| {
+            counted
+                .borrow_mut()
+                .as_mut()
+                .map_or(0, |counted| std::mem::take(&mut counted.count))
+        })
+    }
+}
+
+impl Drop for TimeoutCounter {
+    fn drop(&mut self) {
+        if !self.installed {
+            return;
+        }
+        if let Some(counted) = COUNTED.with(|counted| counted.borrow_mut().take()) {
+            Reflect::set(
+                &window(),
+                &JsValue::from_str("setTimeout"),
+                &counted.previous,
+            )
+            .unwrap();
+        }
+    }
+}
+
+/// Runs a future without counting the timeouts it schedules in [`TimeoutCounter`].
+pub(crate) async fn uncounted<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    poll_fn(|cx| {
+        UNCOUNTED.with(|uncounted| uncounted.set(true));
+        let poll = future.as_mut().poll(cx);
+        UNCOUNTED.with(|uncounted| uncounted.set(false));
+        poll
+    })
+    .await
+}
+
+/// Replacement of `setTimeout` installed by [`TimeoutCounter`].
+fn count_timeout(callback: JsValue, delay: JsValue) -> JsValue {
+    let previous = COUNTED.with(|counted| {
+        let mut counted = counted.borrow_mut();
+        let counted = counted.as_mut()?;
+        let immediate = delay.as_f64().unwrap_or(0.0) <= 0.0;
+        if immediate && !UNCOUNTED.with(Cell::get) && !is_paused() {
+            counted.count += 1;
+        }
+        Some(counted.previous.clone())
+    });
+    // Called through a reference kept after the counter was dropped
+    let window = window();
+    let function = previous
+        .unwrap_or_else(|| Reflect::get(&window, &JsValue::from_str("setTimeout")).unwrap());
+    function
+        .unchecked_ref::<Function>()
+        .call2(&window, &callback, &delay)
+        .unwrap()
+}
+
+/// Calls the original function from [`PATCHED`] with given index.
+fn real_timer(idx: usize, args: &[&JsValue]) -> JsValue {
+    let window = window();
+    let function = CLOCK
+        .with(|clock| {
+            clock
+                .borrow()
+                .as_ref()
+                .map(|clock| clock.originals[idx].clone())
+        })
+        .unwrap_or_else(|| Reflect::get(&window, &JsValue::from_str(PATCHED[idx])).unwrap());
+    let args = args.iter().copied().cloned().collect::<js_sys::Array>();
+    function
+        .unchecked_ref::<Function>()
+        .apply(&window, &args)
+        .unwrap()
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_pause() {
+    use crate::query::{HasRole, Query};
+    use crate::{cleanup, mount_html};
+    use gloo::timers::callback::Timeout;
+
+    let paused = pause();
+    let mount = mount_html(r#"<p role="status">Saved</p>"#);
+    let toast = mount.get(&HasRole("status")).unwrap();
+    Timeout::new(5_000, move || toast.remove()).forget();
+
+    advance(Duration::from_secs(5));
+    assert!(mount.get(&HasRole("status")).is_none());
+
+    drop(paused);
+    cleanup();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn paused_time_fires_debounced_input_on_advance() {
+    use crate::query::{HasRole, HasText, Query};
+    use crate::wait::{wait_for, WaitOptions};
+    use crate::{cleanup, mount_html, tick};
+    use gloo::events::EventListener;
+    use gloo::timers::callback::{Interval, Timeout};
+    use std::rc::Rc;
+
+    let set_timeout = Reflect::get(&window(), &JsValue::from_str("setTimeout")).unwrap();
+    let _paused = pause();
+    // Pausing again doesn't take over resuming
+    drop(pause());
+    assert!(is_paused());
+    let patched = Reflect::get(&window(), &JsValue::from_str("setTimeout")).unwrap();
+
+    let mount = mount_html(r#"<input type="search" /><p>No results</p>"#);
+    let search = mount.get(&HasRole("searchbox")).unwrap();
+    let results = mount.get(&HasText("No results")).unwrap();
+    // Searches 5s after the last input
+    let debounce = Rc::new(RefCell::new(None));
+    let _listener = EventListener::new(&search, "input", {
+        let debounce = debounce.clone();
+        move |_| {
+            let results = results.clone();
+            let search = Timeout::new(5_000, move || results.set_inner_text("3 results"));
+            *debounce.borrow_mut() = Some(search);
+        }
+    });
+
+    search
+        .dispatch_event(&web_sys::Event::new("input").unwrap())
+        .unwrap();
+    advance(Duration::from_secs(3));
+    // Replaces, and so cancels, the pending search
+    search
+        .dispatch_event(&web_sys::Event::new("input").unwrap())
+        .unwrap();
+    advance(Duration::from_millis(4_999));
+    assert!(mount.get(&HasText("No results")).is_some());
+    advance(Duration::from_millis(1));
+    assert!(mount.get(&HasText("3 results")).is_some());
+
+    // Intervals fire as many times as they would, and the crate's own waiting isn't affected
+    let ticks = Rc::new(RefCell::new(0));
+    let _interval = Interval::new(100, {
+        let ticks = ticks.clone();
+        move || *ticks.borrow_mut() += 1
+    });
+    advance(Duration::from_millis(350));
+    assert_eq!(*ticks.borrow(), 3);
+    tick().await;
+    let options = WaitOptions::default().timeout(Duration::from_millis(50));
+    assert!(wait_for(&mount, || None::<()>, options).await.is_err());
+
+    resume();
+    assert!(!is_paused());
+    assert_eq!(
+        Reflect::get(&window(), &JsValue::from_str("setTimeout")).unwrap(),
+        set_timeout
+    );
+    // Timers scheduled after resuming are real again
+    let fired = Rc::new(RefCell::new(false));
+    Timeout::new(0, {
+        let fired = fired.clone();
+        move || *fired.borrow_mut() = true
+    })
+    .forget();
+    real_sleep(Duration::from_millis(10)).await;
+    assert!(*fired.borrow());
+    assert_eq!(*ticks.borrow(), 3);
+    // Also when scheduled with a replacement captured while the time was paused
+    *fired.borrow_mut() = false;
+    let callback = Closure::once({
+        let fired = fired.clone();
+        move || *fired.borrow_mut() = true
+    });
+    patched
+        .unchecked_ref::<Function>()
+        .call2(&window(), callback.as_ref(), &JsValue::from(0))
+        .unwrap();
+    real_sleep(Duration::from_millis(10)).await;
+    assert!(*fired.borrow());
+
+    cleanup();
+}
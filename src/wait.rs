@@ -0,0 +1,1587 @@
+use crate::query::{Matcher, Query, QueryConfig};
+use crate::snapshot::snapshot_html;
+use crate::time::RealTimeout;
+use futures_core::Stream;
+use gloo::events::EventListener;
+use gloo::render::request_animation_frame;
+#[cfg(test)]
+use gloo::timers::callback::Timeout;
+use gloo::utils::{document, window};
+use std::cell::{Cell, RefCell};
+use std::future::{poll_fn, Future};
+use std::panic::Location;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{
+    Element, Event, EventTarget, HtmlElement, MutationObserver, MutationObserverInit,
+    MutationRecord,
+};
+
+/// Configures how long the async helpers like [`find`] and [`wait_for`] wait, and how often
+/// they check the DOM.
+///
+/// The default is waiting for at most 1s and checking every 50ms, as well as after every mutation
+/// of the DOM. The defaults can be changed for all subsequent calls with [`configure`], eg. for a slow CI,
+/// or the options can be given to a single call.
+///
+/// # Example:
+/// ```no_run
+/// use frontest::prelude::*;
+/// use frontest::wait::{find_with, WaitOptions};
+/// use std::time::Duration;
+///
+/// # async fn find_slow_button() {
+/// let _config = configure(|config| {
+///     config.wait_options(WaitOptions::default().timeout(Duration::from_secs(2)))
+/// });
+/// let options = WaitOptions::current()
+///     .timeout(Duration::from_secs(5))
+///     .on_timeout(|err| gloo::console::error!(err.to_string()));
+/// let button = find_with(&screen(), &HasRole("button"), options).await.unwrap();
+/// # }
+/// ```
+///
+/// Options are compared and hashed without the [`on_timeout`] callback, as function pointers
+/// can't be reliably compared.
+///
+/// [`configure`]: crate::configure
+/// [`on_timeout`]: WaitOptions::on_timeout
+#[derive(Copy, Clone, Debug)]
+pub struct WaitOptions {
+    interval: Duration,
+    timeout: Duration,
+    animation_frames: bool,
+    on_timeout: Option<fn(&WaitTimeout)>,
+}
+
+impl Default for WaitOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_millis(50),
+            timeout: Duration::from_secs(1),
+            animation_frames: false,
+            on_timeout: None,
+        }
+    }
+}
+
+impl PartialEq for WaitOptions {
+    fn eq(&self, other: &Self) -> bool {
+        (self.interval, self.timeout, self.animation_frames)
+            == (other.interval, other.timeout, other.animation_frames)
+    }
+}
+
+impl Eq for WaitOptions {}
+
+impl std::hash::Hash for WaitOptions {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        (self.interval, self.timeout, self.animation_frames).hash(state);
+    }
+}
+
+impl WaitOptions {
+    /// Returns the options currently set with [`configure`].
+    ///
+    /// [`configure`]: crate::configure
+    pub fn current() -> Self {
+        QueryConfig::current().wait_options
+    }
+
+    /// Sets the time between consecutive checks, if there were no mutations in the meantime.
+    ///
+    /// Even with a zero interval, checks are separated by a yield to the event loop.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Sets the time after which waiting fails with [`WaitTimeout`].
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets whether to check also before every repaint of the page, like `requestAnimationFrame`.
+    ///
+    /// Useful for changes not visible as mutations of the DOM, eg. animations or css transitions.
+    pub fn animation_frames(mut self, enabled: bool) -> Self {
+        self.animation_frames = enabled;
+        self
+    }
+
+    /// Sets a function called with the error when waiting times out, eg. to log extra diagnostics.
+    pub fn on_timeout(mut self, callback: fn(&WaitTimeout)) -> Self {
+        self.on_timeout = Some(callback);
+        self
+    }
+
+    fn timed_out(&self, elapsed: Duration, details: String) -> WaitTimeout {
+        let err = WaitTimeout { elapsed, details };
+        if let Some(callback) = self.on_timeout {
+            callback(&err);
+        }
+        err
+    }
+}
+
+/// Former configuration of the async helpers, replaced by [`WaitOptions`].
+#[deprecated(note = "use `WaitOptions` instead")]
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub struct AsyncConfig {
+    /// Time between consecutive checks.
+    pub interval: Duration,
+    /// Time after which waiting fails with [`WaitTimeout`].
+    pub timeout: Duration,
+}
+
+#[allow(deprecated)]
+impl Default for AsyncConfig {
+    fn default() -> Self {
+        let options = WaitOptions::default();
+        Self {
+            interval: options.interval,
+            timeout: options.timeout,
+        }
+    }
+}
+
+#[allow(deprecated)]
+impl AsyncConfig {
+    /// Returns the interval and timeout of the current [`WaitOptions`].
+    pub fn current() -> Self {
+        let options = WaitOptions::current();
+        Self {
+            interval: options.interval,
+            timeout: options.timeout,
+        }
+    }
+}
+
+#[allow(deprecated)]
+impl From<AsyncConfig> for WaitOptions {
+    /// Takes the interval and timeout from the config and the rest from the current options.
+    fn from(config: AsyncConfig) -> Self {
+        WaitOptions::current()
+            .interval(config.interval)
+            .timeout(config.timeout)
+    }
+}
+
+/// Changes the interval and timeout of the default [`WaitOptions`] until they are changed again.
+#[deprecated(note = "use `configure` with `QueryConfig::wait_options` instead")]
+#[allow(deprecated)]
+pub fn set_default_async_config(config: AsyncConfig) {
+    let config = QueryConfig::current().wait_options(config.into());
+    // Unlike with `configure`, the change isn't scoped
+    std::mem::forget(crate::query::ConfigGuard::set(config));
+}
+
+/// Returned when the awaited condition wasn't met before the timeout.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WaitTimeout {
+    elapsed: Duration,
+    details: String,
+}
+
+impl WaitTimeout {
+    /// Returns how long the helper was waiting before giving up.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+impl std::fmt::Display for WaitTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "timed out after {}ms", self.elapsed.as_millis())?;
+        if !self.details.is_empty() {
+            write!(f, " {}", self.details)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for WaitTimeout {}
+
+/// Returns the current time of the page, with a sub-millisecond precision.
+fn now() -> Duration {
+    let millis = window().performance().map_or(0.0, |perf| perf.now());
+    Duration::from_secs_f64(millis / 1000.0)
+}
+
+/// Wakes up a waiting helper, eg. when the DOM changes or a timer fires.
+#[derive(Clone, Default)]
+struct Signal(Rc<RefCell<(bool, Option<Waker>)>>);
+
+impl Signal {
+    fn notify(&self) {
+        let mut state = self.0.borrow_mut();
+        state.0 = true;
+        if let Some(waker) = state.1.take() {
+            waker.wake();
+        }
+    }
+
+    /// Resolves once notified, immediately if it was notified since the last call.
+    async fn notified(&self) {
+        poll_fn(|cx| self.poll_notified(cx)).await
+    }
+
+    fn poll_notified(&self, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.0.borrow_mut();
+        if std::mem::take(&mut state.0) {
+            Poll::Ready(())
+        } else {
+            state.1 = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+/// Reports every batch of mutations in a subtree, until dropped.
+pub(crate) struct Observer {
+    observer: MutationObserver,
+    _callback: Closure<dyn FnMut(js_sys::Array)>,
+}
+
+impl Observer {
+    pub(crate) fn new(
+        root: &Element,
+        mut on_mutations: impl FnMut(Vec<MutationRecord>) + 'static,
+    ) -> Self {
+        let callback = Closure::<dyn FnMut(js_sys::Array)>::new(move |records: js_sys::Array| {
+            on_mutations(records.iter().map(JsCast::unchecked_into).collect())
+        });
+        let observer = MutationObserver::new(callback.as_ref().unchecked_ref()).unwrap();
+        let init = MutationObserverInit::new();
+        init.set_child_list(true);
+        init.set_subtree(true);
+        init.set_attributes(true);
+        init.set_character_data(true);
+        observer.observe_with_options(root, &init).unwrap();
+        Self {
+            observer,
+            _callback: callback,
+        }
+    }
+
+    /// Notifies a [`Signal`] about every mutation.
+    fn notifying(root: &Element, signal: Signal) -> Self {
+        Self::new(root, move |_| signal.notify())
+    }
+}
+
+impl Drop for Observer {
+    fn drop(&mut self) {
+        self.observer.disconnect();
+    }
+}
+
+/// Waits until the closure returns [`Some`], checking it whenever the subtree of `root` changes.
+///
+/// The closure is called immediately, then after every mutation observed with a [`MutationObserver`]:
+/// added or removed nodes, changed attributes and texts. As a safety net for changes not visible
+/// as mutations, it is also called after every [`WaitOptions::interval`] without mutations,
+/// and optionally on [`WaitOptions::animation_frames`]. It fails after the [`WaitOptions::timeout`],
+/// showing the content of `root`.
+///
+/// With the `yew` feature, the scheduler of [`yew`] is yielded to between the checks, so updates
+/// of components triggered right before waiting, eg. by a click, don't need a [`tick`] first.
+///
+/// # Example:
+/// ```no_run
+/// use frontest::prelude::*;
+/// use frontest::wait::WaitOptions;
+///
+/// # async fn wait_for_count() {
+/// let mount = mount_html("<ul></ul>");
+/// let count = wait_for(
+///     &mount,
+///     || {
+///         let count = mount.get_all(&HasRole("listitem")).len();
+///         (count >= 3).then_some(count)
+///     },
+///     WaitOptions::current(),
+/// )
+/// .await
+/// .unwrap();
+/// # }
+/// ```
+///
+/// [`MutationObserver`]: https://developer.mozilla.org/en-US/docs/Web/API/MutationObserver
+/// [`yew`]: ::yew
+/// [`tick`]: crate::tick
+pub async fn wait_for<T>(
+    root: &Element,
+    f: impl FnMut() -> Option<T>,
+    options: WaitOptions,
+) -> Result<T, WaitTimeout> {
+    poll_until(root, f, options).await.map_err(|elapsed| {
+        let details = format!("waiting for the condition in:\n{}", snapshot_html(root));
+        options.timed_out(elapsed, details)
+    })
+}
+
+/// Waits until the closure returns [`Some`], checking it on every mutation of the whole document.
+///
+/// A one-argument `wait_for` can't be kept next to the current [`wait_for`], so this is the way
+/// to keep calls written for it compiling: `wait_for(f)` becomes `wait_for_with(f, AsyncConfig::current())`.
+#[deprecated(note = "use `wait_for` with a root and `WaitOptions` instead")]
+#[allow(deprecated)]
+pub async fn wait_for_with<T>(
+    f: impl FnMut() -> Option<T>,
+    config: AsyncConfig,
+) -> Result<T, WaitTimeout> {
+    let root = document().document_element().unwrap();
+    wait_for(&root, f, config.into()).await
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+#[allow(deprecated)]
+async fn deprecated_async_config_forwards_to_wait_options() {
+    use crate::{cleanup, mount_html};
+
+    let config = AsyncConfig {
+        interval: Duration::from_millis(10),
+        timeout: Duration::from_millis(50),
+    };
+    set_default_async_config(config);
+    assert_eq!(AsyncConfig::current(), config);
+    assert_eq!(
+        WaitOptions::current(),
+        WaitOptions::default()
+            .interval(Duration::from_millis(10))
+            .timeout(Duration::from_millis(50))
+    );
+
+    let mount = mount_html("<p>Loading</p>");
+    let loaded = mount.clone();
+    Timeout::new(20, move || loaded.set_inner_html("<p>Done</p>")).forget();
+    let done = wait_for_with(
+        || mount.text_content().filter(|text| text == "Done"),
+        AsyncConfig::current(),
+    );
+    assert_eq!(done.await.unwrap(), "Done");
+    assert!(wait_for_with(|| None::<()>, config).await.is_err());
+
+    set_default_async_config(AsyncConfig::default());
+    assert_eq!(WaitOptions::current(), WaitOptions::default());
+
+    cleanup();
+}
+
+/// Repeats `f` until it returns [`Some`] or the timeout passes, returning the elapsed time on failure.
+async fn poll_until<T>(
+    root: &Element,
+    mut f: impl FnMut() -> Option<T>,
+    options: WaitOptions,
+) -> Result<T, Duration> {
+    let start = now();
+    let signal = Signal::default();
+    let _observer = Observer::notifying(root, signal.clone());
+    loop {
+        if let Some(result) = f() {
+            return Ok(result);
+        }
+        // Updates of components are run by the scheduler of yew only once it's yielded to.
+        // `tick` uses the real timers, so it doesn't hang when the time is paused.
+        #[cfg(feature = "yew")]
+        {
+            crate::tick().await;
+            if let Some(result) = f() {
+                return Ok(result);
+            }
+        }
+        let elapsed = now().saturating_sub(start);
+        if elapsed >= options.timeout {
+            return Err(elapsed);
+        }
+        // Even the shortest interval yields to the event loop, so it never blocks it
+        let delay = options.interval.min(options.timeout - elapsed).as_millis();
+        let _timer = RealTimeout::new(u32::try_from(delay).unwrap_or(u32::MAX), {
+            let signal = signal.clone();
+            move || signal.notify()
+        });
+        let _frame = options.animation_frames.then(|| {
+            let signal = signal.clone();
+            request_animation_frame(move |_| signal.notify())
+        });
+        signal.notified().await;
+    }
+}
+
+/// Waits until exactly one element is matched by a [`Matcher`], with the default [`WaitOptions`].
+///
+/// It is an async version of [`Query::get`], useful for content which appears after
+/// a timer, a network request or an update of a component. The query is repeated
+/// on every mutation of the document, see [`wait_for`].
+///
+/// # Example:
+/// ```no_run
+/// use frontest::prelude::*;
+/// use gloo::timers::callback::Timeout;
+///
+/// # async fn find_greeting() {
+/// let mount = mount_html("<p>Loading</p>");
+/// let loaded = mount.clone();
+/// Timeout::new(100, move || loaded.set_inner_html("<p>Hello</p>")).forget();
+///
+/// let greeting = find(&mount, &HasText("Hello")).await.unwrap();
+///
+/// cleanup();
+/// # }
+/// ```
+///
+/// # Panics:
+/// If more than one element is found, unless configured otherwise with [`QueryConfig`].
+///
+/// [`QueryConfig`]: crate::query::QueryConfig
+pub async fn find<Q: Query + ?Sized, M: Matcher + ?Sized>(
+    query: &Q,
+    matcher: &M,
+) -> Result<HtmlElement, WaitTimeout> {
+    find_with(query, matcher, WaitOptions::current()).await
+}
+
+/// Same as [`find`], but with explicit [`WaitOptions`].
+pub async fn find_with<Q: Query + ?Sized, M: Matcher + ?Sized>(
+    query: &Q,
+    matcher: &M,
+    options: WaitOptions,
+) -> Result<HtmlElement, WaitTimeout> {
+    // Not finding anything yet is expected, so it never panics
+    let query_config = QueryConfig::current().panic_on_not_found(false);
+    let query = crate::query::ConfiguredQuery::new(query, query_config);
+    // Queries aren't limited to a single subtree, so the whole document is observed
+    let root = document().document_element().unwrap();
+    poll_until(&root, || query.get(matcher), options)
+        .await
+        .map_err(|elapsed| {
+            let details = format!(
+                "waiting for an element matching {} in:\n{}",
+                matcher.describe(),
+                query.root_html()
+            );
+            options.timed_out(elapsed, details)
+        })
+}
+
+/// Shorthand for [`find`] with matchers given as `key = value` pairs, all of which must match.
+///
+/// See [`get_by!`] for supported keys.
+///
+/// # Example:
+/// ```no_run
+/// use frontest::prelude::*;
+/// use gloo::timers::callback::Timeout;
+///
+/// # async fn find_greeting() {
+/// let mount = mount_html("<p>Loading</p>");
+/// let loaded = mount.clone();
+/// Timeout::new(100, move || loaded.set_inner_html("<h1>Hello</h1>")).forget();
+///
+/// let greeting = find_by!(mount, role = "heading", text = "Hello").await.unwrap();
+///
+/// cleanup();
+/// # }
+/// ```
+///
+/// [`get_by!`]: crate::get_by
+#[macro_export]
+macro_rules! find_by {
+    ($query:expr, $($key:ident = $value:expr),+ $(,)?) => {
+        $crate::wait::find(&$query, &$crate::__matchers_by!($($key = $value),+))
+    };
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_find() {
+    use crate::query::HasText;
+    use crate::{cleanup, mount_html};
+
+    let mount = mount_html("<p>Loading</p>");
+    let loaded = mount.clone();
+    Timeout::new(100, move || loaded.set_inner_html("<p>Hello</p>")).forget();
+
+    let greeting = find(&mount, &HasText("Hello")).await.unwrap();
+    assert_eq!(greeting.inner_text(), "Hello");
+
+    cleanup();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_find_by() {
+    use crate::{cleanup, mount_html};
+
+    let mount = mount_html("<p>Loading</p>");
+    let loaded = mount.clone();
+    Timeout::new(100, move || loaded.set_inner_html("<h1>Hello</h1>")).forget();
+
+    let greeting = crate::find_by!(mount, role = "heading", text = "Hello")
+        .await
+        .unwrap();
+    assert_eq!(greeting.local_name(), "h1");
+
+    cleanup();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn default_wait_options_apply_to_find() {
+    use crate::query::HasText;
+    use crate::{cleanup, configure, mount_html};
+
+    let appear_later = |mount: &web_sys::Element| {
+        let mount = mount.clone();
+        Timeout::new(200, move || mount.set_inner_html("<p>Done</p>")).forget();
+    };
+
+    let mount = mount_html("<p>Loading</p>");
+    appear_later(&mount);
+    let config = configure(|config| {
+        config.wait_options(
+            WaitOptions::default()
+                .interval(Duration::from_millis(10))
+                .timeout(Duration::from_millis(50)),
+        )
+    });
+    let err = find(&mount, &HasText("Done")).await.unwrap_err();
+    assert!(err.elapsed() >= Duration::from_millis(50));
+    assert!(err.to_string().contains(
+        "waiting for an element matching HasText(\"Done\") in:\n<div>\n  <p>\n    Loading"
+    ));
+    drop(config);
+
+    assert_eq!(WaitOptions::current(), WaitOptions::default());
+    assert!(find(&mount, &HasText("Done")).await.is_ok());
+
+    // Explicit options take precedence over the default ones
+    let mount = mount_html("<p>Loading</p>");
+    appear_later(&mount);
+    let options = WaitOptions::current().timeout(Duration::from_millis(50));
+    assert!(find_with(&mount, &HasText("Done"), options).await.is_err());
+
+    cleanup();
+}
+
+#[cfg(all(test, feature = "yew"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn find_runs_component_updates_after_click() {
+    use crate::interaction::click_sync;
+    use crate::query::{HasRole, HasText, Query};
+    use crate::time;
+    use crate::yew::render;
+    use yew::prelude::*;
+
+    #[function_component(Toggle)]
+    fn toggle() -> Html {
+        let open = use_state(|| false);
+        let onclick = {
+            let open = open.clone();
+            Callback::from(move |_| open.set(!*open))
+        };
+        html! {
+            <>
+                <button {onclick}>{ "Toggle" }</button>
+                if *open { <p>{ "Opened" }</p> }
+            </>
+        }
+    }
+
+    let mount = render(html! { <Toggle /> }).await;
+    let button = mount.get(&HasRole("button")).unwrap();
+    // Only a yielded scheduler or a mutation can wake the waiting up in time
+    let options = WaitOptions::default().interval(Duration::from_secs(10));
+
+    click_sync(&button);
+    let start = now();
+    assert!(find_with(&mount, &HasText("Opened"), options).await.is_ok());
+    assert!(now() - start < Duration::from_millis(500));
+
+    // Doesn't depend on the timers of the page
+    let paused = time::pause();
+    click_sync(&button);
+    let closed = wait_for(
+        &mount,
+        || mount.get(&HasText("Opened")).is_none().then_some(()),
+        options,
+    );
+    assert_eq!(closed.await, Ok(()));
+    click_sync(&button);
+    assert!(find_with(&mount, &HasText("Opened"), options).await.is_ok());
+    drop(paused);
+
+    crate::cleanup();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn wait_for_resolves_with_closure_result() {
+    use crate::{cleanup, mount_html};
+
+    let mount = mount_html("<p>Loading</p>");
+    let mut calls = 0;
+    let options = WaitOptions::default().interval(Duration::from_millis(1));
+    let result = wait_for(
+        &mount,
+        || {
+            calls += 1;
+            (calls == 3).then_some("third")
+        },
+        options,
+    )
+    .await;
+    assert_eq!(result, Ok("third"));
+
+    let err = wait_for(&mount, || None::<()>, options.timeout(Duration::ZERO))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "timed out after 0ms waiting for the condition in:\n<div>\n  <p>\n    Loading\n  </p>\n</div>"
+    );
+
+    cleanup();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn wait_for_is_woken_by_mutations() {
+    use crate::{cleanup, mount_html};
+
+    let mount = mount_html(r#"<button aria-busy="true">Save</button>"#);
+    let button = mount.first_element_child().unwrap();
+    Timeout::new(20, {
+        let button = button.clone();
+        move || button.remove_attribute("aria-busy").unwrap()
+    })
+    .forget();
+
+    // The interval is longer than the timeout, so only the mutation can trigger the check
+    let options = WaitOptions::default().interval(Duration::from_secs(10));
+    let start = now();
+    let ready = wait_for(
+        &mount,
+        || (!button.has_attribute("aria-busy")).then_some(()),
+        options,
+    )
+    .await;
+    assert_eq!(ready, Ok(()));
+    assert!(now() - start < Duration::from_millis(500));
+
+    cleanup();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn wait_for_times_out_without_mutations() {
+    use crate::{cleanup, mount_html};
+
+    let mount = mount_html(r#"<button aria-busy="true">Save</button>"#);
+    let button = mount.first_element_child().unwrap();
+    let options = WaitOptions::default()
+        .interval(Duration::from_millis(10))
+        .timeout(Duration::from_millis(100));
+    let err = wait_for(
+        &mount,
+        || (!button.has_attribute("aria-busy")).then_some(()),
+        options,
+    )
+    .await
+    .unwrap_err();
+    assert!(err.elapsed() >= Duration::from_millis(100));
+    assert!(err
+        .to_string()
+        .ends_with("in:\n<div>\n  <button aria-busy=\"true\">\n    Save\n  </button>\n</div>"));
+
+    cleanup();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn wait_options_never_busy_loop() {
+    use crate::{cleanup, mount_html};
+
+    // Set by a timer without touching the DOM, so it can only be seen if checks yield to the event loop
+    let done = Rc::new(Cell::new(false));
+    Timeout::new(20, {
+        let done = done.clone();
+        move || done.set(true)
+    })
+    .forget();
+
+    let mount = mount_html("<p>Loading</p>");
+    let mut calls = 0;
+    let options = WaitOptions::default().interval(Duration::ZERO);
+    let result = wait_for(
+        &mount,
+        || {
+            calls += 1;
+            done.get().then_some(())
+        },
+        options,
+    )
+    .await;
+    assert_eq!(result, Ok(()));
+    assert!(calls > 1);
+
+    cleanup();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn wait_options_check_on_animation_frames_and_report_timeouts() {
+    use crate::{cleanup, mount_html};
+
+    thread_local! {
+        static TIMEOUTS: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+    }
+
+    let done = Rc::new(Cell::new(false));
+    Timeout::new(20, {
+        let done = done.clone();
+        move || done.set(true)
+    })
+    .forget();
+
+    // Neither mutations nor the interval trigger the check, only the animation frames
+    let mount = mount_html("<p>Loading</p>");
+    let options = WaitOptions::default()
+        .interval(Duration::from_secs(10))
+        .animation_frames(true)
+        .on_timeout(|err| TIMEOUTS.with(|timeouts| timeouts.borrow_mut().push(err.to_string())));
+    let start = now();
+    assert_eq!(
+        wait_for(&mount, || done.get().then_some(()), options).await,
+        Ok(())
+    );
+    assert!(now() - start < Duration::from_millis(500));
+    assert!(TIMEOUTS.with(|timeouts| timeouts.borrow().is_empty()));
+
+    let options = options.timeout(Duration::from_millis(20));
+    let err = wait_for(&mount, || None::<()>, options).await.unwrap_err();
+    assert_eq!(TIMEOUTS.with(|timeouts| timeouts.take()), [err.to_string()]);
+    // The callback isn't compared
+    assert_eq!(
+        options,
+        WaitOptions::default()
+            .interval(Duration::from_secs(10))
+            .animation_frames(true)
+            .timeout(Duration::from_millis(20))
+    );
+
+    cleanup();
+}
+
+/// Waits until no element in the subtree of `root` is matched by a [`Matcher`].
+///
+/// Useful to check that something disappears, eg. a row after clicking delete or a spinner
+/// after loading. Like [`wait_for`], the check is repeated on every mutation of the subtree.
+/// Unlike `waitForElementToBeRemoved` from [`testing-library`], it resolves immediately
+/// if nothing matches at the time of the call, instead of failing. If the element could
+/// have been removed already, it is what the test was waiting for anyway.
+///
+/// On timeout, the error lists the elements which are still matching.
+///
+/// # Example:
+/// ```no_run
+/// use frontest::prelude::*;
+/// use frontest::wait::{wait_for_removed, WaitOptions};
+/// use gloo::timers::callback::Timeout;
+///
+/// # async fn wait_for_spinner() {
+/// let mount = mount_html(r#"<div role="progressbar"></div>"#);
+/// let loaded = mount.clone();
+/// Timeout::new(100, move || loaded.set_inner_html("<p>Loaded</p>")).forget();
+///
+/// wait_for_removed(&mount, &HasRole("progressbar"), WaitOptions::current())
+///     .await
+///     .unwrap();
+///
+/// cleanup();
+/// # }
+/// ```
+///
+/// [`testing-library`]: https://testing-library.com/docs/dom-testing-library/api-async#waitforelementtoberemoved
+pub async fn wait_for_removed<M: Matcher + ?Sized>(
+    root: &Element,
+    matcher: &M,
+    options: WaitOptions,
+) -> Result<(), WaitTimeout> {
+    poll_until(
+        root,
+        || root.get_all(matcher).is_empty().then_some(()),
+        options,
+    )
+    .await
+    .map_err(|elapsed| {
+        let remaining = root.get_all(matcher);
+        let details = format!(
+            "waiting for elements matching {} to be removed, {} still present:\n{}",
+            matcher.describe(),
+            remaining.len(),
+            remaining
+                .iter()
+                .map(|elem| snapshot_html(elem))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+        options.timed_out(elapsed, details)
+    })
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_wait_for_removed() {
+    use crate::query::HasRole;
+    use crate::{cleanup, mount_html};
+
+    let mount = mount_html(r#"<div role="progressbar"></div>"#);
+    let loaded = mount.clone();
+    Timeout::new(100, move || loaded.set_inner_html("<p>Loaded</p>")).forget();
+
+    wait_for_removed(&mount, &HasRole("progressbar"), WaitOptions::current())
+        .await
+        .unwrap();
+
+    cleanup();
+}
+
+#[cfg(all(test, feature = "yew"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn wait_for_removed_waits_for_component_update() {
+    use crate::query::{HasRole, HasText, Joinable, Query};
+    use crate::yew::render;
+    use gloo::utils::body;
+    use yew::prelude::*;
+
+    #[function_component(Rows)]
+    fn rows() -> Html {
+        let rows = use_state(|| vec!["First", "Second"]);
+        let rows_html = rows.iter().map(|&row| {
+            let onclick = {
+                let rows = rows.clone();
+                // Deleting pretends to wait for a response from the server
+                Callback::from(move |_| {
+                    let rows = rows.clone();
+                    yew::platform::spawn_local(async move {
+                        yew::platform::time::sleep(Duration::from_millis(50)).await;
+                        rows.set(rows.iter().copied().filter(|&r| r != row).collect());
+                    });
+                })
+            };
+            html! {
+                <li>
+                    <span>{ row }</span>
+                    <button {onclick}>{ "Delete" }</button>
+                </li>
+            }
+        });
+        html! { <ul>{ for rows_html }</ul> }
+    }
+
+    let mount = render(html! { <Rows /> }).await;
+    let options = WaitOptions::default().interval(Duration::from_secs(10));
+
+    let second = mount.get(&HasText("Second")).unwrap();
+    second
+        .next_element_sibling()
+        .unwrap()
+        .unchecked_into::<HtmlElement>()
+        .click();
+    let start = now();
+    assert_eq!(
+        wait_for_removed(&mount, &HasText("Second"), options).await,
+        Ok(())
+    );
+    // Woken up by the mutation, not by the interval
+    assert!(now() - start < Duration::from_millis(500));
+    assert!(mount.get(&HasText("First")).is_some());
+
+    // Nothing matches already
+    assert_eq!(
+        wait_for_removed(&mount, &HasText("Second"), options).await,
+        Ok(())
+    );
+
+    let err = wait_for_removed(
+        &mount,
+        &HasRole("listitem").and(HasText("First")),
+        options.timeout(Duration::from_millis(50)),
+    )
+    .await
+    .unwrap_err();
+    assert!(err.to_string().ends_with(
+        "waiting for elements matching HasRole(\"listitem\").and(HasText(\"First\")) to be removed, \
+        1 still present:\n<li>\n  <span>\n    First\n  </span>\n  <button>\n    Delete\n  </button>\n</li>"
+    ));
+
+    body().remove_child(&mount).unwrap();
+}
+
+/// Panics if an element matched by a [`Matcher`] shows up in the subtree of `root` within given time.
+///
+/// Useful for regressions about things which must not be shown, like a duplicated toast
+/// or a flash of an error. The subtree is checked immediately, then on every mutation observed
+/// with a [`MutationObserver`]. Added elements are checked even if they were removed
+/// before the check, so brief flashes aren't missed between the checks.
+///
+/// # Example:
+/// ```no_run
+/// use frontest::prelude::*;
+/// use frontest::wait::assert_never_appears;
+/// use std::time::Duration;
+///
+/// # async fn no_error_after_save() {
+/// let mount = mount_html("<button>Save</button>");
+/// mount.get(&HasRole("button")).unwrap().click();
+///
+/// assert_never_appears(&mount, &HasRole("alert"), Duration::from_millis(200)).await;
+///
+/// cleanup();
+/// # }
+/// ```
+///
+/// # Panics:
+/// If a matching element appears, showing when it appeared and its html.
+/// The message starts with the location of the caller.
+///
+/// [`MutationObserver`]: https://developer.mozilla.org/en-US/docs/Web/API/MutationObserver
+#[track_caller]
+pub fn assert_never_appears<'a, M: Matcher + ?Sized>(
+    root: &'a Element,
+    matcher: &'a M,
+    within: Duration,
+) -> impl Future<Output = ()> + 'a {
+    // Async functions don't track their callers, so the location is taken before awaiting
+    let caller = Location::caller();
+    async move {
+        if let Some((elapsed, html)) = first_appearance(root, matcher, within).await {
+            panic!(
+                "{}: {} appeared after {}ms, but it should never appear:\n{}",
+                caller,
+                matcher.describe(),
+                elapsed.as_millis(),
+                html
+            );
+        }
+    }
+}
+
+/// Returns when, and as what html, an element matched by a [`Matcher`] first appeared in the subtree.
+async fn first_appearance<M: Matcher + ?Sized>(
+    root: &Element,
+    matcher: &M,
+    within: Duration,
+) -> Option<(Duration, String)> {
+    let start = now();
+    if let Some(elem) = root.get_all(matcher).first() {
+        return Some((Duration::ZERO, snapshot_html(elem)));
+    }
+
+    let signal = Signal::default();
+    let added = Rc::new(RefCell::new(vec![]));
+    let _observer = Observer::new(root, {
+        let signal = signal.clone();
+        let added = added.clone();
+        move |records| {
+            let time = now();
+            let elems = records
+                .iter()
+                .flat_map(|record| {
+                    let nodes = record.added_nodes();
+                    (0..nodes.length()).filter_map(move |idx| nodes.item(idx))
+                })
+                .filter_map(|node| node.dyn_into::<Element>().ok())
+                .flat_map(|elem| {
+                    let descendants = elem.query_selector_all("*").unwrap();
+                    let descendants =
+                        (0..descendants.length()).filter_map(move |idx| descendants.item(idx));
+                    std::iter::once(elem.into()).chain(descendants)
+                })
+                .filter_map(|node| node.dyn_into::<HtmlElement>().ok())
+                .collect::<Vec<_>>();
+            added.borrow_mut().push((time, elems));
+            signal.notify();
+        }
+    });
+    loop {
+        let batches = added.take();
+        for (time, elems) in &batches {
+            if let Some(elem) = elems.iter().find(|elem| matcher.matches(elem)) {
+                return Some((time.saturating_sub(start), snapshot_html(elem)));
+            }
+        }
+        // Changed attributes or texts can make existing elements match too
+        if let Some((time, _)) = batches.last() {
+            if let Some(elem) = root.get_all(matcher).first() {
+                return Some((time.saturating_sub(start), snapshot_html(elem)));
+            }
+        }
+        let elapsed = now().saturating_sub(start);
+        if elapsed >= within {
+            return None;
+        }
+        let delay = (within - elapsed).as_millis();
+        let _timer = RealTimeout::new(u32::try_from(delay).unwrap_or(u32::MAX), {
+            let signal = signal.clone();
+            move || signal.notify()
+        });
+        signal.notified().await;
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_assert_never_appears() {
+    use crate::query::HasRole;
+    use crate::{cleanup, mount_html};
+
+    let mount = mount_html("<button>Save</button>");
+    mount.get(&HasRole("button")).unwrap().click();
+
+    assert_never_appears(&mount, &HasRole("alert"), Duration::from_millis(200)).await;
+
+    cleanup();
+}
+
+#[cfg(all(test, feature = "yew"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn first_appearance_catches_brief_flashes() {
+    use crate::query::HasRole;
+    use crate::yew::render;
+    use gloo::utils::body;
+    use yew::platform::time::sleep;
+    use yew::prelude::*;
+
+    #[function_component(Flash)]
+    fn flash() -> Html {
+        let error = use_state(|| false);
+        {
+            let error = error.clone();
+            use_effect_with((), move |_| {
+                yew::platform::spawn_local(async move {
+                    sleep(Duration::from_millis(20)).await;
+                    error.set(true);
+                    sleep(Duration::ZERO).await;
+                    error.set(false);
+                });
+            });
+        }
+        html! {
+            <div>
+                if *error {
+                    <p role="alert">{ "Error" }</p>
+                }
+                <button>{ "Save" }</button>
+            </div>
+        }
+    }
+
+    let mount = render(html! { <Flash /> }).await;
+    let (elapsed, html) = first_appearance(&mount, &HasRole("alert"), Duration::from_millis(500))
+        .await
+        .unwrap();
+    assert!(elapsed > Duration::ZERO);
+    assert_eq!(html, "<p role=\"alert\">\n  Error\n</p>");
+    // The flash is already gone
+    sleep(Duration::from_millis(10)).await;
+    assert!(mount.get(&HasRole("alert")).is_none());
+
+    // Already present elements are reported right away
+    assert_eq!(
+        first_appearance(&mount, &HasRole("button"), Duration::from_millis(500)).await,
+        Some((Duration::ZERO, String::from("<button>\n  Save\n</button>")))
+    );
+    assert_never_appears(&mount, &HasRole("alert"), Duration::from_millis(50)).await;
+
+    body().remove_child(&mount).unwrap();
+}
+
+/// Waits until there are no mutations in the subtree of `root` for the `quiet_period`.
+///
+/// Useful to let everything settle, eg. before taking a snapshot, without listing what
+/// should be waited for. It fails if the subtree keeps changing after the [`WaitOptions::timeout`].
+/// Use [`wait_until_stable_ignoring`] if some attributes never settle, eg. because of an animation.
+///
+/// # Example:
+/// ```no_run
+/// use frontest::prelude::*;
+/// use frontest::wait::{wait_until_stable, WaitOptions};
+/// use std::time::Duration;
+///
+/// # async fn snapshot_settled() {
+/// let mount = mount_html("<ul></ul>");
+/// wait_until_stable(&mount, Duration::from_millis(100), WaitOptions::current())
+///     .await
+///     .unwrap();
+/// let html = frontest::snapshot::snapshot_html(&mount);
+/// # }
+/// ```
+pub async fn wait_until_stable(
+    root: &Element,
+    quiet_period: Duration,
+    options: WaitOptions,
+) -> Result<(), WaitTimeout> {
+    wait_until_stable_ignoring(root, quiet_period, &[], options).await
+}
+
+/// Same as [`wait_until_stable`], but changes of given attributes, eg. `style` or `class`, don't count
+/// as mutations.
+pub async fn wait_until_stable_ignoring(
+    root: &Element,
+    quiet_period: Duration,
+    ignored_attributes: &[&str],
+    options: WaitOptions,
+) -> Result<(), WaitTimeout> {
+    let start = now();
+    let last_mutation = Rc::new(Cell::new(start));
+    let signal = Signal::default();
+    let ignored = ignored_attributes
+        .iter()
+        .map(|attr| attr.to_string())
+        .collect::<Vec<_>>();
+    let _observer = Observer::new(root, {
+        let last_mutation = last_mutation.clone();
+        let signal = signal.clone();
+        move |records| {
+            let ignored = |record: &MutationRecord| {
+                record.type_() == "attributes"
+                    && record
+                        .attribute_name()
+                        .is_some_and(|name| ignored.contains(&name))
+            };
+            if !records.iter().all(ignored) {
+                last_mutation.set(now());
+                signal.notify();
+            }
+        }
+    });
+    loop {
+        let now = now();
+        let quiet = now.saturating_sub(last_mutation.get());
+        if quiet >= quiet_period {
+            return Ok(());
+        }
+        let elapsed = now.saturating_sub(start);
+        if elapsed >= options.timeout {
+            let details = format!(
+                "waiting for no mutations for {}ms in:\n{}",
+                quiet_period.as_millis(),
+                snapshot_html(root)
+            );
+            return Err(options.timed_out(elapsed, details));
+        }
+        let delay = (quiet_period - quiet).min(options.timeout - elapsed);
+        let delay = delay.as_millis() + 1;
+        let _timer = RealTimeout::new(u32::try_from(delay).unwrap_or(u32::MAX), {
+            let signal = signal.clone();
+            move || signal.notify()
+        });
+        signal.notified().await;
+    }
+}
+
+#[cfg(all(test, feature = "yew"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn wait_until_stable_waits_for_all_updates() {
+    use crate::query::HasText;
+    use crate::yew::render;
+    use gloo::utils::body;
+    use yew::platform::time::sleep;
+    use yew::prelude::*;
+
+    #[function_component(Steps)]
+    fn steps() -> Html {
+        let step = use_state(|| 0);
+        {
+            let step = step.clone();
+            use_effect_with((), move |_| {
+                yew::platform::spawn_local(async move {
+                    for next in 1..=3 {
+                        sleep(Duration::from_millis(30)).await;
+                        step.set(next);
+                    }
+                });
+            });
+        }
+        html! { <p>{ format!("Step {}", *step) }</p> }
+    }
+
+    let mount = render(html! { <Steps /> }).await;
+    let start = now();
+    wait_until_stable(&mount, Duration::from_millis(50), WaitOptions::default())
+        .await
+        .unwrap();
+    assert!(now() - start >= Duration::from_millis(90));
+    assert!(mount.get(&HasText("Step 3")).is_some());
+
+    let err = wait_until_stable(
+        &mount,
+        Duration::from_millis(50),
+        WaitOptions::default().timeout(Duration::from_millis(20)),
+    )
+    .await
+    .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("waiting for no mutations for 50ms in:\n<div>"));
+
+    body().remove_child(&mount).unwrap();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn wait_until_stable_ignores_given_attributes() {
+    use crate::{cleanup, mount_html};
+    use gloo::timers::callback::Interval;
+
+    // Animated by javascript, so the style changes every few milliseconds
+    let mount = mount_html(r#"<div class="spinner" style="opacity: 0"></div>"#);
+    let spinner = mount.first_element_child().unwrap();
+    let mut frame = 0;
+    let animation = Interval::new(5, move || {
+        frame += 1;
+        let style = format!("opacity: {}", frame % 10);
+        spinner.set_attribute("style", &style).unwrap();
+    });
+
+    let options = WaitOptions::default().timeout(Duration::from_millis(200));
+    let quiet = Duration::from_millis(50);
+    assert!(wait_until_stable(&mount, quiet, options).await.is_err());
+    assert_eq!(
+        wait_until_stable_ignoring(&mount, quiet, &["style", "class"], options).await,
+        Ok(())
+    );
+
+    drop(animation);
+    cleanup();
+}
+
+/// Watches the subtree of `root`, emitting all elements matched by a [`Matcher`] whenever they change.
+///
+/// The first item is the set of elements matched at the first poll. Then the subtree is queried
+/// again after every mutation, and the result is emitted only if it is different from the previous one.
+/// Elements are returned in document order, like from [`Query::get_all`]. The stream never ends
+/// and the [`MutationObserver`] is disconnected when it is dropped.
+///
+/// This allows asserting a sequence of states, eg. that a list shows a spinner before its items.
+///
+/// # Example:
+/// ```no_run
+/// use frontest::prelude::*;
+/// use frontest::wait::watch;
+/// use futures::StreamExt;
+/// use gloo::timers::callback::Timeout;
+///
+/// # async fn watch_loading() {
+/// let mount = mount_html(r#"<p role="status">Loading</p>"#);
+/// let loaded = mount.clone();
+/// Timeout::new(100, move || loaded.set_inner_html("<ul><li>First</li></ul>")).forget();
+///
+/// let mut states = Box::pin(watch(&mount, MatchesSelector("[role=status], li")));
+/// assert_eq!(states.next().await.unwrap()[0].inner_text(), "Loading");
+/// assert_eq!(states.next().await.unwrap()[0].inner_text(), "First");
+///
+/// cleanup();
+/// # }
+/// ```
+///
+/// [`MutationObserver`]: https://developer.mozilla.org/en-US/docs/Web/API/MutationObserver
+pub fn watch<M: Matcher + 'static>(
+    root: &Element,
+    matcher: M,
+) -> impl Stream<Item = Vec<HtmlElement>> {
+    let signal = Signal::default();
+    // The current state is emitted right away
+    signal.notify();
+    Watch {
+        root: root.clone(),
+        matcher: Box::new(matcher),
+        _observer: Observer::notifying(root, signal.clone()),
+        signal,
+        last: None,
+    }
+}
+
+/// The stream returned by [`watch`].
+struct Watch<M> {
+    root: Element,
+    // Boxed, so the stream is `Unpin` regardless of the matcher
+    matcher: Box<M>,
+    _observer: Observer,
+    signal: Signal,
+    last: Option<Vec<HtmlElement>>,
+}
+
+impl<M: Matcher> Stream for Watch<M> {
+    type Item = Vec<HtmlElement>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        while this.signal.poll_notified(cx).is_ready() {
+            let matched = this.root.get_all(&*this.matcher);
+            if this.last.as_ref() != Some(&matched) {
+                this.last = Some(matched.clone());
+                return Poll::Ready(Some(matched));
+            }
+        }
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_watch() {
+    use crate::query::MatchesSelector;
+    use crate::{cleanup, mount_html};
+    use futures::StreamExt;
+
+    let mount = mount_html(r#"<p role="status">Loading</p>"#);
+    let loaded = mount.clone();
+    Timeout::new(100, move || {
+        loaded.set_inner_html("<ul><li>First</li></ul>")
+    })
+    .forget();
+
+    let mut states = Box::pin(watch(&mount, MatchesSelector("[role=status], li")));
+    assert_eq!(states.next().await.unwrap()[0].inner_text(), "Loading");
+    assert_eq!(states.next().await.unwrap()[0].inner_text(), "First");
+
+    cleanup();
+}
+
+#[cfg(all(test, feature = "yew"))]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn watch_emits_every_state_of_a_component() {
+    use crate::query::{HasRole, Joinable};
+    use crate::yew::render;
+    use futures::StreamExt;
+    use gloo::utils::body;
+    use yew::platform::time::sleep;
+    use yew::prelude::*;
+
+    #[derive(Clone, PartialEq)]
+    enum State {
+        Loading,
+        Empty,
+        Populated,
+    }
+
+    #[function_component(List)]
+    fn list() -> Html {
+        let state = use_state(|| State::Loading);
+        {
+            let state = state.clone();
+            use_effect_with((), move |_| {
+                yew::platform::spawn_local(async move {
+                    sleep(Duration::from_millis(20)).await;
+                    state.set(State::Empty);
+                    sleep(Duration::from_millis(20)).await;
+                    state.set(State::Populated);
+                });
+            });
+        }
+        match *state {
+            State::Loading => html! { <span role="status">{ "Loading" }</span> },
+            State::Empty => html! { <p role="status">{ "No items" }</p> },
+            State::Populated => html! {
+                <ul>
+                    <li>{ "First" }</li>
+                    <li>{ "Second" }</li>
+                </ul>
+            },
+        }
+    }
+
+    let mount = render(html! { <List /> }).await;
+    let states = watch(&mount, HasRole("status").or(HasRole("listitem")))
+        .take(3)
+        .map(|elems| {
+            elems
+                .iter()
+                .map(|elem| elem.inner_text())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(
+        states,
+        [vec!["Loading"], vec!["No items"], vec!["First", "Second"]]
+    );
+
+    body().remove_child(&mount).unwrap();
+}
+
+/// Resolves with the next event of given type dispatched on the `target`, waiting as long as `options` allow.
+///
+/// The listener is added with `once: true` when this function is called, not when the future is
+/// first polled, so events dispatched synchronously right after it, eg. by [`HtmlElement::click`],
+/// are received. It is removed when the future is dropped or times out. Events dispatched
+/// on descendants are received too if they bubble.
+///
+/// # Example:
+/// ```no_run
+/// use frontest::prelude::*;
+/// use frontest::wait::{next_event_as, WaitOptions};
+/// use web_sys::{CustomEvent, CustomEventInit};
+///
+/// # async fn saved() {
+/// let mount = mount_html("<form></form>");
+/// let saved = next_event_as::<CustomEvent>(&mount, "saved", WaitOptions::current());
+///
+/// let init = CustomEventInit::new();
+/// init.set_detail(&"42".into());
+/// let event = CustomEvent::new_with_event_init_dict("saved", &init).unwrap();
+/// mount.dispatch_event(&event).unwrap();
+///
+/// assert_eq!(saved.await.unwrap().detail(), "42");
+///
+/// cleanup();
+/// # }
+/// ```
+pub fn next_event(
+    target: &EventTarget,
+    event_type: &str,
+    options: WaitOptions,
+) -> impl Future<Output = Result<Event, WaitTimeout>> {
+    let start = now();
+    let signal = Signal::default();
+    let received = Rc::new(RefCell::new(None));
+    let listener = EventListener::once(target, event_type.to_owned(), {
+        let received = received.clone();
+        let signal = signal.clone();
+        move |event| {
+            *received.borrow_mut() = Some(event.clone());
+            signal.notify();
+        }
+    });
+    let event_type = event_type.to_owned();
+    async move {
+        let _listener = listener;
+        loop {
+            if let Some(event) = received.borrow_mut().take() {
+                return Ok(event);
+            }
+            let elapsed = now().saturating_sub(start);
+            if elapsed >= options.timeout {
+                let details = format!("waiting for a {:?} event", event_type);
+                return Err(options.timed_out(elapsed, details));
+            }
+            let delay = (options.timeout - elapsed).as_millis();
+            let _timer = RealTimeout::new(u32::try_from(delay).unwrap_or(u32::MAX), {
+                let signal = signal.clone();
+                move || signal.notify()
+            });
+            signal.notified().await;
+        }
+    }
+}
+
+/// Same as [`next_event`], but casts the event into a specific type, like [`web_sys::CustomEvent`].
+///
+/// # Panics:
+/// If the received event is not a `T`.
+pub fn next_event_as<T: JsCast>(
+    target: &EventTarget,
+    event_type: &str,
+    options: WaitOptions,
+) -> impl Future<Output = Result<T, WaitTimeout>> {
+    let event = next_event(target, event_type, options);
+    async move {
+        event.await.map(|event| {
+            let expected = std::any::type_name::<T>();
+            let expected = expected.rsplit("::").next().unwrap_or(expected);
+            event.dyn_into::<T>().unwrap_or_else(|event| {
+                panic!(
+                    "expected the {:?} event to be {}, but it is {}",
+                    event.type_(),
+                    expected,
+                    event.constructor().name()
+                )
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn doctest_next_event_as() {
+    use crate::{cleanup, mount_html};
+    use web_sys::{CustomEvent, CustomEventInit};
+
+    let mount = mount_html("<form></form>");
+    let saved = next_event_as::<CustomEvent>(&mount, "saved", WaitOptions::current());
+
+    let init = CustomEventInit::new();
+    init.set_detail(&"42".into());
+    let event = CustomEvent::new_with_event_init_dict("saved", &init).unwrap();
+    mount.dispatch_event(&event).unwrap();
+
+    assert_eq!(saved.await.unwrap().detail(), "42");
+
+    cleanup();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+async fn next_event_receives_clicks_and_times_out() {
+    use crate::query::{HasRole, Query};
+    use crate::{cleanup, mount_html};
+    use web_sys::{CustomEvent, MouseEvent};
+
+    let mount = mount_html("<div><button>Save</button></div>");
+    let button = mount.get(&HasRole("button")).unwrap();
+
+    // Bubbling from the button
+    let click = next_event(&mount, "click", WaitOptions::current());
+    button.click();
+    let click = click.await.unwrap();
+    assert_eq!(click.type_(), "click");
+    assert!(click.dyn_ref::<MouseEvent>().is_some());
+
+    // Dispatched later
+    let clicked = button.clone();
+    Timeout::new(50, move || clicked.click()).forget();
+    let click = next_event_as::<MouseEvent>(&button, "click", WaitOptions::current());
+    assert_eq!(click.await.unwrap().button(), 0);
+
+    // Nothing is dispatched
+    let options = WaitOptions::default().timeout(Duration::from_millis(50));
+    let err = next_event(&button, "saved", options).await.unwrap_err();
+    assert!(err.elapsed() >= Duration::from_millis(50));
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "timed out after {}ms waiting for a \"saved\" event",
+            err.elapsed().as_millis()
+        )
+    );
+
+    // Only the events of given type are received
+    let saved = next_event_as::<CustomEvent>(&button, "saved", options);
+    button.click();
+    assert!(saved.await.is_err());
+
+    cleanup();
+}
+
+#[cfg(test)]
+#[wasm_bindgen_test::wasm_bindgen_test]
+#[should_panic(expected = "expected the \"saved\" event to be CustomEvent, but it is Event")]
+async fn next_event_as_panics_on_other_types() {
+    use gloo::utils::document;
+
+    // Not attached, as the panic skips removing it and it would leak into other tests
+    let form = document().create_element("form").unwrap();
+    let saved = next_event_as::<web_sys::CustomEvent>(&form, "saved", WaitOptions::current());
+    form.dispatch_event(&Event::new("saved").unwrap()).unwrap();
+    let _ = saved.await;
+}
@@ -0,0 +1,19 @@
+//! Tests which don't need a DOM, run in node instead of a browser.
+//!
+//! Everything rendering or querying the page needs a browser, as node has no DOM, and is tested
+//! within the crate with `run_in_browser`.
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+async fn flush_runs_spawned_futures() {
+    let done = Rc::new(Cell::new(false));
+    wasm_bindgen_futures::spawn_local({
+        let done = done.clone();
+        async move { done.set(true) }
+    });
+    assert!(!done.get());
+    frontest::flush().await;
+    assert!(done.get());
+}